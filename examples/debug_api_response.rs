@@ -34,31 +34,31 @@ fn print_usage() {
     println!("\n使用方法: cargo run --example debug_api_response <command>");
     println!("\n可用命令:");
     println!("  all              - 运行所有 API 测试");
-    println!("");
+    println!();
     println!("  === Wallet API ===");
     println!("  address_info     - 地址信息 (/api/mpc/sub_wallet/address/info)");
     println!("  query_address    - 查询钱包地址 (/api/mpc/sub_wallet/get/address/list)");
     println!("  wallet_assets    - 获取钱包资产 (/api/mpc/sub_wallet/assets)");
     println!("  change_status    - 修改钱包显示状态 (/api/mpc/sub_wallet/show_status)");
-    println!("");
+    println!();
     println!("  === Workspace API ===");
     println!("  supported        - 支持的币种 (/api/mpc/coin/supported)");
     println!("  coin_list        - 币种详情 (/api/mpc/coin/list)");
     println!("  block_height     - 最新区块高度 (/api/mpc/main/coin/last/block/height)");
-    println!("");
+    println!();
     println!("  === Deposit API ===");
     println!("  deposit          - 同步充值记录 (/api/mpc/trans/sync/deposit)");
-    println!("");
+    println!();
     println!("  === Withdraw API ===");
     println!("  withdraw         - 同步提现记录 (/api/mpc/trans/sync/withdraw)");
-    println!("");
+    println!();
     println!("  === Auto Sweep API ===");
     println!("  auto_collect     - 同步自动归集记录 (/api/mpc/auto_collect/sync/list)");
     println!("  auto_sub_wallets - 自动归集钱包列表 (/api/mpc/auto_collect/sub_wallets)");
-    println!("");
+    println!();
     println!("  === Web3 API ===");
     println!("  web3             - 同步 Web3 交易记录 (/api/mpc/web3/trans/sync/list)");
-    println!("");
+    println!();
     println!("  === TRON Resource API ===");
     println!("  tron_resource    - 同步 TRON 资源购买记录 (/api/mpc/tron/buy_resource/sync/list)");
 }
@@ -24,12 +24,14 @@ use chainup_custody_sdk::{
         GetLastBlockHeightParams,
         GetWalletAssetsParams,
         // Notify API
+        MpcNotification,
         QueryWalletAddressParams,
         SetAutoCollectSymbolParams,
         WalletAddressInfoParams,
         // Withdraw API
         WithdrawParams,
     },
+    RecordId,
 };
 
 fn main() -> Result<(), ChainUpError> {
@@ -131,7 +133,8 @@ fn main() -> Result<(), ChainUpError> {
 
             // 2. 获取特定充值记录
             if !records.is_empty() {
-                let deposit_ids: Vec<i64> = records.iter().take(3).filter_map(|d| d.id).collect();
+                let deposit_ids: Vec<RecordId> =
+                    records.iter().take(3).filter_map(|d| d.id).collect();
                 if !deposit_ids.is_empty() {
                     let params = GetDepositRecordsParams::new(deposit_ids);
                     match deposit_api.get_deposit_records(params) {
@@ -406,9 +409,9 @@ fn main() -> Result<(), ChainUpError> {
     // 测试用加密数据（在 webhook 回调中使用）
     let encrypted_data = "Af-uUJj8a2-Og7E5CwzANv4vo8NMf-z-DijwrIuK74Or8eRveM7G_-f0ErtX4WurcVrjdWC-tqU0BDhBwiDijbdyCFBvYB5UmLnHL_Rg13amhQTM-kaHoh-U9WPhYB3vGRwWkTwJ_aETERVVciAvoTf5CalqydMSe8G3KNz-ymrSVUe92DfW5ZdDKJm1hNYYteGJvg0hk--GRiPybPv2W78NlTLyWmXq094megsVzZv-KlsEGPUvPoBnEJ0Xu__AO-l-GfCG4rVO4rb8J01Nq_0Q9eRKcKWq0ci7MfnPPLMhtAWwRvSd3U8PUNHOLqGaJzOLraFnuFUHn90h7T23_DeAduA2W6dto99qb8YQ_iVnMnOKfE0Ls7Vv5S2qhgQJ0nl-BA3PPPOwW37cMb-wTbi3ZezU_S1NQEbrruEChkPhTaK0AqsM6mESV8wGflcWx3N9XPv6QatJ9zedBnkfJ4bJ4Vy2rUEtQF8eVc6zXhV8PuDRiSMf0V0yxzMjE6o9z0s087KSAqFphitlHvQMPJ29FUnyvCe_Czr5WPuhl89GOZjERE2uoNTfHqAlZVzMamoPv4y0qyIjJTufAQm-WwrQK9kGesky7eCiOXVdtR9UhEYpzEJSgXxENjUrHMx6D2AlEzlr17a2DgI-WrWB7oUnyiNnf__ElmLPPkJBdFUfzJByQkLxkUB0FLvTWdVbiIRPmPpdgb7jkhJsHUSOH0NmULqu8bYiEQtGfqRJh8I98qDzHWwfE_VAbqwATj2oD959Fm1eInBqh7eXGoy2WR3o00VpPrNvoE4eJNmw3WpVzlRF7ZVwOpcWRT-dHTShz9mB2Etk9P8D4rGmMZyXHkt4aGUJkE1b3cOEjzkOEFX8CaNe-VHiBYhIyFzMetn7mfIFB0hl565FGEumbhDKNNz_m9T2qPM5k4BQ9fLWUt_WJAVdC81_piIlBOQfYPDbdYoc_9ser1p-Jy5cgTyOMdWuSWC3jMsT09xr8dMcLkKmd39khGidAvGqOOPL1ST0";
 
-    // 使用 decrypt_notification 解密通知数据
-    println!("\n--- decrypt_notification ---");
-    match notify_api.decrypt_notification(encrypted_data) {
+    // 使用 decrypt_notification_raw 解密通知数据（全部字段）
+    println!("\n--- decrypt_notification_raw ---");
+    match notify_api.decrypt_notification_raw(encrypted_data) {
         Ok(data) => {
             println!("✓ 解密成功，字段详情:");
             println!("  id: {:?}", data.id);
@@ -458,6 +461,25 @@ fn main() -> Result<(), ChainUpError> {
                 println!("  extra (未定义字段): {:?}", data.extra);
             }
         }
+        Err(e) => println!("✗ decrypt_notification_raw 失败: {}", e),
+    }
+
+    // 使用 decrypt_notification 按通知类型解密
+    println!("\n--- decrypt_notification ---");
+    match notify_api.decrypt_notification(encrypted_data) {
+        Ok(MpcNotification::Deposit(deposit)) => {
+            println!(
+                "✓ 充值通知: {} {} (状态 {:?})",
+                deposit.amount, deposit.symbol, deposit.status
+            )
+        }
+        Ok(MpcNotification::Withdraw(withdraw)) => {
+            println!(
+                "✓ 提现通知: {} {} (状态 {:?})",
+                withdraw.amount, withdraw.symbol, withdraw.status
+            )
+        }
+        Ok(other) => println!("✓ 其他通知: {:?}", other),
         Err(e) => println!("✗ decrypt_notification 失败: {}", e),
     }
 
@@ -0,0 +1,140 @@
+//! ChainUp Custody command line tool
+//!
+//! Usage:
+//!   chainup-custody keygen [--out-dir <dir>] [--bits <n>] [--force]
+//!   chainup-custody drift [--fail-on-drift] <recording.jsonl>...
+
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chainup_custody_sdk::crypto::keygen::{MpcKeySet, DEFAULT_KEY_BITS};
+use chainup_custody_sdk::drift::DriftDetector;
+use chainup_custody_sdk::error::ChainUpError;
+
+fn print_usage() {
+    println!("ChainUp Custody CLI v{}", chainup_custody_sdk::VERSION);
+    println!();
+    println!("Usage: chainup-custody <command> [options]");
+    println!();
+    println!("Commands:");
+    println!("  keygen    Generate the transport and signing RSA key pairs for a new app_id");
+    println!("  drift     Compare recorded responses against the SDK record types");
+    println!();
+    println!("keygen options:");
+    println!("  --out-dir <dir>   Directory for the PEM files (default: current directory)");
+    println!("  --bits <n>        RSA modulus size (default: {})", DEFAULT_KEY_BITS);
+    println!("  --force           Overwrite existing key files");
+    println!();
+    println!("drift options:");
+    println!("  <recording.jsonl>  JSON Lines files of {{\"endpoint\": ..., \"response\": ...}}");
+    println!("  --fail-on-drift    Exit with status 2 if any drift is found");
+}
+
+fn keygen(args: &[String]) -> Result<(), ChainUpError> {
+    let mut out_dir = PathBuf::from(".");
+    let mut bits = DEFAULT_KEY_BITS;
+    let mut force = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--out-dir" => {
+                out_dir = iter
+                    .next()
+                    .map(PathBuf::from)
+                    .ok_or_else(|| usage_error("--out-dir requires a value"))?;
+            }
+            "--bits" => {
+                bits = iter
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(|| usage_error("--bits requires a numeric value"))?;
+            }
+            "--force" => force = true,
+            other => return Err(usage_error(&format!("Unknown option: {}", other))),
+        }
+    }
+
+    let key_set = MpcKeySet::generate_with_bits(bits)?;
+    let written = key_set.write_to_dir(&out_dir, force)?;
+
+    println!("Generated {}-bit RSA key pairs:", bits);
+    for path in &written {
+        println!("  {}", path.display());
+    }
+    println!();
+    println!("Upload these public keys in the ChainUp console.");
+    println!();
+    println!("Transport (API) public key:");
+    println!("{}", key_set.transport.public_key_base64());
+    println!();
+    println!("Signing (withdrawal) public key:");
+    println!("{}", key_set.signing.public_key_base64());
+    println!();
+    println!("Keep the private keys secret; load them with set_rsa_private_key / set_sign_private_key.");
+    Ok(())
+}
+
+fn drift(args: &[String]) -> Result<bool, ChainUpError> {
+    let mut fail_on_drift = false;
+    let mut files = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--fail-on-drift" => fail_on_drift = true,
+            other if other.starts_with("--") => {
+                return Err(usage_error(&format!("Unknown option: {}", other)))
+            }
+            path => files.push(PathBuf::from(path)),
+        }
+    }
+    if files.is_empty() {
+        return Err(usage_error("drift requires at least one recording file"));
+    }
+
+    let mut detector = DriftDetector::new();
+    for path in &files {
+        let file = File::open(path)?;
+        detector.observe_jsonl(BufReader::new(file))?;
+    }
+
+    let report = detector.into_report();
+    println!("{}", report.to_json_pretty()?);
+    Ok(fail_on_drift && report.has_drift())
+}
+
+fn usage_error(message: &str) -> ChainUpError {
+    chainup_custody_sdk::error::ValidationError::new(message).into()
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("keygen") => keygen(&args[1..]),
+        Some("drift") => match drift(&args[1..]) {
+            Ok(true) => return ExitCode::from(2),
+            Ok(false) => Ok(()),
+            Err(e) => Err(e),
+        },
+        Some("-h") | Some("--help") | Some("help") | None => {
+            print_usage();
+            return ExitCode::SUCCESS;
+        }
+        Some(other) => {
+            eprintln!("Unknown command: {}", other);
+            print_usage();
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
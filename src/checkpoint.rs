@@ -0,0 +1,407 @@
+//! Persistent `max_id` checkpoints for the `sync_*` endpoints
+//!
+//! A [`CheckpointStore`] remembers, per key, the `max_id` up to which
+//! records have been processed. [`CheckpointedSync`] loads that value to
+//! resume a sync iterator and only writes a new one after the consumer
+//! acknowledges a page. A crash between fetching and acknowledging a page
+//! makes the next run fetch it again, so every record is delivered at least
+//! once; consumers should make processing idempotent (e.g. keyed on the
+//! record ID).
+//!
+//! Stores:
+//!
+//! - [`MemoryCheckpointStore`]: in-process only, mainly for tests
+//! - [`FileCheckpointStore`]: a JSON file replaced atomically on every save
+//! - `SqliteCheckpointStore`: a table in a SQLite database (`sqlite` feature)
+//!
+//! The API helpers (e.g. `sync_withdraw_records_checkpointed`) use the
+//! endpoint path as the key. Use a separate store per app when syncing
+//! several apps.
+//!
+//! # Example
+//! ```ignore
+//! let store = FileCheckpointStore::open("checkpoints.json")?;
+//! let mut sync = withdraw_api.sync_withdraw_records_checkpointed(&store)?;
+//! while let Some(page) = sync.next_page() {
+//!     save_to_db(&page?)?;
+//!     sync.ack()?;
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::error::{ChainUpError, Result};
+use crate::pagination::{SyncIter, SyncRecord};
+
+/// Storage for the last processed `max_id` of each sync endpoint
+pub trait CheckpointStore {
+    /// Returns the saved `max_id` for `key`, or `None` if nothing was saved yet
+    fn load(&self, key: &str) -> Result<Option<i64>>;
+
+    /// Saves `max_id` for `key`, replacing any previous value
+    fn save(&self, key: &str, max_id: i64) -> Result<()>;
+}
+
+/// Checkpoints kept in memory; lost when the process exits
+#[derive(Debug, Default)]
+pub struct MemoryCheckpointStore {
+    checkpoints: Mutex<HashMap<String, i64>>,
+}
+
+impl MemoryCheckpointStore {
+    /// Creates an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CheckpointStore for MemoryCheckpointStore {
+    fn load(&self, key: &str) -> Result<Option<i64>> {
+        Ok(lock(&self.checkpoints).get(key).copied())
+    }
+
+    fn save(&self, key: &str, max_id: i64) -> Result<()> {
+        lock(&self.checkpoints).insert(key.to_string(), max_id);
+        Ok(())
+    }
+}
+
+/// Checkpoints kept in a JSON file
+///
+/// The file holds one object mapping keys to `max_id` values. Every save
+/// writes the whole object to a temporary file next to it, syncs it to disk
+/// and renames it over the original, so a crash never leaves a partially
+/// written file behind.
+#[derive(Debug)]
+pub struct FileCheckpointStore {
+    path: PathBuf,
+    checkpoints: Mutex<BTreeMap<String, i64>>,
+}
+
+impl FileCheckpointStore {
+    /// Opens the store at `path`, reading existing checkpoints if the file exists
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let checkpoints = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path,
+            checkpoints: Mutex::new(checkpoints),
+        })
+    }
+
+    /// Path of the checkpoint file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn write(&self, checkpoints: &BTreeMap<String, i64>) -> Result<()> {
+        let mut tmp_name = self.path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp_path = self.path.with_file_name(tmp_name);
+
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&serde_json::to_vec_pretty(checkpoints)?)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&tmp_path, &self.path)?;
+
+        // Persist the rename itself; not supported on every platform
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            if let Ok(dir) = File::open(dir) {
+                let _ = dir.sync_all();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CheckpointStore for FileCheckpointStore {
+    fn load(&self, key: &str) -> Result<Option<i64>> {
+        Ok(lock(&self.checkpoints).get(key).copied())
+    }
+
+    fn save(&self, key: &str, max_id: i64) -> Result<()> {
+        let mut checkpoints = lock(&self.checkpoints);
+        let previous = checkpoints.insert(key.to_string(), max_id);
+        if let Err(e) = self.write(&checkpoints) {
+            // Keep memory consistent with the file
+            match previous {
+                Some(previous) => checkpoints.insert(key.to_string(), previous),
+                None => checkpoints.remove(key),
+            };
+            return Err(e);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub use self::sqlite::SqliteCheckpointStore;
+
+#[cfg(feature = "sqlite")]
+pub(crate) mod sqlite {
+    use std::path::Path;
+    use std::sync::Mutex;
+
+    use rusqlite::{params, Connection, OptionalExtension};
+
+    use super::{lock, CheckpointStore};
+    use crate::error::{ChainUpError, Result, ValidationError};
+
+    /// Checkpoints kept in a SQLite table
+    ///
+    /// Uses the table `chainup_sync_checkpoints` unless another name is given
+    /// with [`SqliteCheckpointStore::with_table`]; the table is created if
+    /// missing. Each save is a single upsert, so it is atomic.
+    #[derive(Debug)]
+    pub struct SqliteCheckpointStore {
+        conn: Mutex<Connection>,
+        table: String,
+    }
+
+    impl SqliteCheckpointStore {
+        /// Default table name
+        pub const DEFAULT_TABLE: &'static str = "chainup_sync_checkpoints";
+
+        /// Opens (or creates) the database at `path`
+        pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+            let conn = Connection::open(path).map_err(sqlite_error)?;
+            Self::with_table(conn, Self::DEFAULT_TABLE)
+        }
+
+        /// Uses an existing connection with the default table
+        pub fn from_connection(conn: Connection) -> Result<Self> {
+            Self::with_table(conn, Self::DEFAULT_TABLE)
+        }
+
+        /// Uses an existing connection and table name
+        ///
+        /// The name may only contain ASCII letters, digits and underscores.
+        pub fn with_table(conn: Connection, table: impl Into<String>) -> Result<Self> {
+            let table = table.into();
+            check_table_name(&table)?;
+            conn.execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    key TEXT PRIMARY KEY NOT NULL,
+                    max_id INTEGER NOT NULL
+                )",
+                table
+            ))
+            .map_err(sqlite_error)?;
+            Ok(Self {
+                conn: Mutex::new(conn),
+                table,
+            })
+        }
+    }
+
+    impl CheckpointStore for SqliteCheckpointStore {
+        fn load(&self, key: &str) -> Result<Option<i64>> {
+            lock(&self.conn)
+                .query_row(
+                    &format!("SELECT max_id FROM {} WHERE key = ?1", self.table),
+                    params![key],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(sqlite_error)
+        }
+
+        fn save(&self, key: &str, max_id: i64) -> Result<()> {
+            lock(&self.conn)
+                .execute(
+                    &format!(
+                        "INSERT INTO {} (key, max_id) VALUES (?1, ?2)
+                         ON CONFLICT(key) DO UPDATE SET max_id = excluded.max_id",
+                        self.table
+                    ),
+                    params![key, max_id],
+                )
+                .map_err(sqlite_error)?;
+            Ok(())
+        }
+    }
+
+    /// Rejects table names that are not plain SQL identifiers
+    pub(crate) fn check_table_name(table: &str) -> Result<()> {
+        let valid = !table.is_empty()
+            && !table.starts_with(|c: char| c.is_ascii_digit())
+            && table.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(ValidationError::new(format!("Invalid table name: {:?}", table)).into());
+        }
+        Ok(())
+    }
+
+    pub(crate) fn sqlite_error(err: rusqlite::Error) -> ChainUpError {
+        ChainUpError::Io(std::io::Error::other(err))
+    }
+}
+
+/// Sync iterator that resumes from and commits to a [`CheckpointStore`]
+///
+/// Pages are handed out with [`next_page`](Self::next_page); the checkpoint
+/// only moves when [`ack`](Self::ack) is called, and then covers every page
+/// returned so far.
+pub struct CheckpointedSync<'a, T> {
+    iter: SyncIter<'a, T>,
+    store: &'a dyn CheckpointStore,
+    key: String,
+    committed: i64,
+    pending: Option<i64>,
+}
+
+impl<'a, T: SyncRecord> CheckpointedSync<'a, T> {
+    /// Loads the checkpoint for `key` and builds the iterator with `iter`
+    ///
+    /// `iter` receives the saved `max_id`, or 0 when there is none.
+    pub fn new(
+        store: &'a dyn CheckpointStore,
+        key: impl Into<String>,
+        iter: impl FnOnce(i64) -> SyncIter<'a, T>,
+    ) -> Result<Self> {
+        let key = key.into();
+        let committed = store.load(&key)?.unwrap_or(0);
+        Ok(Self {
+            iter: iter(committed),
+            store,
+            key,
+            committed,
+            pending: None,
+        })
+    }
+
+    /// Sets the endpoint's page size; see [`SyncIter::with_page_size`]
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.iter = self.iter.with_page_size(page_size);
+        self
+    }
+
+    /// Store key of this sync
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The last `max_id` written to the store
+    pub fn checkpoint(&self) -> i64 {
+        self.committed
+    }
+
+    /// Returns the next page, without moving the checkpoint
+    ///
+    /// Returns `None` when the endpoint is exhausted.
+    pub fn next_page(&mut self) -> Option<Result<Vec<T>>> {
+        let page = self.iter.next_page();
+        if let Some(Ok(_)) = page {
+            self.pending = Some(self.iter.max_id());
+        }
+        page
+    }
+
+    /// Marks every page returned so far as processed and saves the checkpoint
+    pub fn ack(&mut self) -> Result<()> {
+        if let Some(max_id) = self.pending {
+            self.store.save(&self.key, max_id)?;
+            self.committed = max_id;
+            self.pending = None;
+        }
+        Ok(())
+    }
+
+    /// Passes every remaining page to `f`, acknowledging each one it accepts
+    ///
+    /// Stops at the first error from the endpoint, the store or `f`; the
+    /// page `f` failed on is not acknowledged.
+    pub fn for_each_page<E>(
+        mut self,
+        mut f: impl FnMut(Vec<T>) -> std::result::Result<(), E>,
+    ) -> std::result::Result<(), E>
+    where
+        E: From<ChainUpError>,
+    {
+        while let Some(page) = self.next_page() {
+            f(page?)?;
+            self.ack()?;
+        }
+        Ok(())
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    // A panic while holding the lock cannot leave a checkpoint half-updated
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpc::api::types::DepositRecord;
+
+    fn record(id: i64) -> DepositRecord {
+        serde_json::from_value(serde_json::json!({ "id": id })).unwrap()
+    }
+
+    fn pages(max_id: i64) -> Result<Vec<DepositRecord>> {
+        Ok(match max_id {
+            0 => vec![record(1), record(2)],
+            2 => vec![record(3)],
+            _ => vec![],
+        })
+    }
+
+    #[test]
+    fn test_checkpointed_sync_resumes_after_last_ack() {
+        let path =
+            std::env::temp_dir().join(format!("chainup-checkpoints-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let store = FileCheckpointStore::open(&path).unwrap();
+
+        // First run: the second page is fetched but never acknowledged
+        let mut sync =
+            CheckpointedSync::new(&store, "deposits", |max_id| SyncIter::new(max_id, pages))
+                .unwrap();
+        assert_eq!(sync.next_page().unwrap().unwrap().len(), 2);
+        sync.ack().unwrap();
+        assert_eq!(sync.next_page().unwrap().unwrap().len(), 1);
+        drop(sync);
+
+        // Second run, from the file: the unacknowledged page comes back
+        let store = FileCheckpointStore::open(&path).unwrap();
+        assert_eq!(store.load("deposits").unwrap(), Some(2));
+        let mut ids = Vec::new();
+        CheckpointedSync::new(&store, "deposits", |max_id| SyncIter::new(max_id, pages))
+            .unwrap()
+            .for_each_page(|page| {
+                ids.extend(page.iter().filter_map(SyncRecord::sync_id));
+                Ok::<_, ChainUpError>(())
+            })
+            .unwrap();
+        assert_eq!(ids, vec![3]);
+        assert_eq!(store.load("deposits").unwrap(), Some(3));
+        assert_eq!(store.load("withdrawals").unwrap(), None);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_checkpoint_store() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let store = SqliteCheckpointStore::from_connection(conn).unwrap();
+        assert_eq!(store.load("deposits").unwrap(), None);
+        store.save("deposits", 10).unwrap();
+        store.save("deposits", 12).unwrap();
+        assert_eq!(store.load("deposits").unwrap(), Some(12));
+
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        assert!(SqliteCheckpointStore::with_table(conn, "bad; name").is_err());
+    }
+}
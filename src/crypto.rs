@@ -11,10 +11,13 @@ use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey};
 use rsa::signature::Verifier;
 use rsa::traits::{PrivateKeyParts, PublicKeyParts};
 use rsa::{BigUint, Pkcs1v15Sign, RsaPrivateKey, RsaPublicKey};
+use serde_json::Value;
 use sha2::{Digest, Sha256};
 
 use crate::error::{CryptoError, Result};
 
+pub mod keygen;
+
 /// Trait for crypto provider implementations
 ///
 /// Allows custom encryption/decryption implementations (e.g., HSM, KMS)
@@ -170,11 +173,7 @@ impl RsaCryptoProvider {
         }
 
         // Remove all whitespace to get pure base64
-        key = key
-            .replace('\n', "")
-            .replace('\r', "")
-            .replace(' ', "")
-            .replace('\t', "");
+        key = key.replace(['\n', '\r', ' ', '\t'], "");
 
         // Format base64 with line breaks every 64 characters for proper PEM format
         let formatted_base64: String = key
@@ -211,6 +210,60 @@ impl RsaCryptoProvider {
         }
     }
 
+    /// Builds the PKCS#1 v1.5 DigestInfo used by ChainUp transaction signatures
+    ///
+    /// The data is hashed with MD5, the lowercase hex digest is hashed again with
+    /// SHA256 (matching the Go SDK), and the result is prefixed with the SHA256
+    /// DigestInfo header: 30 31 30 0d 06 09 60 86 48 01 65 03 04 02 01 05 00 04 20
+    fn sign_digest_info(data: &str) -> Vec<u8> {
+        let mut md5_hasher = Md5::new();
+        md5_hasher.update(data.as_bytes());
+        let md5_hash = format!("{:x}", md5_hasher.finalize());
+
+        let mut sha256_hasher = Sha256::new();
+        sha256_hasher.update(md5_hash.as_bytes());
+        let hash = sha256_hasher.finalize();
+
+        let sha256_prefix: [u8; 19] = [
+            0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+            0x01, 0x05, 0x00, 0x04, 0x20,
+        ];
+        let mut digest_info = Vec::with_capacity(sha256_prefix.len() + hash.len());
+        digest_info.extend_from_slice(&sha256_prefix);
+        digest_info.extend_from_slice(&hash);
+        digest_info
+    }
+
+    /// Verifies a transaction signature produced by [`CryptoProvider::sign`]
+    ///
+    /// Unlike [`CryptoProvider::verify`], which checks a plain SHA256 signature,
+    /// this uses the same MD5-hex → SHA256 scheme as `sign`, so the two are inverses.
+    ///
+    /// # Arguments
+    /// * `public_key` - Public key of the signing key pair (PEM or raw base64)
+    /// * `data` - Signed data
+    /// * `signature` - Base64 encoded signature
+    ///
+    /// # Returns
+    /// True if the signature was made by the matching private key
+    pub fn verify_sign_with_key(public_key: &str, data: &str, signature: &str) -> Result<bool> {
+        let formatted = Self::format_rsa_key(public_key, "public");
+        let public_key = RsaPublicKey::from_public_key_pem(&formatted)
+            .map_err(|e| CryptoError::new(format!("Failed to parse public key: {}", e)))?;
+
+        let signature_bytes = base64::engine::general_purpose::STANDARD
+            .decode(signature.trim())
+            .map_err(|e| CryptoError::new(format!("Failed to decode signature: {}", e)))?;
+
+        Ok(public_key
+            .verify(
+                Pkcs1v15Sign::new_unprefixed(),
+                &Self::sign_digest_info(data),
+                &signature_bytes,
+            )
+            .is_ok())
+    }
+
     /// Raw RSA encrypt with private key (non-standard, for signature-like encryption)
     fn raw_encrypt_with_private_key(&self, data: &[u8]) -> Result<Vec<u8>> {
         let private_key = self
@@ -288,12 +341,11 @@ impl RsaCryptoProvider {
                 if padded_decrypted.len() >= 2
                     && (padded_decrypted[1] == 0x01 || padded_decrypted[1] == 0x02)
                 {
-                    for i in 2..padded_decrypted.len() {
-                        if padded_decrypted[i] == 0 {
-                            separator_idx = Some(i);
-                            break;
-                        }
-                    }
+                    separator_idx = padded_decrypted
+                        .iter()
+                        .skip(2)
+                        .position(|&b| b == 0)
+                        .map(|pos| pos + 2);
                 }
 
                 if let Some(idx) = separator_idx {
@@ -358,30 +410,12 @@ impl CryptoProvider for RsaCryptoProvider {
             .or(self.private_key.as_ref())
             .ok_or_else(|| CryptoError::new("Neither sign_private_key nor private_key is set"))?;
 
-        // Step 1: MD5 hash the data (matching Go SDK implementation)
-        let mut md5_hasher = Md5::new();
-        md5_hasher.update(data.as_bytes());
-        let md5_hash = format!("{:x}", md5_hasher.finalize());
-
-        // Step 2: SHA256 hash the MD5 hex string
-        let mut sha256_hasher = Sha256::new();
-        sha256_hasher.update(md5_hash.as_bytes());
-        let hash = sha256_hasher.finalize();
+        // Step 1-2: MD5 hex, then SHA256 wrapped in a DigestInfo structure
+        let digest_info = Self::sign_digest_info(data);
 
-        // Step 3: RSA sign with PKCS1v15 using SHA256
-        // Use Pkcs1v15Sign::new_unprefixed() with SHA256 DigestInfo prefix
-        // SHA256 DigestInfo prefix: 30 31 30 0d 06 09 60 86 48 01 65 03 04 02 01 05 00 04 20
+        // Step 3: RSA sign with PKCS1v15 (DigestInfo already carries the SHA256 prefix)
         let padding = Pkcs1v15Sign::new_unprefixed();
 
-        // Build DigestInfo structure for SHA256: prefix + hash
-        let sha256_prefix: [u8; 19] = [
-            0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
-            0x01, 0x05, 0x00, 0x04, 0x20,
-        ];
-        let mut digest_info = Vec::with_capacity(sha256_prefix.len() + hash.len());
-        digest_info.extend_from_slice(&sha256_prefix);
-        digest_info.extend_from_slice(&hash);
-
         let signature = signing_key
             .sign(padding, &digest_info)
             .map_err(|e| CryptoError::new(format!("Failed to sign: {}", e)))?;
@@ -408,6 +442,57 @@ impl CryptoProvider for RsaCryptoProvider {
     }
 }
 
+/// Decrypts a ChainUp response envelope
+///
+/// MPC and WaaS both return `{"data": "encrypted_string"}`; the decrypted
+/// content is the actual API response (`{"code":"0","data":[...],"msg":"success"}`).
+/// An envelope without encrypted data, or a plain error envelope (non-zero
+/// `code`) whose `data` does not decrypt, is returned as-is for
+/// `validate_response` to turn into an `ApiError`.
+///
+/// Any other encrypted `data` that cannot be decrypted is a `CryptoError`:
+/// it almost always means the ChainUp public key (`public_key_field` in the
+/// config) or app_id is wrong, and must not be mistaken for an API response.
+pub(crate) fn decrypt_response(
+    provider: &dyn CryptoProvider,
+    path: &str,
+    response: Value,
+    public_key_field: &str,
+    app_id: &str,
+) -> Result<Value> {
+    let encrypted_response_data = match response.get("data") {
+        Some(Value::String(s)) if !s.is_empty() => s,
+        _ => return Ok(response),
+    };
+
+    match provider.decrypt_with_public_key(encrypted_response_data) {
+        Ok(decrypted) => serde_json::from_str(&decrypted).map_err(|e| {
+            CryptoError::new(format!(
+                "Decrypted response from {} is not valid JSON: {}",
+                path, e
+            ))
+            .into()
+        }),
+        // A success code with undecryptable data is still a crypto failure
+        Err(_) if response_code(&response).is_some_and(|code| code != 0) => Ok(response),
+        Err(e) => Err(CryptoError::new(format!(
+            "Failed to decrypt response from {}: {}. Check that {} is \
+             the ChainUp public key for app_id '{}' (not your own public key)",
+            path, e, public_key_field, app_id
+        ))
+        .into()),
+    }
+}
+
+/// Reads the envelope `code`, sent as a string or a number
+pub(crate) fn response_code(response: &Value) -> Option<i32> {
+    response.get("code").and_then(|v| {
+        v.as_str()
+            .and_then(|s| s.parse::<i32>().ok())
+            .or_else(|| v.as_i64().map(|i| i as i32))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,167 @@
+//! Response decoding strictness
+//!
+//! Every response type keeps fields it does not know about in its `extra`
+//! map, so nothing ChainUp adds is lost. [`DecodeMode`] controls whether
+//! such fields are also reported: ignored (the default), logged as warnings,
+//! or turned into an error so API changes are noticed early.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::error::Result;
+
+/// How to treat response fields that no SDK type declares
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecodeMode {
+    /// Keep unknown fields in `extra` without reporting them
+    #[default]
+    Lenient,
+    /// Keep unknown fields and log them with `log::warn!`
+    Warn,
+    /// Fail the call with a JSON error listing the unknown fields
+    Strict,
+}
+
+/// Response types that can report fields captured in their `extra` map
+///
+/// Field paths are dotted, with `[]` for list elements, e.g.
+/// `list[].new_field`.
+pub trait UnknownFields {
+    /// Appends the paths of unknown fields, prefixed with `prefix`
+    fn collect_unknown_fields(&self, prefix: &str, out: &mut Vec<String>);
+
+    /// Returns the sorted, de-duplicated paths of unknown fields
+    fn unknown_fields(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        self.collect_unknown_fields("", &mut out);
+        out.sort();
+        out.dedup();
+        out
+    }
+}
+
+/// Reports unknown fields of a decoded response according to `mode`
+pub(crate) fn check_unknown_fields<T: UnknownFields + ?Sized>(
+    mode: DecodeMode,
+    path: &str,
+    response: &T,
+) -> Result<()> {
+    if mode == DecodeMode::Lenient {
+        return Ok(());
+    }
+    let fields = response.unknown_fields();
+    if fields.is_empty() {
+        return Ok(());
+    }
+
+    let message = format!(
+        "Unknown fields in response from {}: {}",
+        path,
+        fields.join(", ")
+    );
+    match mode {
+        DecodeMode::Strict => Err(<serde_json::Error as serde::de::Error>::custom(message).into()),
+        _ => {
+            log::warn!("{}", message);
+            Ok(())
+        }
+    }
+}
+
+/// Joins a field path prefix and a field name
+pub(crate) fn field_path(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+/// Appends the keys of an `extra` map
+pub(crate) fn collect_extra(extra: &HashMap<String, Value>, prefix: &str, out: &mut Vec<String>) {
+    out.extend(extra.keys().map(|key| field_path(prefix, key)));
+}
+
+impl<T: UnknownFields> UnknownFields for Vec<T> {
+    fn collect_unknown_fields(&self, prefix: &str, out: &mut Vec<String>) {
+        let prefix = format!("{}[]", prefix);
+        for item in self {
+            item.collect_unknown_fields(&prefix, out);
+        }
+    }
+}
+
+impl<T: UnknownFields> UnknownFields for Option<T> {
+    fn collect_unknown_fields(&self, prefix: &str, out: &mut Vec<String>) {
+        if let Some(value) = self {
+            value.collect_unknown_fields(prefix, out);
+        }
+    }
+}
+
+macro_rules! no_unknown_fields {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl UnknownFields for $ty {
+                fn collect_unknown_fields(&self, _prefix: &str, _out: &mut Vec<String>) {}
+            }
+        )*
+    };
+}
+
+no_unknown_fields!(Value, String, bool, i32, i64);
+
+/// Implements [`UnknownFields`] for a record with an `extra` map
+///
+/// Nested response fields can be listed after the type to be checked too.
+macro_rules! impl_unknown_fields {
+    ($ty:ty $(, $field:ident)* $(,)?) => {
+        impl $crate::decode::UnknownFields for $ty {
+            fn collect_unknown_fields(&self, prefix: &str, out: &mut Vec<String>) {
+                $crate::decode::collect_extra(&self.extra, prefix, out);
+                $(
+                    $crate::decode::UnknownFields::collect_unknown_fields(
+                        &self.$field,
+                        &$crate::decode::field_path(prefix, stringify!($field)),
+                        out,
+                    );
+                )*
+            }
+        }
+    };
+}
+
+pub(crate) use impl_unknown_fields;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpc::api::types::{SyncWithdrawRecordsResponse, WithdrawRecord};
+    use serde_json::json;
+
+    #[test]
+    fn test_unknown_fields() {
+        let response: SyncWithdrawRecordsResponse = serde_json::from_value(json!({
+            "list": [{
+                "id": "12",
+                "sub_wallet_id": 1000537,
+                "amount": "0.1",
+                "status": 2000,
+                "created_at": "1700000000000",
+                "risk_level": 2
+            }]
+        }))
+        .unwrap();
+
+        let record: &WithdrawRecord = &response.list[0];
+        assert_eq!(record.amount, Some("0.1".parse().unwrap()));
+        assert_eq!(record.extra["risk_level"], 2);
+        assert_eq!(response.unknown_fields(), vec!["list[].risk_level"]);
+
+        assert!(check_unknown_fields(DecodeMode::Lenient, "/sync", &response).is_ok());
+        assert!(check_unknown_fields(DecodeMode::Warn, "/sync", &response).is_ok());
+        let err = check_unknown_fields(DecodeMode::Strict, "/sync", &response).unwrap_err();
+        assert!(err.to_string().contains("list[].risk_level"));
+    }
+}
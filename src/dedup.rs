@@ -0,0 +1,629 @@
+//! Duplicate detection for ChainUp notifications
+//!
+//! ChainUp delivers a notification again until it is acknowledged, and sends
+//! the same deposit once per confirmation update. A [`DedupStore`] remembers
+//! which notification states were handled, keyed on [`NotificationKey`]
+//! (record kind, ID, status and confirmations): a new status or confirmation
+//! count comes through as [`Delivery::New`], a repeat of a handled state as
+//! [`Delivery::Duplicate`].
+//!
+//! [`NotifyApi::decrypt_notification_once`](crate::mpc::api::NotifyApi::decrypt_notification_once)
+//! and
+//! [`AsyncNotifyApi::notify_request_once`](crate::waas::api::AsyncNotifyApi::notify_request_once)
+//! only look the key up; call [`Delivery::commit`] once the notification was
+//! handled, so a failed attempt is handled again on redelivery. Concurrent
+//! redeliveries of the same state may both come back as new. The webhook
+//! server's `with_dedup` also asks for redelivery of a notification still
+//! being handled.
+//!
+//! Stores:
+//!
+//! - [`MemoryDedupStore`]: the most recently seen keys, in-process only
+//! - [`FileDedupStore`]: the most recently seen keys, in an append-only file
+//! - `SqliteDedupStore`: a table in a SQLite database (`sqlite` feature)
+//!
+//! # Example
+//! ```ignore
+//! let store = FileDedupStore::open("notifications.log")?;
+//! match notify_api.decrypt_notification_once(&data, &store)? {
+//!     delivery @ Delivery::New { .. } => {
+//!         apply(delivery.notification())?;
+//!         delivery.commit(&store)?;
+//!     }
+//!     Delivery::Duplicate { .. } => {}
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::error::{Result, ValidationError};
+use crate::mpc_types::MpcNotifyData;
+use crate::waas_types::NotifyData;
+
+/// Number of keys the LRU stores keep unless configured otherwise
+pub const DEFAULT_CAPACITY: usize = 100_000;
+
+/// Storage for the notification states already handled
+pub trait DedupStore: Send + Sync {
+    /// Whether `key` is marked as handled
+    fn contains(&self, key: &str) -> Result<bool>;
+
+    /// Marks `key` as handled; returns `false` if it already was
+    fn insert(&self, key: &str) -> Result<bool>;
+
+    /// Forgets `key`, so its next delivery counts as new
+    fn forget(&self, key: &str) -> Result<()>;
+}
+
+/// Identity of one notification state
+///
+/// Formatted as `kind:id:status:confirmations`, with `-` for missing parts.
+/// Record IDs are only unique per kind: MPC keys use
+/// [`MpcNotifyData::kind`] (falling back to `side` for unknown kinds), WaaS
+/// keys the `side`. For WaaS withdrawals the audit statuses are part of the
+/// status, so an audit decision is not mistaken for a redelivery.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NotificationKey(String);
+
+impl NotificationKey {
+    /// Builds a key from its parts
+    pub fn new(
+        kind: Option<&str>,
+        id: Option<impl fmt::Display>,
+        status: Option<impl fmt::Display>,
+        confirmations: Option<i32>,
+    ) -> Self {
+        fn part(value: Option<impl fmt::Display>) -> String {
+            value.map_or_else(|| "-".to_string(), |value| value.to_string())
+        }
+        Self(format!(
+            "{}:{}:{}:{}",
+            kind.unwrap_or("-"),
+            part(id),
+            part(status),
+            part(confirmations)
+        ))
+    }
+
+    /// The key as stored
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for NotificationKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&MpcNotifyData> for NotificationKey {
+    fn from(data: &MpcNotifyData) -> Self {
+        let kind = match data.kind() {
+            "unknown" => data.side.as_deref(),
+            kind => Some(kind),
+        };
+        Self::new(
+            kind,
+            data.id,
+            data.status,
+            data.confirmations.or(data.confirm),
+        )
+    }
+}
+
+impl From<&NotifyData> for NotificationKey {
+    fn from(data: &NotifyData) -> Self {
+        let audit = [data.saas_status, data.company_status]
+            .into_iter()
+            .flatten()
+            .map(|status| format!("/{}", status.code()))
+            .collect::<String>();
+        let status = data.status.map(|status| format!("{}{}", status, audit));
+        Self::new(data.side.as_deref(), data.id, status, data.confirmations)
+    }
+}
+
+/// A notification checked against a [`DedupStore`]
+#[derive(Debug, Clone)]
+pub enum Delivery<T> {
+    /// A state not handled yet; [`commit`](Delivery::commit) it once handled
+    New {
+        /// The notification
+        notification: T,
+        /// Its dedup key
+        key: NotificationKey,
+    },
+    /// A state that was handled before
+    Duplicate {
+        /// The notification
+        notification: T,
+        /// Its dedup key
+        key: NotificationKey,
+    },
+}
+
+impl<T> Delivery<T> {
+    /// Classifies `notification` by whether `key` is in `store`
+    ///
+    /// The key is not stored; see [`commit`](Self::commit).
+    pub fn check(store: &dyn DedupStore, key: NotificationKey, notification: T) -> Result<Self> {
+        Ok(if store.contains(key.as_str())? {
+            Delivery::Duplicate { notification, key }
+        } else {
+            Delivery::New { notification, key }
+        })
+    }
+
+    /// Marks a new delivery's state as handled in `store`
+    ///
+    /// Call once handling succeeded; does nothing for a duplicate.
+    pub fn commit(&self, store: &dyn DedupStore) -> Result<()> {
+        if let Delivery::New { key, .. } = self {
+            store.insert(key.as_str())?;
+        }
+        Ok(())
+    }
+
+    /// Whether the state was handled before
+    pub fn is_duplicate(&self) -> bool {
+        matches!(self, Delivery::Duplicate { .. })
+    }
+
+    /// The notification
+    pub fn notification(&self) -> &T {
+        match self {
+            Delivery::New { notification, .. } | Delivery::Duplicate { notification, .. } => {
+                notification
+            }
+        }
+    }
+
+    /// Its dedup key
+    pub fn key(&self) -> &NotificationKey {
+        match self {
+            Delivery::New { key, .. } | Delivery::Duplicate { key, .. } => key,
+        }
+    }
+
+    /// Consumes the delivery, returning the notification
+    pub fn into_notification(self) -> T {
+        match self {
+            Delivery::New { notification, .. } | Delivery::Duplicate { notification, .. } => {
+                notification
+            }
+        }
+    }
+}
+
+/// Keys ordered by last use, dropping the oldest beyond `capacity`
+#[derive(Debug)]
+struct Lru {
+    capacity: usize,
+    tick: u64,
+    ticks: HashMap<String, u64>,
+    keys: BTreeMap<u64, String>,
+}
+
+impl Lru {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            tick: 0,
+            ticks: HashMap::new(),
+            keys: BTreeMap::new(),
+        }
+    }
+
+    /// Marks `key` as most recently used; returns `false` if it was present
+    fn insert(&mut self, key: &str) -> bool {
+        self.tick += 1;
+        if let Some(tick) = self.ticks.get_mut(key) {
+            let key = self.keys.remove(tick).unwrap_or_else(|| key.to_string());
+            *tick = self.tick;
+            self.keys.insert(self.tick, key);
+            return false;
+        }
+
+        self.ticks.insert(key.to_string(), self.tick);
+        self.keys.insert(self.tick, key.to_string());
+        while self.len() > self.capacity {
+            if let Some((_, oldest)) = self.keys.pop_first() {
+                self.ticks.remove(&oldest);
+            }
+        }
+        true
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        self.ticks.contains_key(key)
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(tick) = self.ticks.remove(key) {
+            self.keys.remove(&tick);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.ticks.len()
+    }
+
+    /// Keys from least to most recently used
+    fn keys(&self) -> impl Iterator<Item = &String> {
+        self.keys.values()
+    }
+}
+
+/// The most recently seen keys, kept in memory; lost when the process exits
+#[derive(Debug)]
+pub struct MemoryDedupStore {
+    keys: Mutex<Lru>,
+}
+
+impl Default for MemoryDedupStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl MemoryDedupStore {
+    /// Creates a store remembering up to `capacity` keys
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            keys: Mutex::new(Lru::new(capacity)),
+        }
+    }
+}
+
+impl DedupStore for MemoryDedupStore {
+    fn contains(&self, key: &str) -> Result<bool> {
+        Ok(lock(&self.keys).contains(key))
+    }
+
+    fn insert(&self, key: &str) -> Result<bool> {
+        Ok(lock(&self.keys).insert(key))
+    }
+
+    fn forget(&self, key: &str) -> Result<()> {
+        lock(&self.keys).remove(key);
+        Ok(())
+    }
+}
+
+/// The most recently seen keys, kept in an append-only file
+///
+/// Every change appends a `+key` or `-key` line and syncs it to disk. The
+/// file is rewritten with only the remembered keys when opened and whenever
+/// it grows past twice the capacity, replacing it atomically like
+/// [`FileCheckpointStore`](crate::checkpoint::FileCheckpointStore) does.
+#[derive(Debug)]
+pub struct FileDedupStore {
+    path: PathBuf,
+    state: Mutex<FileState>,
+}
+
+#[derive(Debug)]
+struct FileState {
+    keys: Lru,
+    file: File,
+    lines: usize,
+}
+
+impl FileDedupStore {
+    /// Opens the store at `path` with [`DEFAULT_CAPACITY`]
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_capacity(path, DEFAULT_CAPACITY)
+    }
+
+    /// Opens the store at `path`, remembering up to `capacity` keys
+    pub fn with_capacity(path: impl AsRef<Path>, capacity: usize) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut keys = Lru::new(capacity);
+        match File::open(&path) {
+            Ok(file) => {
+                for line in BufReader::new(file).lines() {
+                    let line = line?;
+                    if let Some(key) = line.strip_prefix('+') {
+                        keys.insert(key);
+                    } else if let Some(key) = line.strip_prefix('-') {
+                        keys.remove(key);
+                    }
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let file = compact(&path, &keys)?;
+        let lines = keys.len();
+        Ok(Self {
+            path,
+            state: Mutex::new(FileState { keys, file, lines }),
+        })
+    }
+
+    /// Path of the file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn append(&self, state: &mut FileState, line: &str) -> Result<()> {
+        if state.lines >= state.keys.capacity * 2 {
+            state.file = compact(&self.path, &state.keys)?;
+            state.lines = state.keys.len();
+        }
+        state.file.write_all(line.as_bytes())?;
+        state.file.write_all(b"\n")?;
+        state.file.sync_data()?;
+        state.lines += 1;
+        Ok(())
+    }
+}
+
+impl DedupStore for FileDedupStore {
+    fn contains(&self, key: &str) -> Result<bool> {
+        Ok(lock(&self.state).keys.contains(key))
+    }
+
+    fn insert(&self, key: &str) -> Result<bool> {
+        check_key(key)?;
+        let mut state = lock(&self.state);
+        if state.keys.contains(key) {
+            // Only refreshes its recency
+            state.keys.insert(key);
+            return Ok(false);
+        }
+        // Written first, so a failed write leaves memory (and the keys the
+        // insert would evict) untouched
+        self.append(&mut state, &format!("+{}", key))?;
+        Ok(state.keys.insert(key))
+    }
+
+    fn forget(&self, key: &str) -> Result<()> {
+        check_key(key)?;
+        let mut state = lock(&self.state);
+        state.keys.remove(key);
+        self.append(&mut state, &format!("-{}", key))
+    }
+}
+
+/// Rewrites `path` with `keys` and opens it for appending
+fn compact(path: &Path, keys: &Lru) -> Result<File> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let mut file = File::create(&tmp_path)?;
+    for key in keys.keys() {
+        writeln!(file, "+{}", key)?;
+    }
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&tmp_path, path)?;
+
+    // Persist the rename itself; not supported on every platform
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        if let Ok(dir) = File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(OpenOptions::new().append(true).open(path)?)
+}
+
+fn check_key(key: &str) -> Result<()> {
+    if key.contains(['\n', '\r']) {
+        return Err(ValidationError::new(format!("Invalid dedup key: {:?}", key)).into());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "sqlite")]
+pub use self::sqlite::SqliteDedupStore;
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use std::path::Path;
+    use std::sync::Mutex;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use rusqlite::{params, Connection};
+
+    use super::{lock, DedupStore};
+    use crate::checkpoint::sqlite::{check_table_name, sqlite_error};
+    use crate::error::Result;
+
+    /// Seen keys kept in a SQLite table
+    ///
+    /// Uses the table `chainup_notification_keys` unless another name is
+    /// given with [`SqliteDedupStore::with_table`]; the table is created if
+    /// missing. Keys are kept until removed with [`prune`](Self::prune).
+    #[derive(Debug)]
+    pub struct SqliteDedupStore {
+        conn: Mutex<Connection>,
+        table: String,
+    }
+
+    impl SqliteDedupStore {
+        /// Default table name
+        pub const DEFAULT_TABLE: &'static str = "chainup_notification_keys";
+
+        /// Opens (or creates) the database at `path`
+        pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+            let conn = Connection::open(path).map_err(sqlite_error)?;
+            Self::with_table(conn, Self::DEFAULT_TABLE)
+        }
+
+        /// Uses an existing connection with the default table
+        pub fn from_connection(conn: Connection) -> Result<Self> {
+            Self::with_table(conn, Self::DEFAULT_TABLE)
+        }
+
+        /// Uses an existing connection and table name
+        ///
+        /// The name may only contain ASCII letters, digits and underscores.
+        pub fn with_table(conn: Connection, table: impl Into<String>) -> Result<Self> {
+            let table = table.into();
+            check_table_name(&table)?;
+            conn.execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    key TEXT PRIMARY KEY NOT NULL,
+                    seen_at INTEGER NOT NULL
+                )",
+                table
+            ))
+            .map_err(sqlite_error)?;
+            Ok(Self {
+                conn: Mutex::new(conn),
+                table,
+            })
+        }
+
+        /// Removes keys first seen more than `age` ago; returns how many
+        pub fn prune(&self, age: Duration) -> Result<usize> {
+            let cutoff = now_millis().saturating_sub(age.as_millis() as i64);
+            lock(&self.conn)
+                .execute(
+                    &format!("DELETE FROM {} WHERE seen_at < ?1", self.table),
+                    params![cutoff],
+                )
+                .map_err(sqlite_error)
+        }
+    }
+
+    impl DedupStore for SqliteDedupStore {
+        fn contains(&self, key: &str) -> Result<bool> {
+            lock(&self.conn)
+                .query_row(
+                    &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE key = ?1)", self.table),
+                    params![key],
+                    |row| row.get(0),
+                )
+                .map_err(sqlite_error)
+        }
+
+        fn insert(&self, key: &str) -> Result<bool> {
+            let inserted = lock(&self.conn)
+                .execute(
+                    &format!(
+                        "INSERT OR IGNORE INTO {} (key, seen_at) VALUES (?1, ?2)",
+                        self.table
+                    ),
+                    params![key, now_millis()],
+                )
+                .map_err(sqlite_error)?;
+            Ok(inserted == 1)
+        }
+
+        fn forget(&self, key: &str) -> Result<()> {
+            lock(&self.conn)
+                .execute(
+                    &format!("DELETE FROM {} WHERE key = ?1", self.table),
+                    params![key],
+                )
+                .map_err(sqlite_error)?;
+            Ok(())
+        }
+    }
+
+    fn now_millis() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as i64)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    // Every update leaves the state consistent, so a poisoned lock is usable
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deposit(confirmations: i32) -> MpcNotifyData {
+        serde_json::from_value(serde_json::json!({
+            "id": 11, "side": "deposit", "status": 1900, "confirmations": confirmations
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_file_dedup_store_survives_reopen() {
+        let path = std::env::temp_dir().join(format!("chainup-dedup-{}.log", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let store = FileDedupStore::with_capacity(&path, 2).unwrap();
+
+        let first = NotificationKey::from(&deposit(1));
+        assert_eq!(first.as_str(), "deposit:11:1900:1");
+        let delivery = Delivery::check(&store, first.clone(), ()).unwrap();
+        assert!(!delivery.is_duplicate());
+        // Not stored until handled
+        assert!(!Delivery::check(&store, first.clone(), ())
+            .unwrap()
+            .is_duplicate());
+        delivery.commit(&store).unwrap();
+        assert!(Delivery::check(&store, first.clone(), ())
+            .unwrap()
+            .is_duplicate());
+        // A new confirmation count is a new state
+        let second = NotificationKey::from(&deposit(2));
+        assert!(store.insert(second.as_str()).unwrap());
+        store.forget(second.as_str()).unwrap();
+        for key in ["a", "b", "c", "d"] {
+            store.insert(key).unwrap();
+        }
+        drop(store);
+
+        // Only the two most recent keys are remembered
+        let store = FileDedupStore::with_capacity(&path, 2).unwrap();
+        assert!(!store.insert("d").unwrap());
+        assert!(!store.insert("c").unwrap());
+        assert!(store.insert(second.as_str()).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 3);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_keys_separate_withdraw_side_kinds() {
+        let notification = |value| -> MpcNotifyData { serde_json::from_value(value).unwrap() };
+        let withdraw = notification(serde_json::json!({
+            "id": 13, "side": "withdraw", "status": 2000
+        }));
+        let collect = notification(serde_json::json!({
+            "id": 13, "side": "withdraw", "trans_type": 10, "status": 2000
+        }));
+        let withdraw = NotificationKey::from(&withdraw);
+        let collect = NotificationKey::from(&collect);
+        assert_eq!(withdraw.as_str(), "withdraw:13:2000:-");
+        assert_eq!(collect.as_str(), "auto_collect:13:2000:-");
+
+        let store = MemoryDedupStore::default();
+        assert!(store.insert(withdraw.as_str()).unwrap());
+        assert!(store.insert(collect.as_str()).unwrap());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_sqlite_dedup_store() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let store = SqliteDedupStore::from_connection(conn).unwrap();
+        assert!(store.insert("deposit:11:1900:1").unwrap());
+        assert!(!store.insert("deposit:11:1900:1").unwrap());
+        assert!(store.contains("deposit:11:1900:1").unwrap());
+        store.forget("deposit:11:1900:1").unwrap();
+        assert!(!store.contains("deposit:11:1900:1").unwrap());
+        assert!(store.insert("deposit:11:1900:1").unwrap());
+        assert_eq!(
+            store.prune(std::time::Duration::from_secs(3600)).unwrap(),
+            0
+        );
+    }
+}
@@ -0,0 +1,440 @@
+//! Schema drift detection for ChainUp responses
+//!
+//! [`DriftDetector`] compares decrypted responses against the SDK's record
+//! types and builds a [`DriftReport`] per endpoint:
+//!
+//! - **new fields**: present in responses but not declared by the record type
+//!   (they end up in its `extra` map)
+//! - **missing fields**: non-optional fields of the record type absent from
+//!   responses
+//! - **type mismatches**: values the record type could not interpret, e.g. a
+//!   timestamp field sent as `"yesterday"`
+//! - **decode errors**: responses the record type cannot decode at all
+//!
+//! Values the record type decodes from a JSON type other than the one it
+//! serializes to (`decimals` sent as `"18"` rather than `18`, timestamps as
+//! formatted strings, decimals as numbers) are accepted forms, not drift. They
+//! are listed under `tolerated_forms`, so a change of form is still visible.
+//!
+//! The report serializes to JSON with sorted keys, so nightly runs can be
+//! diffed directly. Responses can come from live calls (`raw_request`) or
+//! from a recording; see [`DriftDetector::observe_jsonl`] for the format.
+//!
+//! # Example
+//! ```ignore
+//! let mut detector = DriftDetector::new();
+//! let path = "/api/mpc/billing/sync_withdraw_list";
+//! let data = client.raw_request(path, "POST", json!({"max_id": 0}))?;
+//! detector.observe(path, &data);
+//! println!("{}", detector.report().to_json_pretty()?);
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io::BufRead;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::decode::{field_path, UnknownFields};
+use crate::error::{Result, ValidationError};
+use crate::mpc::api::types as mpc;
+use crate::waas::api::types as waas;
+
+/// Drift found for one endpoint
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointReport {
+    /// SDK record type the responses were checked against
+    pub record_type: String,
+    /// Number of records checked
+    pub samples: usize,
+    /// Undeclared fields, with the JSON types seen for each
+    pub new_fields: BTreeMap<String, BTreeSet<String>>,
+    /// Non-optional fields absent from responses, with the number of
+    /// records lacking each
+    pub missing_fields: BTreeMap<String, usize>,
+    /// Fields with values the SDK could not interpret
+    pub type_mismatches: BTreeMap<String, TypeMismatch>,
+    /// Fields sent in a JSON type other than the SDK's that the SDK still
+    /// decodes; not drift
+    pub tolerated_forms: BTreeMap<String, TypeMismatch>,
+    /// Errors from records the SDK type could not decode
+    pub decode_errors: BTreeSet<String>,
+}
+
+impl EndpointReport {
+    /// Returns true if any drift was found
+    pub fn has_drift(&self) -> bool {
+        !self.new_fields.is_empty()
+            || !self.missing_fields.is_empty()
+            || !self.type_mismatches.is_empty()
+            || !self.decode_errors.is_empty()
+    }
+}
+
+/// A field whose JSON type differs from the SDK's
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeMismatch {
+    /// JSON type produced by the SDK record type, or `"unrecognized"` if the
+    /// SDK could not interpret the value
+    pub expected: String,
+    /// JSON types seen in responses
+    pub found: BTreeSet<String>,
+}
+
+/// Drift report over all observed responses
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriftReport {
+    /// Per-endpoint results, keyed by API path
+    pub endpoints: BTreeMap<String, EndpointReport>,
+    /// Endpoints with no known record type, with the number of responses seen
+    pub unknown_endpoints: BTreeMap<String, usize>,
+    /// Number of error responses (non-zero `code`) that were skipped
+    pub error_responses: usize,
+}
+
+impl DriftReport {
+    /// Returns true if any endpoint shows drift
+    pub fn has_drift(&self) -> bool {
+        self.endpoints.values().any(EndpointReport::has_drift)
+    }
+
+    /// Serializes the report as pretty-printed JSON
+    pub fn to_json_pretty(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+type CheckFn = fn(&Value, &mut EndpointReport);
+
+macro_rules! schemas {
+    ($($path:literal => $ty:ty),* $(,)?) => {
+        &[$(($path, stringify!($ty), check_records::<$ty> as CheckFn)),*]
+    };
+}
+
+/// Endpoints with a known record type
+///
+/// Responses may be a single record or a list of records. Webhook payloads
+/// are registered under the pseudo-paths `mpc/notify` and `waas/notify`.
+const SCHEMAS: &[(&str, &str, CheckFn)] = schemas![
+    "/api/mpc/auto_collect/sub_wallets" => mpc::AutoCollectResult,
+    "/api/mpc/billing/sync_auto_collect_list" => mpc::AutoCollectRecord,
+    "/api/mpc/billing/deposit_list" => mpc::DepositRecord,
+    "/api/mpc/billing/sync_deposit_list" => mpc::DepositRecord,
+    "/api/mpc/tron/delegate" => mpc::TronDelegateResult,
+    "/api/mpc/tron/delegate/trans_list" => mpc::TronResourceRecord,
+    "/api/mpc/tron/delegate/sync_trans_list" => mpc::TronResourceRecord,
+    "/api/mpc/sub_wallet/create" => mpc::WalletInfo,
+    "/api/mpc/sub_wallet/create/address" => mpc::WalletAddressInfo,
+    "/api/mpc/sub_wallet/list" => mpc::WalletInfo,
+    "/api/mpc/sub_wallet/get/address/list" => mpc::WalletAddressInfo,
+    "/api/mpc/sub_wallet/assets" => mpc::WalletAssetInfo,
+    "/api/mpc/sub_wallet/address/info" => mpc::WalletAddressInfoResponse,
+    "/api/mpc/web3/trans/create" => mpc::Web3TransRecord,
+    "/api/mpc/web3/pending" => mpc::Web3TransRecord,
+    "/api/mpc/web3/trans_list" => mpc::Web3TransRecord,
+    "/api/mpc/web3/sync_trans_list" => mpc::Web3TransRecord,
+    "/api/mpc/billing/withdraw" => mpc::WithdrawResponse,
+    "/api/mpc/billing/withdraw_list" => mpc::WithdrawRecord,
+    "/api/mpc/billing/sync_withdraw_list" => mpc::WithdrawRecord,
+    "/api/mpc/coin_list" => mpc::CoinDetails,
+    "/api/mpc/chain_height" => mpc::BlockHeightInfo,
+    "/api/mpc/wallet/open_coin" => mpc::GetSupportedCoinsResponse,
+    "mpc/notify" => mpc::MpcNotifyData,
+    "/account/getByUidAndSymbol" => waas::UserAccountInfo,
+    "/account/getDepositAddress" => waas::UserAddressInfo,
+    "/account/getDepositAddressInfo" => waas::UserAddressInfo,
+    "/account/getCompanyBySymbol" => waas::CompanyAccountInfo,
+    "/address/syncList" => waas::UserAddressInfo,
+    "/billing/withdraw" => waas::WithdrawResponse,
+    "/billing/withdrawList" => waas::WithdrawRecord,
+    "/billing/syncWithdrawList" => waas::WithdrawRecord,
+    "/billing/depositList" => waas::DepositRecord,
+    "/billing/syncDepositList" => waas::DepositRecord,
+    "/billing/minerFeeList" => waas::MinerFeeRecord,
+    "/billing/syncMinerFeeList" => waas::MinerFeeRecord,
+    "/user/getCoinList" => waas::CoinInfo,
+    "/account/transfer" => waas::TransferRecord,
+    "/account/transferList" => waas::TransferRecord,
+    "/account/syncTransferList" => waas::TransferRecord,
+    "/user/createUser" => waas::UserInfo,
+    "/user/registerEmail" => waas::UserInfo,
+    "/user/info" => waas::UserInfo,
+    "/user/syncList" => waas::UserInfo,
+    "waas/notify" => waas::NotifyData,
+];
+
+/// Collects drift over decrypted responses
+#[derive(Debug, Default)]
+pub struct DriftDetector {
+    report: DriftReport,
+}
+
+/// One line of a recording read by [`DriftDetector::observe_jsonl`]
+#[derive(Debug, Deserialize)]
+struct RecordedResponse {
+    endpoint: String,
+    response: Value,
+}
+
+impl DriftDetector {
+    /// Creates an empty detector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the API paths the detector has record types for
+    pub fn known_endpoints() -> impl Iterator<Item = &'static str> {
+        SCHEMAS.iter().map(|(path, _, _)| *path)
+    }
+
+    /// Checks one decrypted response
+    ///
+    /// # Arguments
+    /// * `endpoint` - API path, e.g. `/api/mpc/billing/sync_withdraw_list`
+    /// * `response` - Either the full `{"code", "msg", "data"}` envelope or
+    ///   just its `data` field (as returned by `raw_request`)
+    pub fn observe(&mut self, endpoint: &str, response: &Value) {
+        let data = match response {
+            Value::Object(map) if map.contains_key("code") => {
+                let code = match &map["code"] {
+                    Value::String(s) => s.parse::<i64>().ok(),
+                    other => other.as_i64(),
+                };
+                if code != Some(0) {
+                    self.report.error_responses += 1;
+                    return;
+                }
+                map.get("data").unwrap_or(&Value::Null)
+            }
+            other => other,
+        };
+
+        let endpoint = endpoint.trim();
+        match SCHEMAS.iter().find(|(path, _, _)| *path == endpoint) {
+            Some((path, record_type, check)) => {
+                let report = self.report.endpoints.entry(path.to_string()).or_default();
+                report.record_type = record_type
+                    .rsplit("::")
+                    .next()
+                    .unwrap_or(record_type)
+                    .to_string();
+                check(data, report);
+            }
+            None => {
+                *self
+                    .report
+                    .unknown_endpoints
+                    .entry(endpoint.to_string())
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    /// Checks every response in a JSON Lines recording
+    ///
+    /// Each non-empty line is `{"endpoint": "<path>", "response": <json>}`,
+    /// where `response` is anything accepted by [`Self::observe`].
+    ///
+    /// # Returns
+    /// Number of responses read
+    pub fn observe_jsonl<R: BufRead>(&mut self, reader: R) -> Result<usize> {
+        let mut count = 0;
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let recorded: RecordedResponse = serde_json::from_str(&line).map_err(|e| {
+                ValidationError::new(format!("Invalid recording at line {}: {}", index + 1, e))
+            })?;
+            self.observe(&recorded.endpoint, &recorded.response);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Returns the report so far
+    pub fn report(&self) -> &DriftReport {
+        &self.report
+    }
+
+    /// Consumes the detector, returning the report
+    pub fn into_report(self) -> DriftReport {
+        self.report
+    }
+}
+
+/// Checks a single record or a list of records against `T`
+fn check_records<T>(data: &Value, report: &mut EndpointReport)
+where
+    T: DeserializeOwned + Serialize + UnknownFields,
+{
+    match data {
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| check_record::<T>(item, report)),
+        Value::Null => {}
+        other => check_record::<T>(other, report),
+    }
+}
+
+fn check_record<T>(input: &Value, report: &mut EndpointReport)
+where
+    T: DeserializeOwned + Serialize + UnknownFields,
+{
+    report.samples += 1;
+
+    let record: T = match serde_json::from_value(input.clone()) {
+        Ok(record) => record,
+        Err(e) => {
+            report.decode_errors.insert(e.to_string());
+            return;
+        }
+    };
+    let canonical = match serde_json::to_value(&record) {
+        Ok(value) => value,
+        Err(e) => {
+            report.decode_errors.insert(e.to_string());
+            return;
+        }
+    };
+    let unknown: HashSet<String> = record.unknown_fields().into_iter().collect();
+
+    compare(input, &canonical, "", &unknown, report);
+}
+
+/// Walks the response alongside the SDK's serialization of it
+fn compare(
+    input: &Value,
+    canonical: &Value,
+    path: &str,
+    unknown: &HashSet<String>,
+    report: &mut EndpointReport,
+) {
+    match (input, canonical) {
+        (Value::Object(input), Value::Object(canonical)) => {
+            for (key, expected) in canonical {
+                let field = field_path(path, key);
+                match input.get(key) {
+                    Some(found) if unknown.contains(&field) => {
+                        report
+                            .new_fields
+                            .entry(field)
+                            .or_default()
+                            .insert(json_type(found).to_string());
+                    }
+                    Some(found) => compare(found, expected, &field, unknown, report),
+                    // An absent optional field decodes to null
+                    None if expected.is_null() => {}
+                    None => *report.missing_fields.entry(field).or_default() += 1,
+                }
+            }
+        }
+        (Value::Array(input), Value::Array(canonical)) => {
+            let field = format!("{}[]", path);
+            for (found, expected) in input.iter().zip(canonical) {
+                compare(found, expected, &field, unknown, report);
+            }
+        }
+        // Null in the response is fine for any field; the SDK defaults it
+        (Value::Null, _) => {}
+        (found, expected) => {
+            let (found, expected) = (json_type(found), json_type(expected));
+            if found != expected {
+                // A value decoded to null was dropped; anything else is a
+                // form the field's deserializer accepts
+                let (mismatches, expected) = if expected == "null" {
+                    (&mut report.type_mismatches, "unrecognized")
+                } else {
+                    (&mut report.tolerated_forms, expected)
+                };
+                mismatches
+                    .entry(path.to_string())
+                    .or_insert_with(|| TypeMismatch {
+                        expected: expected.to_string(),
+                        found: BTreeSet::new(),
+                    })
+                    .found
+                    .insert(found.to_string());
+            }
+        }
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_drift_report() {
+        let mut detector = DriftDetector::new();
+        detector.observe(
+            "/api/mpc/coin_list",
+            &json!({
+                "code": "0",
+                "msg": "success",
+                "data": [{
+                    "symbol": "ETH",
+                    "decimals": "18",
+                    "risk_level": 2
+                }]
+            }),
+        );
+        detector.observe(
+            "/api/mpc/coin_list",
+            &json!([{"symbol": "BTC", "decimals": "eight"}]),
+        );
+        detector.observe(
+            "/api/mpc/coin_list",
+            &json!({"code": "110", "msg": "error"}),
+        );
+        detector.observe("/api/mpc/new_endpoint", &json!([]));
+
+        let report = detector.into_report();
+        let coins = &report.endpoints["/api/mpc/coin_list"];
+        assert_eq!(coins.record_type, "CoinDetails");
+        assert_eq!(coins.samples, 2);
+        assert!(coins.new_fields["risk_level"].contains("integer"));
+        assert_eq!(coins.tolerated_forms["decimals"].expected, "integer");
+        assert!(coins.tolerated_forms["decimals"].found.contains("string"));
+        assert_eq!(coins.type_mismatches["decimals"].expected, "unrecognized");
+        assert!(!coins.missing_fields.contains_key("base_symbol"));
+        assert_eq!(report.error_responses, 1);
+        assert_eq!(report.unknown_endpoints["/api/mpc/new_endpoint"], 1);
+        assert!(report.has_drift());
+    }
+
+    #[test]
+    fn test_missing_fields_are_non_optional() {
+        #[derive(Serialize, Deserialize)]
+        struct Sample {
+            #[serde(default)]
+            name: String,
+            #[serde(default)]
+            note: Option<String>,
+            #[serde(flatten)]
+            extra: std::collections::HashMap<String, Value>,
+        }
+        crate::decode::impl_unknown_fields!(Sample);
+
+        let mut report = EndpointReport::default();
+        check_records::<Sample>(&json!([{}, {"name": "a"}]), &mut report);
+        assert_eq!(report.missing_fields.len(), 1);
+        assert_eq!(report.missing_fields["name"], 1);
+    }
+}
@@ -0,0 +1,113 @@
+//! Generic endpoint calls
+//!
+//! ChainUp sometimes ships endpoints before the SDK wraps them. [`Endpoint`]
+//! describes such an endpoint so it can be called through
+//! [`MpcClient::call`](crate::MpcClient::call) or
+//! [`WaasClient::call`](crate::WaasClient::call), reusing the same
+//! encryption, response decryption and `code`/`msg` validation as the
+//! wrapped APIs. For one-off calls, `raw_request` takes a path, a method and
+//! a `serde_json::Value` instead.
+//!
+//! # Example
+//! ```ignore
+//! use chainup_custody_sdk::Endpoint;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize)]
+//! struct NewEndpointParams {
+//!     symbol: String,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct NewEndpointItem {
+//!     id: i64,
+//!     symbol: Option<String>,
+//! }
+//!
+//! struct NewEndpoint;
+//!
+//! impl Endpoint for NewEndpoint {
+//!     type Params = NewEndpointParams;
+//!     type Response = Vec<NewEndpointItem>;
+//!     const PATH: &'static str = "/api/mpc/new_endpoint";
+//! }
+//!
+//! let items = client.call::<NewEndpoint>(&NewEndpointParams { symbol: "ETH".into() })?;
+//!
+//! // Or without declaring types
+//! let raw = client.raw_request("/api/mpc/new_endpoint", "POST", json!({"symbol": "ETH"}))?;
+//! ```
+
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{Result, ValidationError};
+
+/// An API endpoint: its path, HTTP method, parameter type and response type
+pub trait Endpoint {
+    /// Request parameters, serialized as a JSON object
+    ///
+    /// Use `()` for endpoints without parameters.
+    type Params: Serialize;
+
+    /// Type of the response `data` field
+    type Response: DeserializeOwned;
+
+    /// API path, e.g. `/api/mpc/wallet/create`
+    const PATH: &'static str;
+
+    /// HTTP method, `"POST"` or `"GET"`
+    const METHOD: &'static str = "POST";
+}
+
+/// Normalizes an HTTP method name, accepting only GET and POST
+pub(crate) fn normalize_method(method: &str) -> Result<&'static str> {
+    match method.to_ascii_uppercase().as_str() {
+        "POST" => Ok("POST"),
+        "GET" => Ok("GET"),
+        _ => Err(ValidationError::new(format!(
+            "Unsupported HTTP method '{}': expected GET or POST",
+            method
+        ))
+        .into()),
+    }
+}
+
+/// Converts request parameters into the map sent to ChainUp
+///
+/// `null` means no parameters; anything other than a JSON object is rejected.
+pub(crate) fn params_to_map(params: Value) -> Result<Option<HashMap<String, Value>>> {
+    match params {
+        Value::Null => Ok(None),
+        Value::Object(map) => Ok(Some(map.into_iter().collect())),
+        other => Err(ValidationError::new(format!(
+            "Request parameters must be a JSON object, got: {}",
+            other
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_params_to_map() {
+        let map = params_to_map(json!({"symbol": "ETH", "max_id": 0}))
+            .unwrap()
+            .unwrap();
+        assert_eq!(map["symbol"], "ETH");
+        assert_eq!(map["max_id"], 0);
+
+        assert!(params_to_map(Value::Null).unwrap().is_none());
+        assert!(params_to_map(json!([1, 2])).is_err());
+
+        assert_eq!(normalize_method("get").unwrap(), "GET");
+        assert!(normalize_method("DELETE").is_err());
+    }
+}
@@ -2,75 +2,106 @@
 //!
 //! This module defines enums matching the Python SDK's enums.
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// API response codes
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-#[repr(i32)]
 pub enum ApiCode {
     /// Success
-    Success = 0,
+    Success,
 
     // System errors
     /// System error
-    SystemError = 100001,
+    SystemError,
     /// Invalid request parameters
-    ParamInvalid = 100004,
+    ParamInvalid,
     /// Signature verification failed
-    SignError = 100005,
+    SignError,
     /// IP address not allowed
-    IpForbidden = 100007,
+    IpForbidden,
     /// Invalid merchant ID
-    MerchantIdInvalid = 100015,
+    MerchantIdInvalid,
     /// Merchant information expired
-    MerchantExpired = 100016,
+    MerchantExpired,
 
     // User-related errors
     /// User is frozen, withdrawal not allowed
-    UserFrozen = 110004,
+    UserFrozen,
     /// Mobile number already registered
-    MobileRegistered = 110023,
+    MobileRegistered,
     /// Withdrawal address has risk
-    WithdrawAddressRisk = 110037,
+    WithdrawAddressRisk,
     /// Invalid withdrawal address
-    WithdrawAddressError = 110055,
+    WithdrawAddressError,
     /// User does not exist
-    UserNotExist = 110065,
+    UserNotExist,
     /// Withdrawal or transfer amount below minimum
-    AmountBelowMin = 110078,
+    AmountBelowMin,
     /// Withdrawal or transfer amount exceeds maximum
-    AmountExceedMax = 110087,
+    AmountExceedMax,
     /// Duplicate request
-    DuplicateRequest = 110088,
+    DuplicateRequest,
     /// Invalid mobile number for registration
-    MobileInvalid = 110089,
+    MobileInvalid,
     /// User registration failed
-    RegisterFailed = 110101,
+    RegisterFailed,
     /// Withdrawal precision exceeded maximum supported
-    PrecisionExceeded = 110161,
+    PrecisionExceeded,
 
     // Coin/transaction-related errors
     /// Coin not supported
-    CoinNotSupported = 120202,
+    CoinNotSupported,
     /// Withdrawal confirmation failed
-    ConfirmFailed = 120206,
+    ConfirmFailed,
     /// Insufficient balance for withdrawal or transfer
-    BalanceInsufficient = 120402,
+    BalanceInsufficient,
     /// Insufficient balance for withdrawal fee
-    FeeInsufficient = 120403,
+    FeeInsufficient,
     /// Withdrawal or transfer amount too small
-    AmountLessThanFee = 120404,
+    AmountLessThanFee,
 
     // Risk control errors
     /// User has risk, withdrawal forbidden
-    UserRiskForbidden = 900006,
+    UserRiskForbidden,
 
     // Transfer-related errors
     /// Cannot transfer to self
-    SelfTransferForbidden = 3040006,
+    SelfTransferForbidden,
+
+    /// Code not known to this SDK version
+    Unknown(i32),
 }
 
 impl ApiCode {
+    /// All codes known to this SDK version
+    pub const KNOWN: &'static [ApiCode] = &[
+        ApiCode::Success,
+        ApiCode::SystemError,
+        ApiCode::ParamInvalid,
+        ApiCode::SignError,
+        ApiCode::IpForbidden,
+        ApiCode::MerchantIdInvalid,
+        ApiCode::MerchantExpired,
+        ApiCode::UserFrozen,
+        ApiCode::MobileRegistered,
+        ApiCode::WithdrawAddressRisk,
+        ApiCode::WithdrawAddressError,
+        ApiCode::UserNotExist,
+        ApiCode::AmountBelowMin,
+        ApiCode::AmountExceedMax,
+        ApiCode::DuplicateRequest,
+        ApiCode::MobileInvalid,
+        ApiCode::RegisterFailed,
+        ApiCode::PrecisionExceeded,
+        ApiCode::CoinNotSupported,
+        ApiCode::ConfirmFailed,
+        ApiCode::BalanceInsufficient,
+        ApiCode::FeeInsufficient,
+        ApiCode::AmountLessThanFee,
+        ApiCode::UserRiskForbidden,
+        ApiCode::SelfTransferForbidden,
+    ];
+
     /// Check if the code represents success
     pub fn is_success(&self) -> bool {
         matches!(self, ApiCode::Success)
@@ -78,39 +109,83 @@ impl ApiCode {
 
     /// Get the numeric value of the code
     pub fn code(&self) -> i32 {
-        *self as i32
+        match self {
+            ApiCode::Success => 0,
+            ApiCode::SystemError => 100001,
+            ApiCode::ParamInvalid => 100004,
+            ApiCode::SignError => 100005,
+            ApiCode::IpForbidden => 100007,
+            ApiCode::MerchantIdInvalid => 100015,
+            ApiCode::MerchantExpired => 100016,
+            ApiCode::UserFrozen => 110004,
+            ApiCode::MobileRegistered => 110023,
+            ApiCode::WithdrawAddressRisk => 110037,
+            ApiCode::WithdrawAddressError => 110055,
+            ApiCode::UserNotExist => 110065,
+            ApiCode::AmountBelowMin => 110078,
+            ApiCode::AmountExceedMax => 110087,
+            ApiCode::DuplicateRequest => 110088,
+            ApiCode::MobileInvalid => 110089,
+            ApiCode::RegisterFailed => 110101,
+            ApiCode::PrecisionExceeded => 110161,
+            ApiCode::CoinNotSupported => 120202,
+            ApiCode::ConfirmFailed => 120206,
+            ApiCode::BalanceInsufficient => 120402,
+            ApiCode::FeeInsufficient => 120403,
+            ApiCode::AmountLessThanFee => 120404,
+            ApiCode::UserRiskForbidden => 900006,
+            ApiCode::SelfTransferForbidden => 3040006,
+            ApiCode::Unknown(code) => *code,
+        }
     }
 
     /// Try to convert from an i32 value
+    ///
+    /// Returns `None` for codes not known to this SDK version;
+    /// use `ApiCode::from(code)` to get an `Unknown` fallback instead.
     pub fn from_code(code: i32) -> Option<Self> {
-        match code {
-            0 => Some(ApiCode::Success),
-            100001 => Some(ApiCode::SystemError),
-            100004 => Some(ApiCode::ParamInvalid),
-            100005 => Some(ApiCode::SignError),
-            100007 => Some(ApiCode::IpForbidden),
-            100015 => Some(ApiCode::MerchantIdInvalid),
-            100016 => Some(ApiCode::MerchantExpired),
-            110004 => Some(ApiCode::UserFrozen),
-            110023 => Some(ApiCode::MobileRegistered),
-            110037 => Some(ApiCode::WithdrawAddressRisk),
-            110055 => Some(ApiCode::WithdrawAddressError),
-            110065 => Some(ApiCode::UserNotExist),
-            110078 => Some(ApiCode::AmountBelowMin),
-            110087 => Some(ApiCode::AmountExceedMax),
-            110088 => Some(ApiCode::DuplicateRequest),
-            110089 => Some(ApiCode::MobileInvalid),
-            110101 => Some(ApiCode::RegisterFailed),
-            110161 => Some(ApiCode::PrecisionExceeded),
-            120202 => Some(ApiCode::CoinNotSupported),
-            120206 => Some(ApiCode::ConfirmFailed),
-            120402 => Some(ApiCode::BalanceInsufficient),
-            120403 => Some(ApiCode::FeeInsufficient),
-            120404 => Some(ApiCode::AmountLessThanFee),
-            900006 => Some(ApiCode::UserRiskForbidden),
-            3040006 => Some(ApiCode::SelfTransferForbidden),
-            _ => None,
-        }
+        Self::KNOWN.iter().copied().find(|c| c.code() == code)
+    }
+
+    /// Whether the request may succeed if sent again unchanged
+    ///
+    /// Only transient server-side failures qualify; business errors such as
+    /// insufficient balance or a duplicate request id never do.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ApiCode::SystemError)
+    }
+
+    /// Whether the request id was already used (the original request was accepted)
+    pub fn is_duplicate(&self) -> bool {
+        matches!(self, ApiCode::DuplicateRequest)
+    }
+
+    /// Whether the balance does not cover the amount or the fee
+    pub fn is_insufficient_funds(&self) -> bool {
+        matches!(self, ApiCode::BalanceInsufficient | ApiCode::FeeInsufficient)
+    }
+
+    /// Whether the request was rejected for credentials, signature or IP whitelist reasons
+    pub fn is_auth_failure(&self) -> bool {
+        matches!(
+            self,
+            ApiCode::SignError
+                | ApiCode::IpForbidden
+                | ApiCode::MerchantIdInvalid
+                | ApiCode::MerchantExpired
+        )
+    }
+}
+
+impl From<i32> for ApiCode {
+    fn from(code: i32) -> Self {
+        Self::from_code(code).unwrap_or(ApiCode::Unknown(code))
+    }
+}
+
+impl From<ApiCode> for i32 {
+    fn from(code: ApiCode) -> Self {
+        code.code()
     }
 }
 
@@ -129,66 +204,241 @@ pub enum HttpMethod {
     Patch,
 }
 
+/// Implements the `i32` conversions and the integer wire format of a status enum
+///
+/// The enum must provide `code()` and `from_code()` and have an `Unknown(i32)`
+/// variant. Deserialization accepts both numbers and numeric strings.
+macro_rules! impl_status_code {
+    ($name:ident) => {
+        impl From<i32> for $name {
+            fn from(code: i32) -> Self {
+                Self::from_code(code).unwrap_or($name::Unknown(code))
+            }
+        }
+
+        impl From<$name> for i32 {
+            fn from(status: $name) -> Self {
+                status.code()
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_i32(self.code())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                #[derive(Deserialize)]
+                #[serde(untagged)]
+                enum StringOrInt {
+                    String(String),
+                    Int(i32),
+                }
+
+                let code = match StringOrInt::deserialize(deserializer)? {
+                    StringOrInt::Int(code) => code,
+                    StringOrInt::String(s) => s.trim().parse().map_err(|_| {
+                        serde::de::Error::custom(format!(
+                            "invalid {} code: {:?}",
+                            stringify!($name),
+                            s
+                        ))
+                    })?,
+                };
+                Ok($name::from(code))
+            }
+        }
+    };
+}
+
 /// MPC Deposit status
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MpcDepositStatus {
     /// Confirming
-    Confirming = 1900,
+    Confirming,
     /// Success
-    Success = 2000,
+    Success,
     /// Failed
-    Failed = 2400,
+    Failed,
+    /// Status not known to this SDK version
+    Unknown(i32),
 }
 
 impl MpcDepositStatus {
+    /// All statuses known to this SDK version
+    pub const KNOWN: &'static [MpcDepositStatus] = &[
+        MpcDepositStatus::Confirming,
+        MpcDepositStatus::Success,
+        MpcDepositStatus::Failed,
+    ];
+
+    /// Get the numeric value of the status
+    pub fn code(&self) -> i32 {
+        match self {
+            MpcDepositStatus::Confirming => 1900,
+            MpcDepositStatus::Success => 2000,
+            MpcDepositStatus::Failed => 2400,
+            MpcDepositStatus::Unknown(code) => *code,
+        }
+    }
+
     /// Try to convert from an i32 value
     pub fn from_code(code: i32) -> Option<Self> {
-        match code {
-            1900 => Some(MpcDepositStatus::Confirming),
-            2000 => Some(MpcDepositStatus::Success),
-            2400 => Some(MpcDepositStatus::Failed),
-            _ => None,
-        }
+        Self::KNOWN.iter().copied().find(|s| s.code() == code)
+    }
+
+    /// Whether the deposit will not change status any more
+    pub fn is_terminal(&self) -> bool {
+        self.is_success() || self.is_failed()
+    }
+
+    /// Whether the deposit was credited
+    pub fn is_success(&self) -> bool {
+        matches!(self, MpcDepositStatus::Success)
+    }
+
+    /// Whether the deposit ended without being credited
+    pub fn is_failed(&self) -> bool {
+        matches!(self, MpcDepositStatus::Failed)
     }
 }
 
+impl_status_code!(MpcDepositStatus);
+
 /// MPC Withdraw status
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[repr(i32)]
+///
+/// Web3 transactions report the same status codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MpcWithdrawStatus {
     /// Pending audit
-    PendingAudit = 1000,
+    PendingAudit,
     /// Audit passed
-    AuditPassed = 1100,
+    AuditPassed,
     /// Processing
-    Processing = 1200,
+    Processing,
     /// Cancelled
-    Cancelled = 2200,
+    Cancelled,
     /// Audit rejected
-    AuditRejected = 2300,
+    AuditRejected,
     /// Success
-    Success = 2000,
+    Success,
     /// Failed
-    Failed = 2400,
+    Failed,
+    /// Status not known to this SDK version
+    Unknown(i32),
 }
 
 impl MpcWithdrawStatus {
+    /// All statuses known to this SDK version
+    pub const KNOWN: &'static [MpcWithdrawStatus] = &[
+        MpcWithdrawStatus::PendingAudit,
+        MpcWithdrawStatus::AuditPassed,
+        MpcWithdrawStatus::Processing,
+        MpcWithdrawStatus::Success,
+        MpcWithdrawStatus::Cancelled,
+        MpcWithdrawStatus::AuditRejected,
+        MpcWithdrawStatus::Failed,
+    ];
+
+    /// Get the numeric value of the status
+    pub fn code(&self) -> i32 {
+        match self {
+            MpcWithdrawStatus::PendingAudit => 1000,
+            MpcWithdrawStatus::AuditPassed => 1100,
+            MpcWithdrawStatus::Processing => 1200,
+            MpcWithdrawStatus::Success => 2000,
+            MpcWithdrawStatus::Cancelled => 2200,
+            MpcWithdrawStatus::AuditRejected => 2300,
+            MpcWithdrawStatus::Failed => 2400,
+            MpcWithdrawStatus::Unknown(code) => *code,
+        }
+    }
+
     /// Try to convert from an i32 value
     pub fn from_code(code: i32) -> Option<Self> {
-        match code {
-            1000 => Some(MpcWithdrawStatus::PendingAudit),
-            1100 => Some(MpcWithdrawStatus::AuditPassed),
-            1200 => Some(MpcWithdrawStatus::Processing),
-            2000 => Some(MpcWithdrawStatus::Success),
-            2200 => Some(MpcWithdrawStatus::Cancelled),
-            2300 => Some(MpcWithdrawStatus::AuditRejected),
-            2400 => Some(MpcWithdrawStatus::Failed),
-            _ => None,
+        Self::KNOWN.iter().copied().find(|s| s.code() == code)
+    }
+
+    /// Whether the withdrawal will not change status any more
+    pub fn is_terminal(&self) -> bool {
+        self.is_success() || self.is_failed()
+    }
+
+    /// Whether the withdrawal was sent on chain successfully
+    pub fn is_success(&self) -> bool {
+        matches!(self, MpcWithdrawStatus::Success)
+    }
+
+    /// Whether the withdrawal ended without funds leaving the wallet
+    /// (cancelled, rejected in audit, or failed)
+    pub fn is_failed(&self) -> bool {
+        matches!(
+            self,
+            MpcWithdrawStatus::Cancelled
+                | MpcWithdrawStatus::AuditRejected
+                | MpcWithdrawStatus::Failed
+        )
+    }
+}
+
+impl_status_code!(MpcWithdrawStatus);
+
+/// WaaS withdrawal audit status (`saas_status` / `company_status`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WaasAuditStatus {
+    /// Not audited yet
+    Pending,
+    /// Audit approved
+    Approved,
+    /// Audit rejected
+    Rejected,
+    /// Status not known to this SDK version
+    Unknown(i32),
+}
+
+impl WaasAuditStatus {
+    /// All statuses known to this SDK version
+    pub const KNOWN: &'static [WaasAuditStatus] = &[
+        WaasAuditStatus::Pending,
+        WaasAuditStatus::Approved,
+        WaasAuditStatus::Rejected,
+    ];
+
+    /// Get the numeric value of the status
+    pub fn code(&self) -> i32 {
+        match self {
+            WaasAuditStatus::Pending => 0,
+            WaasAuditStatus::Approved => 1,
+            WaasAuditStatus::Rejected => 2,
+            WaasAuditStatus::Unknown(code) => *code,
         }
     }
+
+    /// Try to convert from an i32 value
+    pub fn from_code(code: i32) -> Option<Self> {
+        Self::KNOWN.iter().copied().find(|s| s.code() == code)
+    }
+
+    /// Whether the audit has been decided
+    pub fn is_terminal(&self) -> bool {
+        self.is_success() || self.is_failed()
+    }
+
+    /// Whether the audit was approved
+    pub fn is_success(&self) -> bool {
+        matches!(self, WaasAuditStatus::Approved)
+    }
+
+    /// Whether the audit was rejected
+    pub fn is_failed(&self) -> bool {
+        matches!(self, WaasAuditStatus::Rejected)
+    }
 }
 
+impl_status_code!(WaasAuditStatus);
+
 /// MPC Web3 transaction type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[repr(i32)]
@@ -378,3 +628,39 @@ pub enum TransactionSide {
     /// Withdrawal transaction
     Withdraw,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_wire_format() {
+        let status: MpcWithdrawStatus = serde_json::from_str("\"2300\"").unwrap();
+        assert_eq!(status, MpcWithdrawStatus::AuditRejected);
+        assert!(status.is_terminal() && status.is_failed() && !status.is_success());
+
+        let status: MpcDepositStatus = serde_json::from_str("1900").unwrap();
+        assert!(!status.is_terminal());
+
+        let status: MpcWithdrawStatus = serde_json::from_str("1300").unwrap();
+        assert_eq!(status, MpcWithdrawStatus::Unknown(1300));
+        assert!(!status.is_terminal());
+        assert_eq!(serde_json::to_string(&status).unwrap(), "1300");
+
+        assert!(serde_json::from_str::<WaasAuditStatus>("\"abc\"").is_err());
+    }
+
+    #[test]
+    fn test_lenient_optional_status_fields() {
+        let notify: crate::waas_types::NotifyData = serde_json::from_value(serde_json::json!({
+            "side": "withdraw", "id": 5, "saas_status": "", "company_status": "1"
+        }))
+        .unwrap();
+        assert_eq!(notify.saas_status, None);
+        assert_eq!(notify.company_status, Some(WaasAuditStatus::Approved));
+
+        let record: crate::mpc_types::WithdrawRecord =
+            serde_json::from_value(serde_json::json!({"id": 5, "status": ""})).unwrap();
+        assert_eq!(record.status, None);
+    }
+}
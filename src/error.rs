@@ -4,8 +4,11 @@
 //! matching the Python SDK's exception structure.
 
 use std::fmt;
+use std::time::Duration;
 use thiserror::Error;
 
+use crate::enums::ApiCode;
+
 /// Base error type for all ChainUp SDK errors
 #[derive(Error, Debug)]
 pub enum ChainUpError {
@@ -40,6 +43,116 @@ pub enum ChainUpError {
     /// Rate limiting errors
     #[error("Rate limit error: {0}")]
     RateLimit(RateLimitError),
+
+    /// Server-side failures (HTTP 5xx), safe to retry
+    #[error("Server error: {0}")]
+    Server(ServerError),
+
+    /// Requests rejected by the server (HTTP 4xx other than auth and rate limits)
+    #[error("Request error: {0}")]
+    Request(RequestError),
+
+    /// Local I/O errors (key files, checkpoints, webhook listener)
+    #[error("IO error: {0}")]
+    Io(#[source] std::io::Error),
+}
+
+/// Structured context of the API call an error belongs to
+///
+/// Attached by the HTTP client and the base APIs, and read back through
+/// [`ChainUpError::context`] and its shorthand accessors.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// API path (e.g. "/api/mpc/billing/withdraw")
+    pub endpoint: Option<String>,
+    /// HTTP method ("GET" or "POST")
+    pub method: Option<String>,
+    /// HTTP status code of the response
+    pub http_status: Option<u16>,
+    /// `request_id` parameter of the call, when present
+    pub request_id: Option<String>,
+    /// Response body, truncated and with encrypted/secret fields redacted
+    pub body: Option<String>,
+    /// Time from sending the request until the error was raised
+    pub elapsed: Option<Duration>,
+}
+
+impl ErrorContext {
+    /// Maximum number of characters kept from a response body
+    pub const MAX_BODY_LEN: usize = 512;
+
+    /// JSON fields whose values are replaced in recorded bodies
+    const REDACTED_FIELDS: [&'static str; 4] = ["data", "sign", "private_key", "secret"];
+
+    /// Creates an empty context
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the endpoint path and HTTP method
+    pub fn with_endpoint(mut self, method: impl Into<String>, endpoint: impl Into<String>) -> Self {
+        self.method = Some(method.into());
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// Sets the HTTP status code
+    pub fn with_http_status(mut self, status: u16) -> Self {
+        self.http_status = Some(status);
+        self
+    }
+
+    /// Sets the request ID
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Records a response body, redacting secret fields and truncating it
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.body = Some(Self::sanitize_body(body));
+        self
+    }
+
+    /// Sets the elapsed time
+    pub fn with_elapsed(mut self, elapsed: Duration) -> Self {
+        self.elapsed = Some(elapsed);
+        self
+    }
+
+    /// Fills fields that are unset here from `other`
+    pub fn merge(&mut self, other: ErrorContext) {
+        self.endpoint = self.endpoint.take().or(other.endpoint);
+        self.method = self.method.take().or(other.method);
+        self.http_status = self.http_status.or(other.http_status);
+        self.request_id = self.request_id.take().or(other.request_id);
+        self.body = self.body.take().or(other.body);
+        self.elapsed = self.elapsed.or(other.elapsed);
+    }
+
+    fn sanitize_body(body: &str) -> String {
+        let redacted = match serde_json::from_str::<serde_json::Value>(body) {
+            Ok(serde_json::Value::Object(mut map)) => {
+                for field in Self::REDACTED_FIELDS {
+                    if let Some(value) = map.get_mut(field) {
+                        if let serde_json::Value::String(s) = value {
+                            *value =
+                                serde_json::Value::String(format!("<redacted {} chars>", s.len()));
+                        }
+                    }
+                }
+                serde_json::Value::Object(map).to_string()
+            }
+            _ => body.to_string(),
+        };
+
+        if redacted.chars().count() > Self::MAX_BODY_LEN {
+            let truncated: String = redacted.chars().take(Self::MAX_BODY_LEN).collect();
+            format!("{}...", truncated)
+        } else {
+            redacted
+        }
+    }
 }
 
 /// API request error with code and message
@@ -51,6 +164,8 @@ pub struct ApiError {
     pub message: String,
     /// Optional additional data
     pub data: Option<serde_json::Value>,
+    /// Request context (endpoint, request_id, HTTP status, ...)
+    pub context: Option<Box<ErrorContext>>,
 }
 
 impl fmt::Display for ApiError {
@@ -68,6 +183,7 @@ impl ApiError {
             code,
             message: message.into(),
             data: None,
+            context: None,
         }
     }
 
@@ -77,6 +193,129 @@ impl ApiError {
             code,
             message: message.into(),
             data: Some(data),
+            context: None,
+        }
+    }
+
+    /// Parsed error code (`ApiCode::Unknown` for codes not known to this SDK)
+    pub fn api_code(&self) -> ApiCode {
+        ApiCode::from(self.code)
+    }
+
+    /// Whether the request may succeed if sent again unchanged
+    pub fn is_retryable(&self) -> bool {
+        self.api_code().is_retryable()
+    }
+
+    /// Whether the request id was already used
+    pub fn is_duplicate(&self) -> bool {
+        self.api_code().is_duplicate()
+    }
+
+    /// Whether the balance does not cover the amount or the fee
+    pub fn is_insufficient_funds(&self) -> bool {
+        self.api_code().is_insufficient_funds()
+    }
+
+    /// Whether the request was rejected for credentials, signature or IP reasons
+    pub fn is_auth_failure(&self) -> bool {
+        self.api_code().is_auth_failure()
+    }
+}
+
+impl ChainUpError {
+    /// Returns the context of the API call this error belongs to
+    ///
+    /// `None` for errors raised outside an API call (configuration, local IO).
+    pub fn context(&self) -> Option<&ErrorContext> {
+        let context = match self {
+            ChainUpError::Api(e) => &e.context,
+            ChainUpError::Config(e) => &e.context,
+            ChainUpError::Crypto(e) => &e.context,
+            ChainUpError::Network(e) => &e.context,
+            ChainUpError::Validation(e) => &e.context,
+            ChainUpError::Signature(e) => &e.context,
+            ChainUpError::Authentication(e) => &e.context,
+            ChainUpError::RateLimit(e) => &e.context,
+            ChainUpError::Server(e) => &e.context,
+            ChainUpError::Request(e) => &e.context,
+            ChainUpError::Io(_) => return None,
+        };
+        context.as_deref()
+    }
+
+    /// Attaches request context, keeping any fields that are already set
+    pub fn with_context(mut self, context: ErrorContext) -> Self {
+        let slot = match &mut self {
+            ChainUpError::Api(e) => &mut e.context,
+            ChainUpError::Config(e) => &mut e.context,
+            ChainUpError::Crypto(e) => &mut e.context,
+            ChainUpError::Network(e) => &mut e.context,
+            ChainUpError::Validation(e) => &mut e.context,
+            ChainUpError::Signature(e) => &mut e.context,
+            ChainUpError::Authentication(e) => &mut e.context,
+            ChainUpError::RateLimit(e) => &mut e.context,
+            ChainUpError::Server(e) => &mut e.context,
+            ChainUpError::Request(e) => &mut e.context,
+            ChainUpError::Io(_) => return self,
+        };
+        match slot {
+            Some(existing) => existing.merge(context),
+            None => *slot = Some(Box::new(context)),
+        }
+        self
+    }
+
+    /// API path of the failed call
+    pub fn endpoint(&self) -> Option<&str> {
+        self.context().and_then(|c| c.endpoint.as_deref())
+    }
+
+    /// HTTP method of the failed call
+    pub fn method(&self) -> Option<&str> {
+        self.context().and_then(|c| c.method.as_deref())
+    }
+
+    /// HTTP status code of the failed call
+    pub fn http_status(&self) -> Option<u16> {
+        self.context().and_then(|c| c.http_status)
+    }
+
+    /// `request_id` of the failed call, for mutating endpoints
+    pub fn request_id(&self) -> Option<&str> {
+        self.context().and_then(|c| c.request_id.as_deref())
+    }
+
+    /// Truncated, redacted response body of the failed call
+    pub fn response_body(&self) -> Option<&str> {
+        self.context().and_then(|c| c.body.as_deref())
+    }
+
+    /// Time spent on the failed call
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.context().and_then(|c| c.elapsed)
+    }
+
+    /// Returns the ChainUp API code when this is an API error
+    pub fn api_code(&self) -> Option<ApiCode> {
+        match self {
+            ChainUpError::Api(e) => Some(e.api_code()),
+            _ => None,
+        }
+    }
+
+    /// Whether the failed call may succeed if sent again unchanged
+    ///
+    /// True for transient API codes, network failures, rate limits and
+    /// server errors, including API errors returned with HTTP 429 or 5xx.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ChainUpError::Api(e) => {
+                e.is_retryable()
+                    || matches!(self.http_status(), Some(status) if status == 429 || status >= 500)
+            }
+            ChainUpError::Network(_) | ChainUpError::RateLimit(_) | ChainUpError::Server(_) => true,
+            _ => false,
         }
     }
 }
@@ -87,6 +326,8 @@ impl ApiError {
 pub struct ConfigError {
     /// Error message
     pub message: String,
+    /// Request context, when the error happened during an API call
+    pub context: Option<Box<ErrorContext>>,
 }
 
 impl ConfigError {
@@ -94,6 +335,7 @@ impl ConfigError {
     pub fn new(message: impl Into<String>) -> Self {
         Self {
             message: message.into(),
+            context: None,
         }
     }
 }
@@ -104,6 +346,8 @@ impl ConfigError {
 pub struct CryptoError {
     /// Error message
     pub message: String,
+    /// Request context, when the error happened during an API call
+    pub context: Option<Box<ErrorContext>>,
 }
 
 impl CryptoError {
@@ -111,6 +355,7 @@ impl CryptoError {
     pub fn new(message: impl Into<String>) -> Self {
         Self {
             message: message.into(),
+            context: None,
         }
     }
 }
@@ -124,6 +369,8 @@ pub struct NetworkError {
     /// Underlying source error
     #[source]
     pub source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    /// Request context (endpoint, HTTP status, response body, ...)
+    pub context: Option<Box<ErrorContext>>,
 }
 
 impl NetworkError {
@@ -132,6 +379,7 @@ impl NetworkError {
         Self {
             message: message.into(),
             source: None,
+            context: None,
         }
     }
 
@@ -143,6 +391,7 @@ impl NetworkError {
         Self {
             message: message.into(),
             source: Some(Box::new(source)),
+            context: None,
         }
     }
 }
@@ -153,6 +402,8 @@ impl NetworkError {
 pub struct ValidationError {
     /// Error message
     pub message: String,
+    /// Request context, when the error happened during an API call
+    pub context: Option<Box<ErrorContext>>,
 }
 
 impl ValidationError {
@@ -160,6 +411,7 @@ impl ValidationError {
     pub fn new(message: impl Into<String>) -> Self {
         Self {
             message: message.into(),
+            context: None,
         }
     }
 }
@@ -170,6 +422,8 @@ impl ValidationError {
 pub struct SignatureError {
     /// Error message
     pub message: String,
+    /// Request context, when the error happened during an API call
+    pub context: Option<Box<ErrorContext>>,
 }
 
 impl SignatureError {
@@ -177,6 +431,7 @@ impl SignatureError {
     pub fn new(message: impl Into<String>) -> Self {
         Self {
             message: message.into(),
+            context: None,
         }
     }
 }
@@ -187,6 +442,8 @@ impl SignatureError {
 pub struct AuthenticationError {
     /// Error message
     pub message: String,
+    /// Request context, when the error happened during an API call
+    pub context: Option<Box<ErrorContext>>,
 }
 
 impl AuthenticationError {
@@ -194,6 +451,7 @@ impl AuthenticationError {
     pub fn new(message: impl Into<String>) -> Self {
         Self {
             message: message.into(),
+            context: None,
         }
     }
 }
@@ -204,6 +462,8 @@ impl AuthenticationError {
 pub struct RateLimitError {
     /// Error message
     pub message: String,
+    /// Request context, when the error happened during an API call
+    pub context: Option<Box<ErrorContext>>,
 }
 
 impl RateLimitError {
@@ -211,6 +471,47 @@ impl RateLimitError {
     pub fn new(message: impl Into<String>) -> Self {
         Self {
             message: message.into(),
+            context: None,
+        }
+    }
+}
+
+/// Server error (HTTP 5xx)
+#[derive(Error, Debug, Clone)]
+#[error("{message}")]
+pub struct ServerError {
+    /// Error message
+    pub message: String,
+    /// Request context, when the error happened during an API call
+    pub context: Option<Box<ErrorContext>>,
+}
+
+impl ServerError {
+    /// Creates a new server error
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            context: None,
+        }
+    }
+}
+
+/// Request error (HTTP 4xx)
+#[derive(Error, Debug, Clone)]
+#[error("{message}")]
+pub struct RequestError {
+    /// Error message
+    pub message: String,
+    /// Request context, when the error happened during an API call
+    pub context: Option<Box<ErrorContext>>,
+}
+
+impl RequestError {
+    /// Creates a new request error
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            context: None,
         }
     }
 }
@@ -268,14 +569,79 @@ impl From<RateLimitError> for ChainUpError {
     }
 }
 
+impl From<ServerError> for ChainUpError {
+    fn from(err: ServerError) -> Self {
+        ChainUpError::Server(err)
+    }
+}
+
+impl From<RequestError> for ChainUpError {
+    fn from(err: RequestError) -> Self {
+        ChainUpError::Request(err)
+    }
+}
+
 impl From<reqwest::Error> for ChainUpError {
     fn from(err: reqwest::Error) -> Self {
         ChainUpError::Network(NetworkError::with_source("HTTP request failed", err))
     }
 }
 
+impl From<std::io::Error> for ChainUpError {
+    fn from(err: std::io::Error) -> Self {
+        ChainUpError::Io(err)
+    }
+}
+
 impl From<serde_json::Error> for ChainUpError {
     fn from(err: serde_json::Error) -> Self {
         ChainUpError::Validation(ValidationError::new(format!("JSON parse error: {}", err)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_error_code_classification() {
+        let err = ApiError::new(110088, "duplicate request");
+        assert_eq!(err.api_code(), ApiCode::DuplicateRequest);
+        assert!(err.is_duplicate());
+        assert!(!err.is_retryable());
+
+        let err = ApiError::new(120403, "fee insufficient");
+        assert!(err.is_insufficient_funds());
+
+        let err = ApiError::new(424242, "new code");
+        assert_eq!(err.api_code(), ApiCode::Unknown(424242));
+        assert_eq!(err.api_code().code(), 424242);
+
+        let err: ChainUpError = ApiError::new(100001, "system error").into();
+        assert_eq!(err.api_code(), Some(ApiCode::SystemError));
+        assert!(err.is_retryable());
+    }
+
+    #[test]
+    fn test_error_context() {
+        let body = format!(r#"{{"code":"0","data":"{}"}}"#, "A".repeat(2000));
+        let err: ChainUpError = NetworkError::new("HTTP request failed with status 502").into();
+        let err = err
+            .with_context(ErrorContext::new().with_http_status(502).with_body(&body))
+            .with_context(
+                ErrorContext::new()
+                    .with_endpoint("POST", "/api/mpc/billing/withdraw")
+                    .with_request_id("req-1")
+                    .with_http_status(200),
+            );
+
+        assert_eq!(err.endpoint(), Some("/api/mpc/billing/withdraw"));
+        assert_eq!(err.method(), Some("POST"));
+        assert_eq!(err.request_id(), Some("req-1"));
+        // Fields set closer to the failure are kept
+        assert_eq!(err.http_status(), Some(502));
+        let recorded = err.response_body().unwrap();
+        assert!(recorded.contains("<redacted 2000 chars>"));
+        assert!(!recorded.contains("AAAA"));
+    }
+}
@@ -4,10 +4,15 @@
 //! with the ChainUp API.
 
 use reqwest::blocking::Client;
+use reqwest::StatusCode;
+use serde_json::Value;
 use std::collections::HashMap;
 use std::time::Duration;
 
-use crate::error::{NetworkError, Result};
+use crate::error::{
+    ApiError, AuthenticationError, ChainUpError, ErrorContext, NetworkError, RateLimitError,
+    RequestError, Result, ServerError,
+};
 
 /// Base HTTP client for API requests
 pub struct HttpClient {
@@ -78,11 +83,7 @@ impl HttpClient {
         }
 
         if !status.is_success() {
-            return Err(NetworkError::new(format!(
-                "HTTP request failed with status {}: {}",
-                status, body
-            ))
-            .into());
+            return Err(Self::status_error(status, &body));
         }
 
         Ok(body)
@@ -121,13 +122,85 @@ impl HttpClient {
         }
 
         if !status.is_success() {
-            return Err(NetworkError::new(format!(
-                "HTTP request failed with status {}: {}",
-                status, body
-            ))
-            .into());
+            return Err(Self::status_error(status, &body));
         }
 
         Ok(body)
     }
+
+    /// Builds the error for a non-success HTTP status
+    ///
+    /// A ChainUp JSON error body (`code`/`msg`) becomes an `ApiError` so the
+    /// ChainUp code is preserved; otherwise the status is classified:
+    /// 401/403 → `Authentication`, 429 → `RateLimit`, 5xx → `Server`,
+    /// other 4xx → `Request`. The body is kept in the error context
+    /// (truncated and redacted) rather than in the message.
+    fn status_error(status: StatusCode, body: &str) -> ChainUpError {
+        let error: ChainUpError = if let Some(api_error) = Self::parse_api_error(body) {
+            api_error.into()
+        } else {
+            let message = format!("HTTP request failed with status {}", status);
+            match status {
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                    AuthenticationError::new(message).into()
+                }
+                StatusCode::TOO_MANY_REQUESTS => RateLimitError::new(message).into(),
+                s if s.is_server_error() => ServerError::new(message).into(),
+                s if s.is_client_error() => RequestError::new(message).into(),
+                _ => NetworkError::new(message).into(),
+            }
+        };
+
+        error.with_context(
+            ErrorContext::new()
+                .with_http_status(status.as_u16())
+                .with_body(body),
+        )
+    }
+
+    /// Parses a ChainUp `{"code": ..., "msg": ...}` error body
+    fn parse_api_error(body: &str) -> Option<ApiError> {
+        let value: Value = serde_json::from_str(body).ok()?;
+        let code = match value.get("code")? {
+            Value::Number(n) => n.as_i64()? as i32,
+            Value::String(s) => s.parse().ok()?,
+            _ => return None,
+        };
+        let message = value
+            .get("msg")
+            .and_then(Value::as_str)
+            .unwrap_or("Unknown error");
+        Some(ApiError::new(code, message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_error_classification() {
+        let err = HttpClient::status_error(StatusCode::FORBIDDEN, "<html>denied</html>");
+        assert!(matches!(err, ChainUpError::Authentication(_)));
+        assert_eq!(err.http_status(), Some(403));
+
+        let err = HttpClient::status_error(StatusCode::TOO_MANY_REQUESTS, "");
+        assert!(matches!(err, ChainUpError::RateLimit(_)));
+        assert!(err.is_retryable());
+
+        let err = HttpClient::status_error(StatusCode::BAD_GATEWAY, "bad gateway");
+        assert!(matches!(err, ChainUpError::Server(_)));
+        assert!(err.is_retryable());
+
+        let err = HttpClient::status_error(StatusCode::NOT_FOUND, "");
+        assert!(matches!(err, ChainUpError::Request(_)));
+        assert!(!err.is_retryable());
+
+        let err = HttpClient::status_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            r#"{"code":"100001","msg":"system error"}"#,
+        );
+        assert_eq!(err.api_code(), Some(crate::enums::ApiCode::SystemError));
+        assert_eq!(err.http_status(), Some(503));
+    }
 }
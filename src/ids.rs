@@ -0,0 +1,209 @@
+//! Identifier newtypes
+//!
+//! MPC sub-wallet IDs, WaaS user IDs and record IDs are all integers on the
+//! wire, and request IDs and coin symbols are both strings. Wrapping them in
+//! distinct types keeps a WaaS `uid` from being passed where an MPC
+//! `sub_wallet_id` is expected.
+//!
+//! All types serialize exactly like the underlying value. Integer IDs also
+//! accept numeric strings when deserializing, as several endpoints return
+//! them quoted.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+macro_rules! int_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize)]
+        #[serde(transparent)]
+        pub struct $name(pub i64);
+
+        impl $name {
+            /// Returns the raw value
+            pub fn value(self) -> i64 {
+                self.0
+            }
+        }
+
+        impl From<i64> for $name {
+            fn from(value: i64) -> Self {
+                $name(value)
+            }
+        }
+
+        impl From<i32> for $name {
+            fn from(value: i32) -> Self {
+                $name(value.into())
+            }
+        }
+
+        impl From<$name> for i64 {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = std::num::ParseIntError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.trim().parse().map($name)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                #[derive(Deserialize)]
+                #[serde(untagged)]
+                enum StringOrInt {
+                    String(String),
+                    Int(i64),
+                }
+
+                match StringOrInt::deserialize(deserializer)? {
+                    StringOrInt::Int(value) => Ok($name(value)),
+                    StringOrInt::String(s) => s.parse().map_err(|_| {
+                        serde::de::Error::custom(format!(
+                            "invalid {}: {:?}",
+                            stringify!($name),
+                            s
+                        ))
+                    }),
+                }
+            }
+        }
+    };
+}
+
+macro_rules! string_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(pub String);
+
+        impl $name {
+            /// Returns the value as a string slice
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl std::ops::Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                $name(value)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                $name(value.to_string())
+            }
+        }
+
+        impl From<&String> for $name {
+            fn from(value: &String) -> Self {
+                $name(value.clone())
+            }
+        }
+
+        impl From<&$name> for $name {
+            fn from(value: &$name) -> Self {
+                value.clone()
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+    };
+}
+
+int_id!(
+    /// MPC sub-wallet ID
+    SubWalletId
+);
+
+int_id!(
+    /// WaaS user ID
+    Uid
+);
+
+int_id!(
+    /// ID of a record returned by the list and `sync_*` endpoints
+    /// (withdrawal, deposit, Web3 transaction, user address, ...)
+    ///
+    /// This is the ID that `max_id` cursors of the `sync_*` endpoints refer to.
+    RecordId
+);
+
+string_id!(
+    /// Merchant-generated unique request ID
+    RequestId
+);
+
+string_id!(
+    /// Coin symbol (e.g. "ETH", "USDTERC20")
+    Symbol
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_id_serde() {
+        let id: SubWalletId = serde_json::from_str("\"1000537\"").unwrap();
+        assert_eq!(id, SubWalletId(1000537));
+        assert_eq!(serde_json::to_string(&id).unwrap(), "1000537");
+
+        let request_id: RequestId = serde_json::from_str("\"req-1\"").unwrap();
+        assert_eq!(request_id, "req-1");
+        assert_eq!(serde_json::to_string(&request_id).unwrap(), "\"req-1\"");
+
+        assert!(serde_json::from_str::<Uid>("\"abc\"").is_err());
+    }
+}
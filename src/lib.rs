@@ -11,88 +11,85 @@
 //!
 //! ## WaaS Example
 //!
-//! ```rust,ignore
-//! use chainup_sdk::waas::{WaasClient, WaasConfig};
-//! use chainup_sdk::crypto::RsaCryptoProvider;
-//! use std::sync::Arc;
-//!
-//! let config = WaasConfig::new(
-//!     "https://api.chainup.com",
-//!     "your-app-id",
-//!     "your-rsa-private-key",
-//!     "chainup-rsa-public-key",
-//! );
-//!
-//! let crypto_provider = Arc::new(RsaCryptoProvider::new(
-//!     &config.rsa_private_key,
-//!     &config.rsa_public_key,
-//! )?);
+//! ```no_run
+//! use chainup_custody_sdk::WaasClient;
 //!
+//! # fn main() -> chainup_custody_sdk::Result<()> {
 //! let client = WaasClient::builder()
-//!     .config(config)
-//!     .crypto_provider(crypto_provider)
+//!     .set_app_id("your-app-id")
+//!     .set_private_key("your-rsa-private-key")
+//!     .set_public_key("chainup-rsa-public-key")
 //!     .build()?;
 //!
 //! // Get coin list
 //! let coins = client.coin().get_coin_list()?;
+//! # Ok(())
+//! # }
 //! ```
 //!
 //! ## MPC Example
 //!
-//! ```rust,ignore
-//! use chainup_sdk::mpc::{MpcClient, MpcConfig};
-//! use chainup_sdk::crypto::RsaCryptoProvider;
-//! use std::sync::Arc;
-//!
-//! let config = MpcConfig::new(
-//!     "https://mpc-api.chainup.com",
-//!     "your-app-id",
-//!     "your-rsa-private-key",
-//!     "chainup-rsa-public-key",
-//!     "your-sign-private-key",
-//! );
-//!
-//! let crypto_provider = Arc::new(RsaCryptoProvider::new(
-//!     &config.rsa_private_key,
-//!     &config.rsa_public_key,
-//! )?);
+//! ```no_run
+//! use chainup_custody_sdk::mpc_types::GetWalletListParams;
+//! use chainup_custody_sdk::MpcClient;
 //!
+//! # fn main() -> chainup_custody_sdk::Result<()> {
 //! let client = MpcClient::builder()
-//!     .config(config)
-//!     .crypto_provider(crypto_provider)
+//!     .set_app_id("your-app-id")
+//!     .set_rsa_private_key("your-rsa-private-key")
+//!     .set_waas_public_key("chainup-rsa-public-key")
+//!     .set_sign_private_key("your-sign-private-key")
 //!     .build()?;
 //!
 //! // Get wallet list
-//! let wallets = client.wallet().get_wallet_list(
-//!     chainup_sdk::mpc::api::types::GetWalletListParams::new(100, 0)
-//! )?;
+//! let wallets = client.wallet().get_wallet_list(GetWalletListParams::new(100, 0))?;
+//! # Ok(())
+//! # }
 //! ```
 
 #![warn(missing_docs)]
 #![warn(rust_2018_idioms)]
 
+pub mod checkpoint;
 pub mod crypto;
+pub mod decode;
+pub mod dedup;
+pub mod drift;
+pub mod endpoint;
 pub mod enums;
 pub mod error;
 pub mod http_client;
+pub mod ids;
+pub mod lookup;
 pub mod mpc;
+pub mod notify_validation;
+pub mod pagination;
+pub mod reconcile;
+pub mod timestamp;
 pub mod utils;
 pub mod waas;
+#[cfg(feature = "webhook")]
+pub mod webhook;
 
 // Re-export Decimal type for convenience
 pub use rust_decimal::Decimal;
 
 // Re-export commonly used types at crate root
 pub use crypto::{CryptoProvider, RsaCryptoProvider};
+pub use decode::{DecodeMode, UnknownFields};
+pub use endpoint::Endpoint;
 pub use enums::*;
 pub use error::{ChainUpError, Result};
+pub use ids::{RecordId, RequestId, SubWalletId, Symbol, Uid};
+pub use lookup::{LookupOptions, LookupResult};
+pub use timestamp::Timestamp;
 
 // Re-export WaaS types
 pub use waas::{WaasClient, WaasClientBuilder, WaasConfig};
 pub use waas::api::types as waas_types;
 
 // Re-export MPC types
-pub use mpc::{MpcClient, MpcClientBuilder, MpcConfig, MpcSignUtil};
+pub use mpc::{MpcClient, MpcClientBuilder, MpcConfig, MpcSignUtil, SignableRequest};
 pub use mpc::api::types as mpc_types;
 
 /// SDK version
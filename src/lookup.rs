@@ -0,0 +1,223 @@
+//! Bulk lookups by ID
+//!
+//! The record lookup endpoints (`get_withdraw_records`, `withdraw_list`,
+//! `deposit_list`, ...) accept at most [`MAX_LOOKUP_IDS`] IDs per request.
+//! The SDK splits longer ID lists into batches, runs them one after another
+//! or with bounded concurrency ([`LookupOptions`]), merges the records and
+//! reports the IDs nothing came back for ([`LookupResult::missing`]).
+//!
+//! # Example
+//! ```ignore
+//! let result = withdraw_api.lookup_withdraw_records(
+//!     &request_ids,
+//!     LookupOptions::new().with_concurrency(4),
+//! )?;
+//! for request_id in &result.missing {
+//!     println!("Unknown withdrawal: {}", request_id);
+//! }
+//! ```
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::error::Result;
+
+/// Maximum number of IDs the lookup endpoints accept per request
+pub const MAX_LOOKUP_IDS: usize = 100;
+
+/// How a bulk lookup is split into requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LookupOptions {
+    chunk_size: usize,
+    concurrency: usize,
+}
+
+impl Default for LookupOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: MAX_LOOKUP_IDS,
+            concurrency: 1,
+        }
+    }
+}
+
+impl LookupOptions {
+    /// Batches of [`MAX_LOOKUP_IDS`], one request at a time
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of IDs per request, capped at [`MAX_LOOKUP_IDS`]
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.clamp(1, MAX_LOOKUP_IDS);
+        self
+    }
+
+    /// Sets how many requests may run at the same time (at least 1)
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Number of IDs per request
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Maximum number of requests in flight
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+}
+
+/// Records found by a bulk lookup and the IDs that were not found
+#[derive(Debug, Clone)]
+pub struct LookupResult<T> {
+    /// Records returned, in the order of the batches
+    pub found: Vec<T>,
+    /// Requested IDs no record came back for, in request order
+    pub missing: Vec<String>,
+}
+
+impl<T> LookupResult<T> {
+    /// Whether every requested ID was found
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// Looks up `ids` in batches with `fetch`
+///
+/// Duplicate IDs are requested once. `key` returns the ID a record answers,
+/// in the same string form as the requested ID. The first failed batch (in
+/// batch order) fails the whole lookup.
+pub(crate) fn lookup<K, T>(
+    ids: &[K],
+    options: LookupOptions,
+    fetch: impl Fn(&[K]) -> Result<Vec<T>> + Sync,
+    key: impl Fn(&T) -> Option<String>,
+) -> Result<LookupResult<T>>
+where
+    K: Clone + fmt::Display + Sync,
+    T: Send,
+{
+    let mut seen = HashSet::new();
+    let ids: Vec<K> = ids
+        .iter()
+        .filter(|id| seen.insert(id.to_string()))
+        .cloned()
+        .collect();
+    let chunks: Vec<&[K]> = ids.chunks(options.chunk_size).collect();
+
+    let mut found = Vec::new();
+    if options.concurrency <= 1 || chunks.len() <= 1 {
+        for chunk in chunks {
+            found.extend(fetch(chunk)?);
+        }
+    } else {
+        for page in fetch_concurrently(&chunks, options.concurrency, &fetch) {
+            found.extend(page?);
+        }
+    }
+
+    let returned: HashSet<String> = found.iter().filter_map(&key).collect();
+    let missing = ids
+        .iter()
+        .map(ToString::to_string)
+        .filter(|id| !returned.contains(id))
+        .collect();
+    Ok(LookupResult { found, missing })
+}
+
+/// Runs `fetch` over `chunks` on up to `concurrency` threads
+///
+/// Results come back in chunk order. After a failure no new chunks are
+/// started; chunks that were never run are left out, which is fine as the
+/// caller stops at the first error.
+fn fetch_concurrently<K, T>(
+    chunks: &[&[K]],
+    concurrency: usize,
+    fetch: &(impl Fn(&[K]) -> Result<Vec<T>> + Sync),
+) -> Vec<Result<Vec<T>>>
+where
+    K: Sync,
+    T: Send,
+{
+    let next = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let results: Mutex<Vec<Option<Result<Vec<T>>>>> =
+        Mutex::new(chunks.iter().map(|_| None).collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency.min(chunks.len()) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                if index >= chunks.len() || failed.load(Ordering::SeqCst) {
+                    break;
+                }
+                let result = fetch(chunks[index]);
+                if result.is_err() {
+                    failed.store(true, Ordering::SeqCst);
+                }
+                results.lock().unwrap_or_else(|e| e.into_inner())[index] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::NetworkError;
+
+    #[test]
+    fn test_lookup_chunks_and_reports_missing() {
+        let ids: Vec<String> = (1..=250).map(|i| i.to_string()).collect();
+        let mut ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+        ids.push("7");
+
+        for concurrency in [1, 3] {
+            let batches = Mutex::new(Vec::new());
+            let result = lookup(
+                &ids,
+                LookupOptions::new().with_concurrency(concurrency),
+                |chunk| {
+                    batches.lock().unwrap().push(chunk.len());
+                    // Every tenth ID is unknown
+                    Ok(chunk
+                        .iter()
+                        .filter(|id| !id.ends_with('0'))
+                        .map(|id| id.to_string())
+                        .collect())
+                },
+                |record: &String| Some(record.clone()),
+            )
+            .unwrap();
+
+            let mut batches = batches.into_inner().unwrap();
+            batches.sort();
+            assert_eq!(batches, vec![50, 100, 100]);
+            assert_eq!(result.found.len(), 225);
+            assert_eq!(result.found[0], "1");
+            assert_eq!(result.missing.len(), 25);
+            assert_eq!(result.missing[..2], ["10", "20"]);
+        }
+
+        let err = lookup(
+            &ids,
+            LookupOptions::new().with_chunk_size(10).with_concurrency(2),
+            |_| Err::<Vec<String>, _>(NetworkError::new("down").into()),
+            |record: &String| Some(record.clone()),
+        );
+        assert!(err.is_err());
+    }
+}
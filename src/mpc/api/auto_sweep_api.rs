@@ -9,10 +9,19 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::checkpoint::{CheckpointStore, CheckpointedSync};
 use crate::crypto::CryptoProvider;
+use crate::decode::impl_unknown_fields;
 use crate::error::{Result, ValidationError};
+use crate::ids::{RecordId, SubWalletId, Symbol};
 use crate::mpc::api::base_api::MpcBaseApi;
 use crate::mpc::config::MpcConfig;
+use crate::pagination::SyncIter;
+#[cfg(feature = "async")]
+use crate::pagination::SyncStream;
+use crate::timestamp::NumericTime;
+use crate::utils::amount::format_amount;
+use crate::utils::serde_helpers::deserialize_optional_numeric_time;
 
 // ============================================================================
 // Request parameter types
@@ -22,12 +31,12 @@ use crate::mpc::config::MpcConfig;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AutoCollectSubWalletsParams {
     /// Unique identifier for the coin (e.g., "USDTERC20")
-    pub symbol: String,
+    pub symbol: Symbol,
 }
 
 impl AutoCollectSubWalletsParams {
     /// Creates new parameters
-    pub fn new(symbol: impl Into<String>) -> Self {
+    pub fn new(symbol: impl Into<Symbol>) -> Self {
         Self {
             symbol: symbol.into(),
         }
@@ -35,7 +44,7 @@ impl AutoCollectSubWalletsParams {
 
     fn to_map(&self) -> HashMap<String, Value> {
         let mut map = HashMap::new();
-        map.insert("symbol".to_string(), Value::String(self.symbol.clone()));
+        map.insert("symbol".to_string(), Value::String(self.symbol.to_string()));
         map
     }
 }
@@ -44,7 +53,7 @@ impl AutoCollectSubWalletsParams {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SetAutoCollectSymbolParams {
     /// Unique identifier for the coin (e.g., "USDTERC20")
-    pub symbol: String,
+    pub symbol: Symbol,
     /// Minimum amount for auto-sweep (up to 6 decimal places)
     pub collect_min: String,
     /// Maximum miner fee amount for auto-sweep refueling (up to 6 decimal places)
@@ -54,7 +63,7 @@ pub struct SetAutoCollectSymbolParams {
 impl SetAutoCollectSymbolParams {
     /// Creates new parameters
     pub fn new(
-        symbol: impl Into<String>,
+        symbol: impl Into<Symbol>,
         collect_min: impl Into<String>,
         fueling_limit: impl Into<String>,
     ) -> Self {
@@ -65,9 +74,22 @@ impl SetAutoCollectSymbolParams {
         }
     }
 
+    /// Creates new parameters from `Decimal` amounts
+    pub fn from_decimal(
+        symbol: impl Into<Symbol>,
+        collect_min: Decimal,
+        fueling_limit: Decimal,
+    ) -> Self {
+        Self::new(
+            symbol,
+            format_amount(collect_min),
+            format_amount(fueling_limit),
+        )
+    }
+
     fn to_map(&self) -> HashMap<String, Value> {
         let mut map = HashMap::new();
-        map.insert("symbol".to_string(), Value::String(self.symbol.clone()));
+        map.insert("symbol".to_string(), Value::String(self.symbol.to_string()));
         map.insert(
             "collect_min".to_string(),
             Value::String(self.collect_min.clone()),
@@ -93,20 +115,25 @@ pub struct AutoCollectResult {
     /// Collect sub wallet ID (for collection destination)
     #[serde(default)]
     pub collect_sub_wallet_id: Option<i64>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(AutoCollectResult);
+
 /// Auto collection record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AutoCollectRecord {
     /// Record ID
     #[serde(default)]
-    pub id: i64,
+    pub id: RecordId,
     /// Sub-wallet ID
     #[serde(default)]
-    pub sub_wallet_id: i64,
+    pub sub_wallet_id: SubWalletId,
     /// Coin symbol
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Collection amount
     #[serde(default, with = "rust_decimal::serde::str_option")]
     pub amount: Option<Decimal>,
@@ -153,13 +180,18 @@ pub struct AutoCollectRecord {
     #[serde(default)]
     pub trans_type: Option<i64>,
     /// Created timestamp (milliseconds)
-    #[serde(default)]
-    pub created_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub created_at: Option<NumericTime>,
     /// Updated timestamp (milliseconds)
-    #[serde(default)]
-    pub updated_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub updated_at: Option<NumericTime>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(AutoCollectRecord);
+
 // ============================================================================
 // Auto Sweep API Implementation
 // ============================================================================
@@ -204,10 +236,8 @@ impl AutoSweepApi {
         }
 
         let data = params.to_map();
-        let response = self
-            .base
-            .get("/api/mpc/auto_collect/sub_wallets", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("GET", "/api/mpc/auto_collect/sub_wallets", Some(&data))
     }
 
     /// Configures auto-sweep for a coin
@@ -240,10 +270,9 @@ impl AutoSweepApi {
         }
 
         let data = params.to_map();
-        let response = self
-            .base
-            .post("/api/mpc/auto_collect/symbol/set", Some(&data))?;
-        let _: serde_json::Value = self.base.validate_response(response)?;
+        let _: serde_json::Value =
+            self.base
+                .request("POST", "/api/mpc/auto_collect/symbol/set", Some(&data))?;
         Ok(())
     }
 
@@ -265,9 +294,53 @@ impl AutoSweepApi {
         let mut data = HashMap::new();
         data.insert("max_id".to_string(), Value::Number(max_id.into()));
 
-        let response = self
-            .base
-            .get("/api/mpc/billing/sync_auto_collect_list", Some(&data))?;
-        self.base.validate_response(response)
+        self.base.request(
+            "GET",
+            "/api/mpc/billing/sync_auto_collect_list",
+            Some(&data),
+        )
+    }
+
+    /// Iterates over all auto-collect records after `max_id`, fetching pages as needed
+    ///
+    /// # Example
+    /// ```ignore
+    /// for record in auto_sweep_api.sync_auto_collect_records_iter(0).with_page_size(100) {
+    ///     println!("Record: {:?}", record?);
+    /// }
+    /// ```
+    pub fn sync_auto_collect_records_iter(&self, max_id: i64) -> SyncIter<'_, AutoCollectRecord> {
+        SyncIter::new(max_id, move |max_id| self.sync_auto_collect_records(max_id))
+    }
+
+    /// Iterates over auto-collect records page by page, resuming from a stored checkpoint
+    ///
+    /// The checkpoint is keyed by the endpoint path and only advances when
+    /// a page is acknowledged; see [`CheckpointedSync`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut sync = auto_sweep_api.sync_auto_collect_records_checkpointed(&store)?;
+    /// while let Some(page) = sync.next_page() {
+    ///     save_to_db(&page?)?;
+    ///     sync.ack()?;
+    /// }
+    /// ```
+    pub fn sync_auto_collect_records_checkpointed<'a>(
+        &'a self,
+        store: &'a dyn CheckpointStore,
+    ) -> Result<CheckpointedSync<'a, AutoCollectRecord>> {
+        CheckpointedSync::new(store, "/api/mpc/billing/sync_auto_collect_list", |max_id| {
+            self.sync_auto_collect_records_iter(max_id)
+        })
+    }
+
+    /// Async stream over all auto-collect records after `max_id`
+    ///
+    /// See [`SyncStream`] for how pages are fetched.
+    #[cfg(feature = "async")]
+    pub fn sync_auto_collect_records_stream(&self, max_id: i64) -> SyncStream<AutoCollectRecord> {
+        let api = Self::new(self.base.config.clone(), self.base.crypto_provider.clone());
+        SyncStream::new(max_id, move |max_id| api.sync_auto_collect_records(max_id))
     }
 }
@@ -4,13 +4,15 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use serde::de::DeserializeOwned;
 use serde_json::Value;
 
-use crate::crypto::CryptoProvider;
-use crate::error::{ApiError, Result};
+use crate::crypto::{self, response_code, CryptoProvider};
+use crate::decode::{check_unknown_fields, UnknownFields};
+use crate::endpoint::{normalize_method, params_to_map, Endpoint};
+use crate::error::{ApiError, ErrorContext, Result};
 use crate::http_client::HttpClient;
 use crate::mpc::config::MpcConfig;
 
@@ -67,6 +69,78 @@ impl MpcBaseApi {
         method: &str,
         path: &str,
         data: Option<&HashMap<String, Value>>,
+    ) -> Result<Value> {
+        self.execute_request_with_context(method, path, data)
+            .map(|(response, _)| response)
+    }
+
+    /// Executes a request and validates its response
+    ///
+    /// Errors from any stage carry an [`ErrorContext`] with the endpoint,
+    /// method, request_id, elapsed time and (for API errors) the response body.
+    ///
+    /// Unknown response fields are reported according to the configured
+    /// [`DecodeMode`](crate::decode::DecodeMode).
+    pub(crate) fn request<T: DeserializeOwned + UnknownFields>(
+        &self,
+        method: &str,
+        path: &str,
+        data: Option<&HashMap<String, Value>>,
+    ) -> Result<T> {
+        let (response, context) = self.execute_request_with_context(method, path, data)?;
+        self.parse_response(&response)
+            .and_then(|parsed: T| {
+                check_unknown_fields(self.config.decode_mode, path, &parsed)?;
+                Ok(parsed)
+            })
+            .map_err(|e| e.with_context(context.with_body(&response.to_string())))
+    }
+
+    /// Like [`Self::request`], for response types that cannot report
+    /// unknown fields (user-defined [`Endpoint`]s and raw calls)
+    fn request_unchecked<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        path: &str,
+        data: Option<&HashMap<String, Value>>,
+    ) -> Result<T> {
+        let (response, context) = self.execute_request_with_context(method, path, data)?;
+        self.parse_response(&response)
+            .map_err(|e| e.with_context(context.with_body(&response.to_string())))
+    }
+
+    /// Executes a request, returning the response with its context
+    ///
+    /// Errors are returned with the context already attached.
+    fn execute_request_with_context(
+        &self,
+        method: &str,
+        path: &str,
+        data: Option<&HashMap<String, Value>>,
+    ) -> Result<(Value, ErrorContext)> {
+        let started = Instant::now();
+        let mut context = ErrorContext::new().with_endpoint(method, path);
+        if let Some(request_id) = data
+            .and_then(|d| d.get("request_id"))
+            .and_then(Value::as_str)
+        {
+            context = context.with_request_id(request_id);
+        }
+
+        let result = self.send_request(method, path, data);
+        let context = context.with_elapsed(started.elapsed());
+        match result {
+            Ok(response) => Ok((response, context)),
+            Err(e) => Err(e.with_context(context)),
+        }
+    }
+
+    /// Encrypts, sends and decrypts a single request
+    fn send_request(
+        &self,
+        method: &str,
+        path: &str,
+        data: Option<&HashMap<String, Value>>,
     ) -> Result<Value> {
         // Step 1: Build request args JSON
         let raw_json = self.build_request_args_json(data);
@@ -103,32 +177,22 @@ impl MpcBaseApi {
 
         let response: Value = serde_json::from_str(&response_body)?;
 
-        // Step 4: Check if response has encrypted data field and decrypt
-        // MPC API returns: {"data": "encrypted_string"}
-        // After decryption, the content is the actual API response: {"code":"0","data":[...],"msg":"success"}
-        if let Some(Value::String(encrypted_response_data)) = response.get("data") {
-            // Decrypt the data field
-            match self
-                .crypto_provider
-                .decrypt_with_public_key(encrypted_response_data)
-            {
-                Ok(decrypted) => {
-                    if self.config.debug {
-                        println!("[MPC Decrypted data]: {}", decrypted);
-                    }
-                    // The decrypted content IS the actual API response with code/msg/data
-                    let decrypted_response: Value = serde_json::from_str(&decrypted)?;
-                    return Ok(decrypted_response);
-                }
-                Err(e) => {
-                    if self.config.debug {
-                        println!("[MPC Decrypt warning]: {}", e);
-                    }
-                    // If decryption fails, return response as-is (might be error response)
-                }
-            }
-        }
+        // Step 4: Decrypt the response envelope
+        self.decrypt_response(path, response)
+    }
 
+    /// Decrypts an MPC response envelope, see [`crypto::decrypt_response`]
+    fn decrypt_response(&self, path: &str, response: Value) -> Result<Value> {
+        let response = crypto::decrypt_response(
+            self.crypto_provider.as_ref(),
+            path,
+            response,
+            "waas_public_key",
+            &self.config.app_id,
+        )?;
+        if self.config.debug {
+            println!("[MPC Decrypted data]: {}", response);
+        }
         Ok(response)
     }
 
@@ -156,23 +220,53 @@ impl MpcBaseApi {
         self.execute_request("GET", path, data)
     }
 
+    /// Calls an [`Endpoint`] through the encrypted request pipeline
+    ///
+    /// # Arguments
+    /// * `params` - Endpoint parameters, serialized as a JSON object
+    ///
+    /// # Returns
+    /// The validated response `data`, parsed as `E::Response`
+    pub fn call<E: Endpoint>(&self, params: &E::Params) -> Result<E::Response> {
+        let method = normalize_method(E::METHOD)?;
+        let data = params_to_map(serde_json::to_value(params)?)?;
+        self.request_unchecked(method, E::PATH, data.as_ref())
+    }
+
+    /// Calls an arbitrary API path with raw JSON parameters
+    ///
+    /// The request is encrypted and the response decrypted and validated
+    /// exactly like the wrapped endpoints.
+    ///
+    /// # Arguments
+    /// * `path` - API path
+    /// * `method` - `"POST"` or `"GET"`
+    /// * `params` - JSON object of request parameters (`null` for none)
+    ///
+    /// # Returns
+    /// The validated response `data` field (`null` if absent)
+    pub fn raw_request(&self, path: &str, method: &str, params: Value) -> Result<Value> {
+        let method = normalize_method(method)?;
+        let data = params_to_map(params)?;
+        self.request_unchecked(method, path, data.as_ref())
+    }
+
     /// Validates API response and extracts data
     ///
+    /// The response has already been decrypted in execute_request.
+    ///
     /// # Arguments
-    /// * `response` - API response JSON
+    /// * `response` - API response JSON (already decrypted)
     ///
     /// # Returns
-    /// Decrypted and parsed response data
+    /// Parsed response data
     pub fn validate_response<T: DeserializeOwned>(&self, response: Value) -> Result<T> {
+        self.parse_response(&response)
+    }
+
+    fn parse_response<T: DeserializeOwned>(&self, response: &Value) -> Result<T> {
         // Check response code - MPC API uses string code
-        let code = response
-            .get("code")
-            .and_then(|v| {
-                v.as_str()
-                    .and_then(|s| s.parse::<i32>().ok())
-                    .or_else(|| v.as_i64().map(|i| i as i32))
-            })
-            .unwrap_or(-1);
+        let code = response_code(response).unwrap_or(-1);
 
         if code != 0 {
             let message = response
@@ -184,20 +278,11 @@ impl MpcBaseApi {
             return Err(ApiError::new(code, message).into());
         }
 
-        // Get and decrypt data field
+        // Get data field (already decrypted in execute_request)
         let data = response.get("data");
 
         match data {
-            Some(Value::String(encrypted_data)) => {
-                // Decrypt the data
-                let decrypted = self
-                    .crypto_provider
-                    .decrypt_with_public_key(encrypted_data)?;
-                let parsed: T = serde_json::from_str(&decrypted)?;
-                Ok(parsed)
-            }
             Some(value) => {
-                // Data is not encrypted, parse directly
                 let parsed: T = serde_json::from_value(value.clone())?;
                 Ok(parsed)
             }
@@ -213,14 +298,7 @@ impl MpcBaseApi {
     /// Validates API response and returns raw Value
     pub fn validate_response_raw(&self, response: Value) -> Result<Value> {
         // Check response code
-        let code = response
-            .get("code")
-            .and_then(|v| {
-                v.as_str()
-                    .and_then(|s| s.parse::<i32>().ok())
-                    .or_else(|| v.as_i64().map(|i| i as i32))
-            })
-            .unwrap_or(-1);
+        let code = response_code(&response).unwrap_or(-1);
 
         if code != 0 {
             let message = response
@@ -232,20 +310,62 @@ impl MpcBaseApi {
             return Err(ApiError::new(code, message).into());
         }
 
-        // Get and decrypt data field
+        // Get data field (already decrypted in execute_request)
         let data = response.get("data");
 
         match data {
-            Some(Value::String(encrypted_data)) => {
-                // Decrypt the data
-                let decrypted = self
-                    .crypto_provider
-                    .decrypt_with_public_key(encrypted_data)?;
-                let parsed: Value = serde_json::from_str(&decrypted)?;
-                Ok(parsed)
-            }
             Some(value) => Ok(value.clone()),
             None => Ok(Value::Null),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::keygen::RsaKeyPair;
+    use crate::crypto::RsaCryptoProvider;
+    use crate::error::ChainUpError;
+
+    fn base_api(chainup: &RsaKeyPair, own: &RsaKeyPair) -> MpcBaseApi {
+        let config = MpcConfig::new("app", own.private_key_pem(), chainup.public_key_pem());
+        let provider =
+            RsaCryptoProvider::with_keys(own.private_key_pem(), chainup.public_key_pem()).unwrap();
+        MpcBaseApi::new(config, Arc::new(provider))
+    }
+
+    #[test]
+    fn test_decrypt_response() {
+        let chainup = RsaKeyPair::generate_with_bits(1024).unwrap();
+        let own = RsaKeyPair::generate_with_bits(1024).unwrap();
+        let chainup_provider =
+            RsaCryptoProvider::with_keys(chainup.private_key_pem(), own.public_key_pem()).unwrap();
+        let encrypted = chainup_provider
+            .encrypt_with_private_key(r#"{"code":"0","msg":"success","data":"ok"}"#)
+            .unwrap();
+
+        let api = base_api(&chainup, &own);
+        let response = api
+            .decrypt_response("/x", serde_json::json!({ "data": encrypted }))
+            .unwrap();
+        let data: String = api.validate_response(response).unwrap();
+        assert_eq!(data, "ok");
+
+        // Error envelopes are passed through for validate_response
+        let envelope = serde_json::json!({"code": "100004", "msg": "sign error"});
+        let response = api.decrypt_response("/x", envelope.clone()).unwrap();
+        assert_eq!(response, envelope);
+
+        // A success code does not excuse undecryptable data
+        let envelope = serde_json::json!({"code": "0", "data": "not-ciphertext"});
+        let err = api.decrypt_response("/x", envelope).unwrap_err();
+        assert!(matches!(err, ChainUpError::Crypto(_)));
+
+        // Wrong ChainUp public key surfaces as a crypto error
+        let api = base_api(&own, &own);
+        let err = api
+            .decrypt_response("/x", serde_json::json!({ "data": encrypted }))
+            .unwrap_err();
+        assert!(matches!(err, ChainUpError::Crypto(_)));
+    }
+}
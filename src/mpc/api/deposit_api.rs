@@ -10,11 +10,22 @@ use serde_json::Value;
 
 use rust_decimal::Decimal;
 
+use crate::checkpoint::{CheckpointStore, CheckpointedSync};
 use crate::crypto::CryptoProvider;
+use crate::decode::impl_unknown_fields;
+use crate::enums::MpcDepositStatus;
 use crate::error::{Result, ValidationError};
+use crate::ids::{RecordId, SubWalletId, Symbol};
+use crate::lookup::{lookup, LookupOptions, LookupResult};
 use crate::mpc::api::base_api::MpcBaseApi;
 use crate::mpc::config::MpcConfig;
-use crate::utils::serde_helpers::deserialize_optional_i64;
+use crate::pagination::SyncIter;
+#[cfg(feature = "async")]
+use crate::pagination::SyncStream;
+use crate::timestamp::NumericTime;
+use crate::utils::serde_helpers::{
+    deserialize_optional_i64, deserialize_optional_numeric_time, deserialize_optional_status,
+};
 
 // ============================================================================
 // Request parameter types
@@ -23,14 +34,16 @@ use crate::utils::serde_helpers::deserialize_optional_i64;
 /// Parameters for getting deposit records
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetDepositRecordsParams {
-    /// Receiving IDs (list of integers, up to 100)
-    pub ids: Vec<i64>,
+    /// Receiving IDs (sent in batches of up to 100)
+    pub ids: Vec<RecordId>,
 }
 
 impl GetDepositRecordsParams {
     /// Creates new parameters
-    pub fn new(ids: Vec<i64>) -> Self {
-        Self { ids }
+    pub fn new(ids: impl IntoIterator<Item = impl Into<RecordId>>) -> Self {
+        Self {
+            ids: ids.into_iter().map(Into::into).collect(),
+        }
     }
 
     fn to_map(&self) -> HashMap<String, Value> {
@@ -55,13 +68,13 @@ impl GetDepositRecordsParams {
 pub struct DepositRecord {
     /// Record ID
     #[serde(default)]
-    pub id: Option<i64>,
+    pub id: Option<RecordId>,
     /// Sub-wallet ID
     #[serde(default)]
-    pub sub_wallet_id: Option<i64>,
+    pub sub_wallet_id: Option<SubWalletId>,
     /// Cryptocurrency symbol
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Base chain symbol
     #[serde(default)]
     pub base_symbol: Option<String>,
@@ -90,8 +103,8 @@ pub struct DepositRecord {
     #[serde(default)]
     pub contract_address: Option<String>,
     /// Status
-    #[serde(default)]
-    pub status: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_optional_status")]
+    pub status: Option<MpcDepositStatus>,
     /// Deposit type (1: normal token deposit, 2: web3 transaction deposit, 10: collection, 11: collection miner fee)
     #[serde(default)]
     pub deposit_type: Option<i32>,
@@ -105,21 +118,31 @@ pub struct DepositRecord {
     #[serde(default)]
     pub remark: Option<String>,
     /// Creation time (timestamp in milliseconds)
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub created_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub created_at: Option<NumericTime>,
     /// Update time (timestamp in milliseconds)
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub updated_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub updated_at: Option<NumericTime>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(DepositRecord);
+
 /// Response for sync deposit records
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncDepositRecordsResponse {
     /// List of deposit records
     #[serde(default)]
     pub list: Vec<DepositRecord>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(SyncDepositRecordsResponse, list);
+
 // ============================================================================
 // Deposit API Implementation
 // ============================================================================
@@ -162,11 +185,39 @@ impl DepositApi {
             .into());
         }
 
-        let data = params.to_map();
-        let response = self
-            .base
-            .get("/api/mpc/billing/deposit_list", Some(&data))?;
-        self.base.validate_response(response)
+        Ok(self
+            .lookup_deposit_records(params, LookupOptions::default())?
+            .found)
+    }
+
+    /// Gets receiving records for any number of IDs
+    ///
+    /// The IDs are sent in batches of up to 100 (see [`LookupOptions`]); the
+    /// result also lists the IDs no record was returned for.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = deposit_api.lookup_deposit_records(
+    ///     GetDepositRecordsParams::new(ids),
+    ///     LookupOptions::new().with_concurrency(4),
+    /// )?;
+    /// println!("{} found, missing: {:?}", result.found.len(), result.missing);
+    /// ```
+    pub fn lookup_deposit_records(
+        &self,
+        params: GetDepositRecordsParams,
+        options: LookupOptions,
+    ) -> Result<LookupResult<DepositRecord>> {
+        lookup(
+            &params.ids,
+            options,
+            |chunk| {
+                let data = GetDepositRecordsParams::new(chunk.iter().copied()).to_map();
+                self.base
+                    .request("GET", "/api/mpc/billing/deposit_list", Some(&data))
+            },
+            |record: &DepositRecord| record.id.map(|id| id.to_string()),
+        )
     }
 
     /// Synchronizes transfer (deposit) records
@@ -188,9 +239,50 @@ impl DepositApi {
         let mut data = HashMap::new();
         data.insert("max_id".to_string(), Value::Number(max_id.into()));
 
-        let response = self
-            .base
-            .get("/api/mpc/billing/sync_deposit_list", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("GET", "/api/mpc/billing/sync_deposit_list", Some(&data))
+    }
+
+    /// Iterates over all deposit records after `max_id`, fetching pages as needed
+    ///
+    /// # Example
+    /// ```ignore
+    /// for record in deposit_api.sync_deposit_records_iter(0).with_page_size(100) {
+    ///     println!("Record: {:?}", record?);
+    /// }
+    /// ```
+    pub fn sync_deposit_records_iter(&self, max_id: i64) -> SyncIter<'_, DepositRecord> {
+        SyncIter::new(max_id, move |max_id| self.sync_deposit_records(max_id))
+    }
+
+    /// Iterates over deposit records page by page, resuming from a stored checkpoint
+    ///
+    /// The checkpoint is keyed by the endpoint path and only advances when
+    /// a page is acknowledged; see [`CheckpointedSync`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut sync = deposit_api.sync_deposit_records_checkpointed(&store)?;
+    /// while let Some(page) = sync.next_page() {
+    ///     save_to_db(&page?)?;
+    ///     sync.ack()?;
+    /// }
+    /// ```
+    pub fn sync_deposit_records_checkpointed<'a>(
+        &'a self,
+        store: &'a dyn CheckpointStore,
+    ) -> Result<CheckpointedSync<'a, DepositRecord>> {
+        CheckpointedSync::new(store, "/api/mpc/billing/sync_deposit_list", |max_id| {
+            self.sync_deposit_records_iter(max_id)
+        })
+    }
+
+    /// Async stream over all deposit records after `max_id`
+    ///
+    /// See [`SyncStream`] for how pages are fetched.
+    #[cfg(feature = "async")]
+    pub fn sync_deposit_records_stream(&self, max_id: i64) -> SyncStream<DepositRecord> {
+        let api = Self::new(self.base.config.clone(), self.base.crypto_provider.clone());
+        SyncStream::new(max_id, move |max_id| api.sync_deposit_records(max_id))
     }
 }
@@ -31,13 +31,17 @@ pub mod types {
     pub use super::deposit_api::{
         DepositRecord, GetDepositRecordsParams, SyncDepositRecordsResponse,
     };
-    pub use super::notify_api::MpcNotifyData;
+    pub use super::notify_api::{
+        MpcAutoCollectNotification, MpcDepositNotification, MpcNotification, MpcNotifyData,
+        MpcTronDelegateNotification, MpcWeb3Notification, MpcWithdrawNotification,
+    };
     pub use super::tron_resource_api::{
-        CreateTronDelegateParams, TronDelegateResult, TronResourceRecord,
+        CreateTronDelegateParams, SyncBuyResourceRecordsResponse, TronDelegateResult,
+        TronResourceRecord,
     };
     pub use super::wallet_api::{
         ChangeWalletShowStatusParams, CreateWalletAddressParams, CreateWalletParams,
-        GetWalletAssetsParams, QueryWalletAddressParams, WalletAddressInfo,
+        GetWalletAssetsParams, GetWalletListParams, QueryWalletAddressParams, WalletAddressInfo,
         WalletAddressInfoParams, WalletAddressInfoResponse, WalletAssetInfo, WalletInfo,
     };
     pub use super::web3_api::{
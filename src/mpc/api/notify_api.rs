@@ -10,22 +10,32 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::crypto::CryptoProvider;
-use crate::error::Result;
+use crate::decode::impl_unknown_fields;
+use crate::dedup::{DedupStore, Delivery, NotificationKey};
+use crate::enums::{MpcDepositStatus, MpcWithdrawStatus};
+use crate::error::{ChainUpError, Result, ValidationError};
+use crate::ids::{RecordId, RequestId, SubWalletId, Symbol};
 use crate::mpc::config::MpcConfig;
+use crate::notify_validation::NotifyValidation;
+use crate::timestamp::{AsTimestamp, NumericTime, TextTime, Timestamp};
 use crate::utils::serde_helpers::{
     deserialize_optional_bool, deserialize_optional_i32, deserialize_optional_i64,
+    deserialize_optional_id, deserialize_optional_numeric_time, deserialize_optional_text_time,
 };
 
 // ============================================================================
 // Notification types
 // ============================================================================
 
-/// MPC notification data structure
+/// MPC notification data structure, as received
+///
+/// Holds the fields of every notification kind; [`MpcNotification`] splits
+/// it by kind.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MpcNotifyData {
     /// Record ID
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub id: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_id")]
+    pub id: Option<RecordId>,
     /// Notification side (deposit/withdraw)
     #[serde(default)]
     pub side: Option<String>,
@@ -34,10 +44,10 @@ pub struct MpcNotifyData {
     pub notify_type: Option<String>,
     /// Request ID
     #[serde(default)]
-    pub request_id: Option<String>,
+    pub request_id: Option<RequestId>,
     /// Sub-wallet ID
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub sub_wallet_id: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_id")]
+    pub sub_wallet_id: Option<SubWalletId>,
     /// App ID
     #[serde(default)]
     pub app_id: Option<String>,
@@ -49,7 +59,7 @@ pub struct MpcNotifyData {
     pub base_symbol: Option<String>,
     /// Symbol
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Contract address (for tokens)
     #[serde(default)]
     pub contract_address: Option<String>,
@@ -81,8 +91,8 @@ pub struct MpcNotifyData {
     #[serde(default, deserialize_with = "deserialize_optional_i64")]
     pub block_height: Option<i64>,
     /// Block time
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub block_time: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub block_time: Option<NumericTime>,
     /// Confirmations count
     #[serde(default, deserialize_with = "deserialize_optional_i32")]
     pub confirmations: Option<i32>,
@@ -144,19 +154,562 @@ pub struct MpcNotifyData {
     #[serde(default)]
     pub sign: Option<String>,
     /// Notify time
-    #[serde(default)]
-    pub notify_time: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_text_time")]
+    pub notify_time: Option<TextTime>,
     /// Creation time
-    #[serde(default)]
-    pub created_at: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_text_time")]
+    pub created_at: Option<TextTime>,
     /// Update time
-    #[serde(default)]
-    pub updated_at: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_text_time")]
+    pub updated_at: Option<TextTime>,
     /// Raw data for additional fields
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(MpcNotifyData);
+
+impl MpcNotifyData {
+    /// Kind of record the notification reports, as named by the
+    /// [`MpcNotification`] variants (`"deposit"`, `"withdraw"`, `"web3"`,
+    /// `"tron_delegate"`, `"auto_collect"`), or `"unknown"`
+    ///
+    /// Withdrawals, Web3 transactions, Tron delegations and auto-collect
+    /// sweeps all arrive with `side` withdraw, and each has its own ID
+    /// sequence.
+    pub fn kind(&self) -> &'static str {
+        let notify_type = self
+            .notify_type
+            .as_deref()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let side = self
+            .side
+            .as_deref()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let mentions = |words: &[&str]| words.iter().any(|word| notify_type.contains(word));
+
+        if self.trans_type == Some(AUTO_COLLECT_TRANS_TYPE) || mentions(&["collect", "sweep"]) {
+            "auto_collect"
+        } else if mentions(&["web3"]) {
+            "web3"
+        } else if mentions(&["tron", "delegate", "resource"]) {
+            "tron_delegate"
+        } else if side == "deposit" {
+            "deposit"
+        } else if side != "withdraw" {
+            "unknown"
+        } else if self.interactive_contract.is_some() || self.input_data.is_some() {
+            "web3"
+        } else if self.delegate_fee.is_some() {
+            "tron_delegate"
+        } else {
+            "withdraw"
+        }
+    }
+}
+
+/// Auto-collect sweeps are reported with this `trans_type`
+const AUTO_COLLECT_TRANS_TYPE: i32 = 10;
+
+/// MPC notification, split by the kind of record it reports
+///
+/// Built from [`MpcNotifyData`] by looking at `notify_type`, `trans_type` and
+/// `side` (see [`MpcNotifyData::kind`]):
+///
+/// - `trans_type` 10, or a `notify_type` mentioning collect/sweep → auto-collect
+/// - a `notify_type` mentioning web3 → Web3 transaction
+/// - a `notify_type` mentioning tron, delegate or resource → Tron delegation
+/// - `side` deposit → deposit
+/// - `side` withdraw → Web3 transaction if it carries `interactive_contract`
+///   or `input_data`, Tron delegation if it carries `delegate_fee`, otherwise
+///   withdrawal
+///
+/// Anything else is kept as [`MpcNotification::Unknown`], as is a recognised
+/// notification missing one of its mandatory fields.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MpcNotification {
+    /// Incoming transfer to a sub-wallet
+    Deposit(MpcDepositNotification),
+    /// Withdrawal from a sub-wallet
+    Withdraw(MpcWithdrawNotification),
+    /// Web3 (contract interaction) transaction
+    Web3(MpcWeb3Notification),
+    /// Tron energy/bandwidth delegation
+    TronDelegate(MpcTronDelegateNotification),
+    /// Auto-collect sweep from a sub-wallet
+    AutoCollect(MpcAutoCollectNotification),
+    /// Notification of a kind this SDK version does not know, or without a
+    /// field its kind requires, as received
+    Unknown(Box<MpcNotifyData>),
+}
+
+impl MpcNotification {
+    /// Record ID
+    pub fn id(&self) -> Option<RecordId> {
+        match self {
+            MpcNotification::Deposit(n) => Some(n.id),
+            MpcNotification::Withdraw(n) => Some(n.id),
+            MpcNotification::Web3(n) => Some(n.id),
+            MpcNotification::TronDelegate(n) => Some(n.id),
+            MpcNotification::AutoCollect(n) => Some(n.id),
+            MpcNotification::Unknown(data) => data.id,
+        }
+    }
+
+    /// Sub-wallet the record belongs to
+    pub fn sub_wallet_id(&self) -> Option<SubWalletId> {
+        match self {
+            MpcNotification::Deposit(n) => Some(n.sub_wallet_id),
+            MpcNotification::Withdraw(n) => Some(n.sub_wallet_id),
+            MpcNotification::Web3(n) => Some(n.sub_wallet_id),
+            MpcNotification::TronDelegate(n) => n.sub_wallet_id,
+            MpcNotification::AutoCollect(n) => Some(n.sub_wallet_id),
+            MpcNotification::Unknown(data) => data.sub_wallet_id,
+        }
+    }
+
+    /// Numeric status code
+    pub fn status_code(&self) -> Option<i32> {
+        match self {
+            MpcNotification::Deposit(n) => Some(n.status.code()),
+            MpcNotification::Withdraw(n) => Some(n.status.code()),
+            MpcNotification::Web3(n) => Some(n.status.code()),
+            MpcNotification::TronDelegate(n) => Some(n.status),
+            MpcNotification::AutoCollect(n) => Some(n.status),
+            MpcNotification::Unknown(data) => data.status,
+        }
+    }
+}
+
+impl From<MpcNotifyData> for MpcNotification {
+    fn from(data: MpcNotifyData) -> Self {
+        let kind = data.kind();
+        let converted = match kind {
+            "auto_collect" => {
+                MpcAutoCollectNotification::try_from(data.clone()).map(MpcNotification::AutoCollect)
+            }
+            "web3" => MpcWeb3Notification::try_from(data.clone()).map(MpcNotification::Web3),
+            "tron_delegate" => MpcTronDelegateNotification::try_from(data.clone())
+                .map(MpcNotification::TronDelegate),
+            "deposit" => {
+                MpcDepositNotification::try_from(data.clone()).map(MpcNotification::Deposit)
+            }
+            "withdraw" => {
+                MpcWithdrawNotification::try_from(data.clone()).map(MpcNotification::Withdraw)
+            }
+            _ => return MpcNotification::Unknown(Box::new(data)),
+        };
+        converted.unwrap_or_else(|e| {
+            log::warn!(
+                "[MPC Notify] Keeping {} notification as unknown: {}",
+                kind,
+                e
+            );
+            MpcNotification::Unknown(Box::new(data))
+        })
+    }
+}
+
+/// Returns a mandatory notification field, or a validation error naming it
+fn required<T>(value: Option<T>, kind: &str, field: &str) -> Result<T> {
+    value.ok_or_else(|| {
+        ValidationError::new(format!("{} notification without `{}`", kind, field)).into()
+    })
+}
+
+/// Deposit notification
+#[derive(Debug, Clone, Serialize)]
+pub struct MpcDepositNotification {
+    /// Record ID
+    pub id: RecordId,
+    /// Sub-wallet ID
+    pub sub_wallet_id: SubWalletId,
+    /// Symbol
+    pub symbol: Symbol,
+    /// Main chain symbol
+    pub main_chain_symbol: Option<String>,
+    /// Contract address (for tokens)
+    pub contract_address: Option<String>,
+    /// Amount
+    pub amount: Decimal,
+    /// Status
+    pub status: MpcDepositStatus,
+    /// Transaction hash
+    pub txid: String,
+    /// Sender address
+    pub address_from: Option<String>,
+    /// Receiving address
+    pub address_to: String,
+    /// Memo
+    pub memo: Option<String>,
+    /// Confirmations count
+    pub confirmations: Option<i32>,
+    /// Confirmations needed to credit the deposit
+    pub safe_confirm: Option<i32>,
+    /// Transaction height
+    pub tx_height: Option<i64>,
+    /// Block time
+    pub block_time: Option<Timestamp>,
+    /// Whether the deposit is a mining reward
+    pub is_mining: bool,
+    /// KYT status
+    pub kyt_status: Option<bool>,
+    /// App ID the notification is addressed to
+    pub app_id: Option<String>,
+    /// Notify time
+    pub notify_time: Option<Timestamp>,
+    /// Creation time
+    pub created_at: Option<Timestamp>,
+    /// Update time
+    pub updated_at: Option<Timestamp>,
+    /// Fields not covered above
+    pub extra: HashMap<String, Value>,
+}
+
+impl TryFrom<MpcNotifyData> for MpcDepositNotification {
+    type Error = ChainUpError;
+
+    fn try_from(data: MpcNotifyData) -> Result<Self> {
+        const KIND: &str = "deposit";
+        Ok(Self {
+            id: required(data.id, KIND, "id")?,
+            sub_wallet_id: required(data.sub_wallet_id, KIND, "sub_wallet_id")?,
+            symbol: required(data.symbol, KIND, "symbol")?,
+            main_chain_symbol: data.main_chain_symbol,
+            contract_address: data.contract_address,
+            amount: required(data.amount, KIND, "amount")?,
+            status: required(data.status, KIND, "status")?.into(),
+            txid: required(data.txid, KIND, "txid")?,
+            address_from: data.address_from.or(data.from),
+            address_to: required(data.address_to.or(data.to), KIND, "address_to")?,
+            memo: data.memo,
+            confirmations: data.confirmations.or(data.confirm),
+            safe_confirm: data.safe_confirm,
+            tx_height: data.tx_height.or(data.block_height),
+            block_time: data.block_time.as_timestamp(),
+            is_mining: data.is_mining == Some(1),
+            kyt_status: data.kyt_status,
+            app_id: data.app_id,
+            notify_time: data.notify_time.as_timestamp(),
+            created_at: data.created_at.as_timestamp(),
+            updated_at: data.updated_at.as_timestamp(),
+            extra: data.extra,
+        })
+    }
+}
+
+/// Withdrawal notification
+#[derive(Debug, Clone, Serialize)]
+pub struct MpcWithdrawNotification {
+    /// Record ID
+    pub id: RecordId,
+    /// Request ID given when withdrawing; `None` for withdrawals made from
+    /// the console or app
+    pub request_id: Option<RequestId>,
+    /// Sub-wallet ID
+    pub sub_wallet_id: SubWalletId,
+    /// Symbol
+    pub symbol: Symbol,
+    /// Main chain symbol
+    pub main_chain_symbol: Option<String>,
+    /// Contract address (for tokens)
+    pub contract_address: Option<String>,
+    /// Amount
+    pub amount: Decimal,
+    /// Estimated fee
+    pub fee: Option<Decimal>,
+    /// Fee actually paid
+    pub real_fee: Option<Decimal>,
+    /// Fee symbol
+    pub fee_symbol: Option<String>,
+    /// Status
+    pub status: MpcWithdrawStatus,
+    /// Transaction hash, once sent
+    pub txid: Option<String>,
+    /// Sending address
+    pub address_from: Option<String>,
+    /// Destination address
+    pub address_to: String,
+    /// Memo
+    pub memo: Option<String>,
+    /// Confirmations count
+    pub confirmations: Option<i32>,
+    /// Transaction height
+    pub tx_height: Option<i64>,
+    /// Withdraw source
+    pub withdraw_source: Option<String>,
+    /// KYT status
+    pub kyt_status: Option<bool>,
+    /// App ID the notification is addressed to
+    pub app_id: Option<String>,
+    /// Notify time
+    pub notify_time: Option<Timestamp>,
+    /// Creation time
+    pub created_at: Option<Timestamp>,
+    /// Update time
+    pub updated_at: Option<Timestamp>,
+    /// Fields not covered above
+    pub extra: HashMap<String, Value>,
+}
+
+impl TryFrom<MpcNotifyData> for MpcWithdrawNotification {
+    type Error = ChainUpError;
+
+    fn try_from(data: MpcNotifyData) -> Result<Self> {
+        const KIND: &str = "withdraw";
+        Ok(Self {
+            id: required(data.id, KIND, "id")?,
+            request_id: data.request_id,
+            sub_wallet_id: required(data.sub_wallet_id, KIND, "sub_wallet_id")?,
+            symbol: required(data.symbol, KIND, "symbol")?,
+            main_chain_symbol: data.main_chain_symbol,
+            contract_address: data.contract_address,
+            amount: required(data.amount, KIND, "amount")?,
+            fee: data.fee,
+            real_fee: data.real_fee,
+            fee_symbol: data.fee_symbol,
+            status: required(data.status, KIND, "status")?.into(),
+            txid: data.txid,
+            address_from: data.address_from.or(data.from),
+            address_to: required(data.address_to.or(data.to), KIND, "address_to")?,
+            memo: data.memo,
+            confirmations: data.confirmations.or(data.confirm),
+            tx_height: data.tx_height.or(data.block_height),
+            withdraw_source: data.withdraw_source,
+            kyt_status: data.kyt_status,
+            app_id: data.app_id,
+            notify_time: data.notify_time.as_timestamp(),
+            created_at: data.created_at.as_timestamp(),
+            updated_at: data.updated_at.as_timestamp(),
+            extra: data.extra,
+        })
+    }
+}
+
+/// Web3 transaction notification
+#[derive(Debug, Clone, Serialize)]
+pub struct MpcWeb3Notification {
+    /// Record ID
+    pub id: RecordId,
+    /// Request ID given when creating the transaction; `None` when not
+    /// created through the API
+    pub request_id: Option<RequestId>,
+    /// Sub-wallet ID
+    pub sub_wallet_id: SubWalletId,
+    /// Main chain symbol
+    pub main_chain_symbol: String,
+    /// Contract the transaction interacts with
+    pub interactive_contract: String,
+    /// Amount sent with the transaction
+    pub amount: Option<Decimal>,
+    /// Hex-encoded call data
+    pub input_data: Option<String>,
+    /// Transaction type (see [`crate::MpcWeb3TransType`])
+    pub trans_type: Option<i32>,
+    /// Estimated fee
+    pub fee: Option<Decimal>,
+    /// Fee actually paid
+    pub real_fee: Option<Decimal>,
+    /// Fee symbol
+    pub fee_symbol: Option<String>,
+    /// Status (same codes as withdrawals)
+    pub status: MpcWithdrawStatus,
+    /// Transaction hash, once sent
+    pub txid: Option<String>,
+    /// Sending address
+    pub address_from: Option<String>,
+    /// Confirmations count
+    pub confirmations: Option<i32>,
+    /// Transaction height
+    pub tx_height: Option<i64>,
+    /// Dapp name
+    pub dapp_name: Option<String>,
+    /// Dapp URL
+    pub dapp_url: Option<String>,
+    /// Dapp image URL
+    pub dapp_img: Option<String>,
+    /// App ID the notification is addressed to
+    pub app_id: Option<String>,
+    /// Notify time
+    pub notify_time: Option<Timestamp>,
+    /// Creation time
+    pub created_at: Option<Timestamp>,
+    /// Update time
+    pub updated_at: Option<Timestamp>,
+    /// Fields not covered above
+    pub extra: HashMap<String, Value>,
+}
+
+impl TryFrom<MpcNotifyData> for MpcWeb3Notification {
+    type Error = ChainUpError;
+
+    fn try_from(data: MpcNotifyData) -> Result<Self> {
+        const KIND: &str = "web3";
+        Ok(Self {
+            id: required(data.id, KIND, "id")?,
+            request_id: data.request_id,
+            sub_wallet_id: required(data.sub_wallet_id, KIND, "sub_wallet_id")?,
+            main_chain_symbol: required(
+                data.main_chain_symbol
+                    .or(data.symbol.map(|symbol| symbol.as_str().to_string())),
+                KIND,
+                "main_chain_symbol",
+            )?,
+            interactive_contract: required(
+                data.interactive_contract.or(data.address_to.or(data.to)),
+                KIND,
+                "interactive_contract",
+            )?,
+            amount: data.amount,
+            input_data: data.input_data,
+            trans_type: data.trans_type,
+            fee: data.fee,
+            real_fee: data.real_fee,
+            fee_symbol: data.fee_symbol,
+            status: required(data.status, KIND, "status")?.into(),
+            txid: data.txid,
+            address_from: data.address_from.or(data.from),
+            confirmations: data.confirmations.or(data.confirm),
+            tx_height: data.tx_height.or(data.block_height),
+            dapp_name: data.dapp_name,
+            dapp_url: data.dapp_url,
+            dapp_img: data.dapp_img,
+            app_id: data.app_id,
+            notify_time: data.notify_time.as_timestamp(),
+            created_at: data.created_at.as_timestamp(),
+            updated_at: data.updated_at.as_timestamp(),
+            extra: data.extra,
+        })
+    }
+}
+
+/// Tron resource delegation notification
+#[derive(Debug, Clone, Serialize)]
+pub struct MpcTronDelegateNotification {
+    /// Record ID
+    pub id: RecordId,
+    /// Request ID given when buying the resource; `None` when not bought
+    /// through the API
+    pub request_id: Option<RequestId>,
+    /// Sub-wallet ID, when the delegation is tied to one
+    pub sub_wallet_id: Option<SubWalletId>,
+    /// Fee paid for the delegation
+    pub delegate_fee: Decimal,
+    /// Fee symbol
+    pub fee_symbol: Option<String>,
+    /// Status code
+    pub status: i32,
+    /// Transaction hash
+    pub txid: Option<String>,
+    /// Address paying for the resource
+    pub address_from: Option<String>,
+    /// Address receiving the resource
+    pub address_to: Option<String>,
+    /// App ID the notification is addressed to
+    pub app_id: Option<String>,
+    /// Notify time
+    pub notify_time: Option<Timestamp>,
+    /// Creation time
+    pub created_at: Option<Timestamp>,
+    /// Update time
+    pub updated_at: Option<Timestamp>,
+    /// Fields not covered above
+    pub extra: HashMap<String, Value>,
+}
+
+impl TryFrom<MpcNotifyData> for MpcTronDelegateNotification {
+    type Error = ChainUpError;
+
+    fn try_from(data: MpcNotifyData) -> Result<Self> {
+        const KIND: &str = "tron delegate";
+        Ok(Self {
+            id: required(data.id, KIND, "id")?,
+            request_id: data.request_id,
+            sub_wallet_id: data.sub_wallet_id,
+            delegate_fee: required(data.delegate_fee.or(data.fee), KIND, "delegate_fee")?,
+            fee_symbol: data.fee_symbol,
+            status: required(data.status, KIND, "status")?,
+            txid: data.txid,
+            address_from: data.address_from.or(data.from),
+            address_to: data.address_to.or(data.to),
+            app_id: data.app_id,
+            notify_time: data.notify_time.as_timestamp(),
+            created_at: data.created_at.as_timestamp(),
+            updated_at: data.updated_at.as_timestamp(),
+            extra: data.extra,
+        })
+    }
+}
+
+/// Auto-collect sweep notification
+#[derive(Debug, Clone, Serialize)]
+pub struct MpcAutoCollectNotification {
+    /// Record ID
+    pub id: RecordId,
+    /// Sub-wallet swept from
+    pub sub_wallet_id: SubWalletId,
+    /// Symbol
+    pub symbol: Symbol,
+    /// Amount swept
+    pub amount: Decimal,
+    /// Fee
+    pub fee: Option<Decimal>,
+    /// Fee symbol
+    pub fee_symbol: Option<String>,
+    /// Status code
+    pub status: i32,
+    /// Transaction hash
+    pub txid: Option<String>,
+    /// Sub-wallet address swept from
+    pub address_from: Option<String>,
+    /// Collection address
+    pub address_to: Option<String>,
+    /// Confirmations count
+    pub confirmations: Option<i32>,
+    /// Transaction height
+    pub tx_height: Option<i64>,
+    /// App ID the notification is addressed to
+    pub app_id: Option<String>,
+    /// Notify time
+    pub notify_time: Option<Timestamp>,
+    /// Creation time
+    pub created_at: Option<Timestamp>,
+    /// Update time
+    pub updated_at: Option<Timestamp>,
+    /// Fields not covered above
+    pub extra: HashMap<String, Value>,
+}
+
+impl TryFrom<MpcNotifyData> for MpcAutoCollectNotification {
+    type Error = ChainUpError;
+
+    fn try_from(data: MpcNotifyData) -> Result<Self> {
+        const KIND: &str = "auto-collect";
+        Ok(Self {
+            id: required(data.id, KIND, "id")?,
+            sub_wallet_id: required(data.sub_wallet_id, KIND, "sub_wallet_id")?,
+            symbol: required(data.symbol, KIND, "symbol")?,
+            amount: required(data.amount, KIND, "amount")?,
+            fee: data.fee,
+            fee_symbol: data.fee_symbol,
+            status: required(data.status, KIND, "status")?,
+            txid: data.txid,
+            address_from: data.address_from.or(data.from),
+            address_to: data.address_to.or(data.to),
+            confirmations: data.confirmations.or(data.confirm),
+            tx_height: data.tx_height.or(data.block_height),
+            app_id: data.app_id,
+            notify_time: data.notify_time.as_timestamp(),
+            created_at: data.created_at.as_timestamp(),
+            updated_at: data.updated_at.as_timestamp(),
+            extra: data.extra,
+        })
+    }
+}
+
 // ============================================================================
 // Notify API Implementation
 // ============================================================================
@@ -168,6 +721,7 @@ pub struct NotifyApi {
     #[allow(dead_code)]
     config: MpcConfig,
     crypto_provider: Arc<dyn CryptoProvider>,
+    validation: Option<NotifyValidation>,
 }
 
 impl NotifyApi {
@@ -176,27 +730,141 @@ impl NotifyApi {
         Self {
             config,
             crypto_provider,
+            validation: None,
         }
     }
 
+    /// Checks every decrypted notification with `validation`
+    ///
+    /// Rejected notifications fail with
+    /// [`ChainUpError::Signature`]; see [`crate::notify_validation`].
+    pub fn with_validation(mut self, validation: NotifyValidation) -> Self {
+        self.validation = Some(validation);
+        self
+    }
+
     /// Decrypts webhook notification data
     ///
     /// # Arguments
     /// * `encrypted_data` - Encrypted notification data
     ///
     /// # Returns
-    /// Decrypted notification data as MpcNotifyData
+    /// Decrypted notification, by kind (see [`MpcNotification`])
     ///
     /// # Example
     /// ```ignore
-    /// let data = notify_api.decrypt_notification("encrypted_string")?;
-    /// println!("Notification type: {:?}", data.notify_type);
+    /// match notify_api.decrypt_notification("encrypted_string")? {
+    ///     MpcNotification::Deposit(deposit) => println!("Deposit of {}", deposit.amount),
+    ///     other => println!("Notification: {:?}", other),
+    /// }
     /// ```
-    pub fn decrypt_notification(&self, encrypted_data: &str) -> Result<MpcNotifyData> {
+    pub fn decrypt_notification(&self, encrypted_data: &str) -> Result<MpcNotification> {
+        Ok(MpcNotification::from(
+            self.decrypt_notification_raw(encrypted_data)?,
+        ))
+    }
+
+    /// Decrypts webhook notification data and checks it against `store`
+    ///
+    /// A state handled before comes back as [`Delivery::Duplicate`]. Nothing
+    /// is stored: call [`Delivery::commit`] once a [`Delivery::New`]
+    /// notification was handled; see [`crate::dedup`].
+    pub fn decrypt_notification_once(
+        &self,
+        encrypted_data: &str,
+        store: &dyn DedupStore,
+    ) -> Result<Delivery<MpcNotification>> {
+        let data = self.decrypt_notification_raw(encrypted_data)?;
+        let key = NotificationKey::from(&data);
+        Delivery::check(store, key, MpcNotification::from(data))
+    }
+
+    /// Decrypts webhook notification data without splitting it by kind
+    ///
+    /// # Arguments
+    /// * `encrypted_data` - Encrypted notification data
+    ///
+    /// # Returns
+    /// Decrypted notification data as MpcNotifyData
+    pub fn decrypt_notification_raw(&self, encrypted_data: &str) -> Result<MpcNotifyData> {
+        let data: MpcNotifyData = serde_json::from_str(&self.decrypt_text(encrypted_data)?)?;
+        Ok(data)
+    }
+
+    /// Decrypts and validates webhook notification data, returning its JSON
+    /// text
+    pub(crate) fn decrypt_text(&self, encrypted_data: &str) -> Result<String> {
         let decrypted = self
             .crypto_provider
             .decrypt_with_public_key(encrypted_data)?;
-        let data: MpcNotifyData = serde_json::from_str(&decrypted)?;
-        Ok(data)
+        if let Some(validation) = &self.validation {
+            validation.check(&decrypted)?;
+        }
+        Ok(decrypted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(value: Value) -> MpcNotification {
+        MpcNotification::from(serde_json::from_value::<MpcNotifyData>(value).unwrap())
+    }
+
+    #[test]
+    fn test_notification_kinds() {
+        let deposit = notification(serde_json::json!({
+            "id": 11, "side": "deposit", "sub_wallet_id": "7", "symbol": "ETH",
+            "amount": "1.5", "status": 1900, "txid": "0xabc", "to": "0xdef",
+            "confirmations": "3", "notify_time": 1_700_000_030_000_i64
+        }));
+        let MpcNotification::Deposit(deposit) = deposit else {
+            panic!("expected a deposit: {:?}", deposit);
+        };
+        assert_eq!(deposit.status, MpcDepositStatus::Confirming);
+        assert_eq!(deposit.address_to, "0xdef");
+        assert_eq!(deposit.confirmations, Some(3));
+        assert_eq!(
+            deposit.notify_time,
+            Some(Timestamp::from_millis(1_700_000_030_000))
+        );
+
+        let web3 = notification(serde_json::json!({
+            "id": 12, "side": "withdraw", "request_id": "r-12", "sub_wallet_id": 7,
+            "main_chain_symbol": "ETH", "interactive_contract": "0xc0",
+            "input_data": "0x095ea7b3", "status": 1200
+        }));
+        assert!(matches!(web3, MpcNotification::Web3(_)));
+
+        let collect = notification(serde_json::json!({
+            "id": 13, "side": "withdraw", "trans_type": 10, "sub_wallet_id": 7,
+            "symbol": "USDT", "amount": "20", "status": 2000
+        }));
+        assert!(matches!(collect, MpcNotification::AutoCollect(_)));
+        assert_eq!(collect.status_code(), Some(2000));
+
+        let unknown = notification(serde_json::json!({"id": 14, "side": "airdrop"}));
+        assert!(matches!(unknown, MpcNotification::Unknown(_)));
+
+        // Console withdrawals carry no request_id
+        let withdraw = notification(serde_json::json!({
+            "id": 15, "side": "withdraw", "sub_wallet_id": 7, "symbol": "ETH",
+            "amount": "1", "status": 1000, "address_to": "0xdef"
+        }));
+        let MpcNotification::Withdraw(withdraw) = withdraw else {
+            panic!("expected a withdrawal: {:?}", withdraw);
+        };
+        assert_eq!(withdraw.request_id, None);
+
+        // A recognised kind without a mandatory field is kept as received
+        let incomplete = notification(serde_json::json!({
+            "id": 16, "side": "withdraw", "sub_wallet_id": 7, "symbol": "ETH",
+            "status": 1000, "address_to": "0xdef"
+        }));
+        let MpcNotification::Unknown(data) = incomplete else {
+            panic!("expected an unknown notification: {:?}", incomplete);
+        };
+        assert_eq!(data.kind(), "withdraw");
     }
 }
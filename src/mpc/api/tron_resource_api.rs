@@ -8,10 +8,19 @@ use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::checkpoint::{CheckpointStore, CheckpointedSync};
 use crate::crypto::CryptoProvider;
+use crate::decode::impl_unknown_fields;
 use crate::error::{Result, ValidationError};
+use crate::ids::{RecordId, RequestId};
+use crate::lookup::{lookup, LookupOptions, LookupResult};
 use crate::mpc::api::base_api::MpcBaseApi;
 use crate::mpc::config::MpcConfig;
+use crate::pagination::SyncIter;
+#[cfg(feature = "async")]
+use crate::pagination::SyncStream;
+use crate::timestamp::NumericTime;
+use crate::utils::serde_helpers::deserialize_optional_numeric_time;
 
 // ============================================================================
 // Request parameter types
@@ -21,7 +30,7 @@ use crate::mpc::config::MpcConfig;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateTronDelegateParams {
     /// Unique request ID (required)
-    pub request_id: String,
+    pub request_id: RequestId,
     /// Buy type: 0=System, 1=Manual (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub buy_type: Option<i32>,
@@ -49,7 +58,7 @@ pub struct CreateTronDelegateParams {
 impl CreateTronDelegateParams {
     /// Creates new parameters with required fields
     pub fn new(
-        request_id: impl Into<String>,
+        request_id: impl Into<RequestId>,
         address_from: impl Into<String>,
         service_charge_type: impl Into<String>,
     ) -> Self {
@@ -106,7 +115,7 @@ impl CreateTronDelegateParams {
         let mut map = HashMap::new();
         map.insert(
             "request_id".to_string(),
-            Value::String(self.request_id.clone()),
+            Value::String(self.request_id.to_string()),
         );
         map.insert(
             "service_charge_type".to_string(),
@@ -157,21 +166,23 @@ pub struct TronDelegateResult {
     pub trans_id: Option<String>,
     /// Request ID
     #[serde(default)]
-    pub request_id: Option<String>,
+    pub request_id: Option<RequestId>,
     /// Raw data for additional fields
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(TronDelegateResult);
+
 /// Tron resource record (buy resource record)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TronResourceRecord {
     /// Record ID
     #[serde(default)]
-    pub id: Option<i64>,
+    pub id: Option<RecordId>,
     /// Request ID
     #[serde(default)]
-    pub request_id: Option<String>,
+    pub request_id: Option<RequestId>,
     /// Buy type: 0=System estimate, 1=Customer specified quantity
     #[serde(default)]
     pub buy_type: Option<i32>,
@@ -212,17 +223,17 @@ pub struct TronResourceRecord {
     #[serde(default)]
     pub reclaim_net_txid: Option<String>,
     /// Energy delegation time (timestamp in ms)
-    #[serde(default)]
-    pub energy_time: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub energy_time: Option<NumericTime>,
     /// Net delegation time (timestamp in ms)
-    #[serde(default)]
-    pub net_time: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub net_time: Option<NumericTime>,
     /// Energy reclaim time (timestamp in ms)
-    #[serde(default)]
-    pub reclaim_energy_time: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub reclaim_energy_time: Option<NumericTime>,
     /// Net reclaim time (timestamp in ms)
-    #[serde(default)]
-    pub reclaim_net_time: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub reclaim_net_time: Option<NumericTime>,
     /// Energy price
     #[serde(default)]
     pub energy_price: Option<String>,
@@ -232,16 +243,26 @@ pub struct TronResourceRecord {
     /// Status
     #[serde(default)]
     pub status: Option<i32>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(TronResourceRecord);
+
 /// Response for sync buy resource records
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncBuyResourceRecordsResponse {
     /// List of Tron resource records
     #[serde(default)]
     pub list: Vec<TronResourceRecord>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(SyncBuyResourceRecordsResponse, list);
+
 // ============================================================================
 // Tron Resource API Implementation
 // ============================================================================
@@ -297,19 +318,19 @@ impl TronResourceApi {
 
         // Additional validation for buy_type 0 or 2
         if let Some(buy_type) = params.buy_type {
-            if buy_type == 0 || buy_type == 2 {
-                if params.address_to.is_none() || params.contract_address.is_none() {
-                    return Err(ValidationError::new(
-                        "For buy_type 0 or 2, address_to and contract_address are required",
-                    )
-                    .into());
-                }
+            if (buy_type == 0 || buy_type == 2)
+                && (params.address_to.is_none() || params.contract_address.is_none())
+            {
+                return Err(ValidationError::new(
+                    "For buy_type 0 or 2, address_to and contract_address are required",
+                )
+                .into());
             }
         }
 
         let data = params.to_map();
-        let response = self.base.post("/api/mpc/tron/delegate", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/api/mpc/tron/delegate", Some(&data))
     }
 
     /// Gets buy resource records by request IDs
@@ -317,7 +338,7 @@ impl TronResourceApi {
     /// Get delegation records by request IDs.
     ///
     /// # Arguments
-    /// * `request_ids` - Request IDs (list of strings, up to 100)
+    /// * `request_ids` - Request IDs (sent in batches of up to 100)
     ///
     /// # Returns
     /// Tron resource records
@@ -337,13 +358,37 @@ impl TronResourceApi {
             .into());
         }
 
-        let mut data = HashMap::new();
-        data.insert("ids".to_string(), Value::String(request_ids.join(",")));
+        Ok(self
+            .lookup_buy_resource_records(request_ids, LookupOptions::default())?
+            .found)
+    }
 
-        let response = self
-            .base
-            .post("/api/mpc/tron/delegate/trans_list", Some(&data))?;
-        self.base.validate_response(response)
+    /// Gets delegation records for any number of request IDs
+    ///
+    /// The IDs are sent in batches of up to 100 (see [`LookupOptions`]); the
+    /// result also lists the IDs no record was returned for.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = tron_api.lookup_buy_resource_records(&ids, LookupOptions::new().with_concurrency(4))?;
+    /// println!("{} found, missing: {:?}", result.found.len(), result.missing);
+    /// ```
+    pub fn lookup_buy_resource_records(
+        &self,
+        request_ids: &[&str],
+        options: LookupOptions,
+    ) -> Result<LookupResult<TronResourceRecord>> {
+        lookup(
+            request_ids,
+            options,
+            |chunk| {
+                let mut data = HashMap::new();
+                data.insert("ids".to_string(), Value::String(chunk.join(",")));
+                self.base
+                    .request("POST", "/api/mpc/tron/delegate/trans_list", Some(&data))
+            },
+            |record: &TronResourceRecord| record.request_id.as_ref().map(ToString::to_string),
+        )
     }
 
     /// Synchronizes buy resource records
@@ -367,9 +412,53 @@ impl TronResourceApi {
         let mut data = HashMap::new();
         data.insert("max_id".to_string(), Value::Number(max_id.into()));
 
-        let response = self
-            .base
-            .post("/api/mpc/tron/delegate/sync_trans_list", Some(&data))?;
-        self.base.validate_response(response)
+        self.base.request(
+            "POST",
+            "/api/mpc/tron/delegate/sync_trans_list",
+            Some(&data),
+        )
+    }
+
+    /// Iterates over all TRON resource records after `max_id`, fetching pages as needed
+    ///
+    /// # Example
+    /// ```ignore
+    /// for record in tron_api.sync_buy_resource_records_iter(0).with_page_size(100) {
+    ///     println!("Record: {:?}", record?);
+    /// }
+    /// ```
+    pub fn sync_buy_resource_records_iter(&self, max_id: i64) -> SyncIter<'_, TronResourceRecord> {
+        SyncIter::new(max_id, move |max_id| self.sync_buy_resource_records(max_id))
+    }
+
+    /// Iterates over TRON resource records page by page, resuming from a stored checkpoint
+    ///
+    /// The checkpoint is keyed by the endpoint path and only advances when
+    /// a page is acknowledged; see [`CheckpointedSync`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut sync = tron_api.sync_buy_resource_records_checkpointed(&store)?;
+    /// while let Some(page) = sync.next_page() {
+    ///     save_to_db(&page?)?;
+    ///     sync.ack()?;
+    /// }
+    /// ```
+    pub fn sync_buy_resource_records_checkpointed<'a>(
+        &'a self,
+        store: &'a dyn CheckpointStore,
+    ) -> Result<CheckpointedSync<'a, TronResourceRecord>> {
+        CheckpointedSync::new(store, "/api/mpc/tron/delegate/sync_trans_list", |max_id| {
+            self.sync_buy_resource_records_iter(max_id)
+        })
+    }
+
+    /// Async stream over all TRON resource records after `max_id`
+    ///
+    /// See [`SyncStream`] for how pages are fetched.
+    #[cfg(feature = "async")]
+    pub fn sync_buy_resource_records_stream(&self, max_id: i64) -> SyncStream<TronResourceRecord> {
+        let api = Self::new(self.base.config.clone(), self.base.crypto_provider.clone());
+        SyncStream::new(max_id, move |max_id| api.sync_buy_resource_records(max_id))
     }
 }
@@ -11,9 +11,13 @@ use serde_json::Value;
 use rust_decimal::Decimal;
 
 use crate::crypto::CryptoProvider;
+use crate::decode::impl_unknown_fields;
 use crate::error::{Result, ValidationError};
+use crate::ids::{SubWalletId, Symbol};
 use crate::mpc::api::base_api::MpcBaseApi;
 use crate::mpc::config::MpcConfig;
+use crate::timestamp::TextTime;
+use crate::utils::serde_helpers::deserialize_optional_text_time;
 
 // ============================================================================
 // Request parameter types
@@ -57,20 +61,53 @@ impl CreateWalletParams {
     }
 }
 
+/// Parameters for listing sub-wallets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetWalletListParams {
+    /// Number of wallets per page (max 100)
+    pub limit: i32,
+    /// Return wallets with an ID greater than this (0 for the first page)
+    pub max_id: SubWalletId,
+}
+
+impl GetWalletListParams {
+    /// Creates new parameters
+    ///
+    /// # Arguments
+    /// * `limit` - Page size (max 100)
+    /// * `max_id` - Last `sub_wallet_id` of the previous page, 0 to start
+    pub fn new(limit: i32, max_id: impl Into<SubWalletId>) -> Self {
+        Self {
+            limit,
+            max_id: max_id.into(),
+        }
+    }
+
+    fn to_map(&self) -> HashMap<String, Value> {
+        let mut map = HashMap::new();
+        map.insert("limit".to_string(), Value::Number(self.limit.into()));
+        map.insert(
+            "max_id".to_string(),
+            Value::Number(self.max_id.value().into()),
+        );
+        map
+    }
+}
+
 /// Parameters for creating a wallet address
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateWalletAddressParams {
     /// Wallet ID
-    pub sub_wallet_id: i64,
+    pub sub_wallet_id: SubWalletId,
     /// Unique identifier for the coin (e.g., "ETH")
-    pub symbol: String,
+    pub symbol: Symbol,
 }
 
 impl CreateWalletAddressParams {
     /// Creates new parameters
-    pub fn new(sub_wallet_id: i64, symbol: impl Into<String>) -> Self {
+    pub fn new(sub_wallet_id: impl Into<SubWalletId>, symbol: impl Into<Symbol>) -> Self {
         Self {
-            sub_wallet_id,
+            sub_wallet_id: sub_wallet_id.into(),
             symbol: symbol.into(),
         }
     }
@@ -79,9 +116,9 @@ impl CreateWalletAddressParams {
         let mut map = HashMap::new();
         map.insert(
             "sub_wallet_id".to_string(),
-            Value::Number(self.sub_wallet_id.into()),
+            Value::Number(self.sub_wallet_id.value().into()),
         );
-        map.insert("symbol".to_string(), Value::String(self.symbol.clone()));
+        map.insert("symbol".to_string(), Value::String(self.symbol.to_string()));
         map
     }
 }
@@ -90,9 +127,9 @@ impl CreateWalletAddressParams {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryWalletAddressParams {
     /// Wallet ID
-    pub sub_wallet_id: i64,
+    pub sub_wallet_id: SubWalletId,
     /// Unique identifier for the coin (e.g., "ETH")
-    pub symbol: String,
+    pub symbol: Symbol,
     /// Starting address ID (optional, default: 0)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_id: Option<i64>,
@@ -100,9 +137,9 @@ pub struct QueryWalletAddressParams {
 
 impl QueryWalletAddressParams {
     /// Creates new parameters
-    pub fn new(sub_wallet_id: i64, symbol: impl Into<String>) -> Self {
+    pub fn new(sub_wallet_id: impl Into<SubWalletId>, symbol: impl Into<Symbol>) -> Self {
         Self {
-            sub_wallet_id,
+            sub_wallet_id: sub_wallet_id.into(),
             symbol: symbol.into(),
             max_id: None,
         }
@@ -118,9 +155,9 @@ impl QueryWalletAddressParams {
         let mut map = HashMap::new();
         map.insert(
             "sub_wallet_id".to_string(),
-            Value::Number(self.sub_wallet_id.into()),
+            Value::Number(self.sub_wallet_id.value().into()),
         );
-        map.insert("symbol".to_string(), Value::String(self.symbol.clone()));
+        map.insert("symbol".to_string(), Value::String(self.symbol.to_string()));
         if let Some(max_id) = self.max_id {
             map.insert("max_id".to_string(), Value::Number(max_id.into()));
         }
@@ -132,16 +169,16 @@ impl QueryWalletAddressParams {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetWalletAssetsParams {
     /// Wallet ID
-    pub sub_wallet_id: i64,
+    pub sub_wallet_id: SubWalletId,
     /// Unique identifier for the coin (e.g., "ETH")
-    pub symbol: String,
+    pub symbol: Symbol,
 }
 
 impl GetWalletAssetsParams {
     /// Creates new parameters
-    pub fn new(sub_wallet_id: i64, symbol: impl Into<String>) -> Self {
+    pub fn new(sub_wallet_id: impl Into<SubWalletId>, symbol: impl Into<Symbol>) -> Self {
         Self {
-            sub_wallet_id,
+            sub_wallet_id: sub_wallet_id.into(),
             symbol: symbol.into(),
         }
     }
@@ -150,9 +187,9 @@ impl GetWalletAssetsParams {
         let mut map = HashMap::new();
         map.insert(
             "sub_wallet_id".to_string(),
-            Value::Number(self.sub_wallet_id.into()),
+            Value::Number(self.sub_wallet_id.value().into()),
         );
-        map.insert("symbol".to_string(), Value::String(self.symbol.clone()));
+        map.insert("symbol".to_string(), Value::String(self.symbol.to_string()));
         map
     }
 }
@@ -246,7 +283,7 @@ impl WalletAddressInfoParams {
 pub struct WalletInfo {
     /// Wallet ID
     #[serde(default)]
-    pub sub_wallet_id: Option<i64>,
+    pub sub_wallet_id: Option<SubWalletId>,
     /// Wallet name
     #[serde(default)]
     pub sub_wallet_name: Option<String>,
@@ -254,10 +291,15 @@ pub struct WalletInfo {
     #[serde(default)]
     pub app_show_status: Option<i32>,
     /// Creation time
-    #[serde(default)]
-    pub created_at: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_text_time")]
+    pub created_at: Option<TextTime>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(WalletInfo);
+
 /// Wallet address information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalletAddressInfo {
@@ -273,8 +315,13 @@ pub struct WalletAddressInfo {
     /// Address memo/tag
     #[serde(default)]
     pub memo: Option<String>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(WalletAddressInfo);
+
 /// Wallet asset information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalletAssetInfo {
@@ -287,8 +334,13 @@ pub struct WalletAssetInfo {
     /// Locked balance
     #[serde(default, with = "rust_decimal::serde::str_option")]
     pub lock_balance: Option<Decimal>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(WalletAssetInfo);
+
 /// Wallet address info response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalletAddressInfoResponse {
@@ -297,12 +349,17 @@ pub struct WalletAddressInfoResponse {
     pub addr_type: Option<i32>,
     /// Wallet ID (if address belongs to this account)
     #[serde(default)]
-    pub sub_wallet_id: Option<i64>,
+    pub sub_wallet_id: Option<SubWalletId>,
     /// Merge address symbol (main chain symbol for address aggregation)
     #[serde(default)]
     pub merge_address_symbol: Option<String>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(WalletAddressInfoResponse);
+
 // ============================================================================
 // Wallet API Implementation
 // ============================================================================
@@ -345,8 +402,8 @@ impl WalletApi {
         }
 
         let data = params.to_map();
-        let response = self.base.post("/api/mpc/sub_wallet/create", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/api/mpc/sub_wallet/create", Some(&data))
     }
 
     /// Creates a wallet address
@@ -370,10 +427,35 @@ impl WalletApi {
         }
 
         let data = params.to_map();
-        let response = self
-            .base
-            .post("/api/mpc/sub_wallet/create/address", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/api/mpc/sub_wallet/create/address", Some(&data))
+    }
+
+    /// Lists sub-wallets
+    ///
+    /// Returns at most `limit` wallets with an ID greater than `max_id`,
+    /// ordered by ID. To enumerate every sub-wallet, pass the last
+    /// `sub_wallet_id` of each page as the next `max_id` until a page comes
+    /// back shorter than `limit`.
+    ///
+    /// # Arguments
+    /// * `params` - Pagination parameters
+    ///
+    /// # Returns
+    /// Wallets in the requested page
+    ///
+    /// # Example
+    /// ```ignore
+    /// let wallets = wallet_api.get_wallet_list(GetWalletListParams::new(100, 0))?;
+    /// ```
+    pub fn get_wallet_list(&self, params: GetWalletListParams) -> Result<Vec<WalletInfo>> {
+        if !(1..=100).contains(&params.limit) {
+            return Err(ValidationError::new("Parameter 'limit' must be between 1 and 100").into());
+        }
+
+        let data = params.to_map();
+        self.base
+            .request("POST", "/api/mpc/sub_wallet/list", Some(&data))
     }
 
     /// Queries wallet address list
@@ -397,10 +479,8 @@ impl WalletApi {
         }
 
         let data = params.to_map();
-        let response = self
-            .base
-            .post("/api/mpc/sub_wallet/get/address/list", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/api/mpc/sub_wallet/get/address/list", Some(&data))
     }
 
     /// Gets wallet assets
@@ -421,8 +501,8 @@ impl WalletApi {
         }
 
         let data = params.to_map();
-        let response = self.base.get("/api/mpc/sub_wallet/assets", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("GET", "/api/mpc/sub_wallet/assets", Some(&data))
     }
 
     /// Modifies the wallet display status
@@ -491,9 +571,7 @@ impl WalletApi {
         }
 
         let data = params.to_map();
-        let response = self
-            .base
-            .get("/api/mpc/sub_wallet/address/info", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("GET", "/api/mpc/sub_wallet/address/info", Some(&data))
     }
 }
@@ -10,12 +10,25 @@ use serde_json::Value;
 
 use rust_decimal::Decimal;
 
+use crate::checkpoint::{CheckpointStore, CheckpointedSync};
 use crate::crypto::CryptoProvider;
+use crate::decode::impl_unknown_fields;
+use crate::enums::MpcWithdrawStatus;
 use crate::error::{Result, ValidationError};
+use crate::ids::{RecordId, RequestId, SubWalletId, Symbol};
+use crate::lookup::{lookup, LookupOptions, LookupResult};
 use crate::mpc::api::base_api::MpcBaseApi;
 use crate::mpc::config::MpcConfig;
 use crate::mpc::sign_util::{MpcSignUtil, Web3SignParams};
-use crate::utils::serde_helpers::{deserialize_optional_i32, deserialize_optional_i64};
+use crate::pagination::SyncIter;
+#[cfg(feature = "async")]
+use crate::pagination::SyncStream;
+use crate::timestamp::NumericTime;
+use crate::utils::amount::format_amount;
+use crate::utils::serde_helpers::{
+    deserialize_optional_i32, deserialize_optional_i64, deserialize_optional_numeric_time,
+    deserialize_optional_status,
+};
 
 // ============================================================================
 // Request parameter types
@@ -25,9 +38,9 @@ use crate::utils::serde_helpers::{deserialize_optional_i32, deserialize_optional
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateWeb3TransParams {
     /// Unique request ID (required)
-    pub request_id: String,
+    pub request_id: RequestId,
     /// Sub-wallet ID (required)
-    pub sub_wallet_id: i64,
+    pub sub_wallet_id: SubWalletId,
     /// Main chain coin symbol, e.g. ETH (required)
     pub main_chain_symbol: String,
     /// Interactive contract address (required)
@@ -63,8 +76,8 @@ impl CreateWeb3TransParams {
     /// Creates new parameters
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        request_id: impl Into<String>,
-        sub_wallet_id: i64,
+        request_id: impl Into<RequestId>,
+        sub_wallet_id: impl Into<SubWalletId>,
         main_chain_symbol: impl Into<String>,
         interactive_contract: impl Into<String>,
         amount: impl Into<String>,
@@ -75,7 +88,7 @@ impl CreateWeb3TransParams {
     ) -> Self {
         Self {
             request_id: request_id.into(),
-            sub_wallet_id,
+            sub_wallet_id: sub_wallet_id.into(),
             main_chain_symbol: main_chain_symbol.into(),
             interactive_contract: interactive_contract.into(),
             amount: amount.into(),
@@ -91,6 +104,35 @@ impl CreateWeb3TransParams {
         }
     }
 
+    /// Creates new parameters from `Decimal` amount and gas price
+    ///
+    /// Both values are formatted with [`format_amount`], so the request and
+    /// the signature use the same string.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_decimal(
+        request_id: impl Into<RequestId>,
+        sub_wallet_id: impl Into<SubWalletId>,
+        main_chain_symbol: impl Into<String>,
+        interactive_contract: impl Into<String>,
+        amount: Decimal,
+        gas_price: Decimal,
+        gas_limit: impl Into<String>,
+        input_data: impl Into<String>,
+        trans_type: impl Into<String>,
+    ) -> Self {
+        Self::new(
+            request_id,
+            sub_wallet_id,
+            main_chain_symbol,
+            interactive_contract,
+            format_amount(amount),
+            format_amount(gas_price),
+            gas_limit,
+            input_data,
+            trans_type,
+        )
+    }
+
     /// Sets the from address
     pub fn with_from(mut self, from: impl Into<String>) -> Self {
         self.from = Some(from.into());
@@ -125,11 +167,11 @@ impl CreateWeb3TransParams {
         let mut map = HashMap::new();
         map.insert(
             "request_id".to_string(),
-            Value::String(self.request_id.clone()),
+            Value::String(self.request_id.to_string()),
         );
         map.insert(
             "sub_wallet_id".to_string(),
-            Value::Number(self.sub_wallet_id.into()),
+            Value::Number(self.sub_wallet_id.value().into()),
         );
         map.insert(
             "main_chain_symbol".to_string(),
@@ -172,6 +214,7 @@ impl CreateWeb3TransParams {
         map
     }
 
+    /// The signed subset of these parameters
     fn to_sign_params(&self) -> Web3SignParams {
         Web3SignParams {
             request_id: self.request_id.clone(),
@@ -229,19 +272,19 @@ impl AccelerateWeb3TransParams {
 pub struct Web3TransRecord {
     /// Record ID
     #[serde(default)]
-    pub id: Option<i64>,
+    pub id: Option<RecordId>,
     /// Request ID
     #[serde(default)]
-    pub request_id: Option<String>,
+    pub request_id: Option<RequestId>,
     /// Sub-wallet ID
     #[serde(default)]
-    pub sub_wallet_id: Option<i64>,
+    pub sub_wallet_id: Option<SubWalletId>,
     /// Main chain symbol
     #[serde(default)]
     pub main_chain_symbol: Option<String>,
     /// Token symbol
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Interactive contract
     #[serde(default)]
     pub interactive_contract: Option<String>,
@@ -275,9 +318,9 @@ pub struct Web3TransRecord {
     /// Fee token symbol
     #[serde(default)]
     pub fee_symbol: Option<String>,
-    /// Status
-    #[serde(default)]
-    pub status: Option<i32>,
+    /// Status (same codes as withdrawals)
+    #[serde(default, deserialize_with = "deserialize_optional_status")]
+    pub status: Option<MpcWithdrawStatus>,
     /// Transaction type
     #[serde(default, deserialize_with = "deserialize_optional_i32")]
     pub trans_type: Option<i32>,
@@ -294,21 +337,31 @@ pub struct Web3TransRecord {
     #[serde(default)]
     pub remark: Option<String>,
     /// Creation time (timestamp in milliseconds)
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub created_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub created_at: Option<NumericTime>,
     /// Update time (timestamp in milliseconds)
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub updated_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub updated_at: Option<NumericTime>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(Web3TransRecord);
+
 /// Response for sync Web3 transaction records
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncWeb3TransRecordsResponse {
     /// List of Web3 transaction records
     #[serde(default)]
     pub list: Vec<Web3TransRecord>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(SyncWeb3TransRecordsResponse, list);
+
 // ============================================================================
 // Web3 API Implementation
 // ============================================================================
@@ -360,14 +413,14 @@ impl Web3Api {
     pub fn create_web3_trans(&self, params: CreateWeb3TransParams) -> Result<Web3TransRecord> {
         // Validate required fields
         let required_fields = [
-            ("request_id", &params.request_id),
-            ("main_chain_symbol", &params.main_chain_symbol),
-            ("interactive_contract", &params.interactive_contract),
-            ("amount", &params.amount),
-            ("gas_price", &params.gas_price),
-            ("gas_limit", &params.gas_limit),
-            ("input_data", &params.input_data),
-            ("trans_type", &params.trans_type),
+            ("request_id", params.request_id.as_str()),
+            ("main_chain_symbol", params.main_chain_symbol.as_str()),
+            ("interactive_contract", params.interactive_contract.as_str()),
+            ("amount", params.amount.as_str()),
+            ("gas_price", params.gas_price.as_str()),
+            ("gas_limit", params.gas_limit.as_str()),
+            ("input_data", params.input_data.as_str()),
+            ("trans_type", params.trans_type.as_str()),
         ];
 
         for (name, value) in required_fields {
@@ -391,14 +444,13 @@ impl Web3Api {
 
         // Generate signature if needed
         if need_sign {
-            let sign_params = params.to_sign_params();
             let signature =
-                MpcSignUtil::generate_web3_sign(&sign_params, self.crypto_provider.as_ref())?;
+                MpcSignUtil::sign(&params.to_sign_params(), self.crypto_provider.as_ref())?;
             data.insert("sign".to_string(), Value::String(signature));
         }
 
-        let response = self.base.post("/api/mpc/web3/trans/create", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/api/mpc/web3/trans/create", Some(&data))
     }
 
     /// Accelerates a Web3 transaction
@@ -435,14 +487,14 @@ impl Web3Api {
         }
 
         let data = params.to_map();
-        let response = self.base.post("/api/mpc/web3/pending", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/api/mpc/web3/pending", Some(&data))
     }
 
     /// Gets Web3 transaction records
     ///
     /// # Arguments
-    /// * `request_ids` - Request IDs (list of strings, up to 100)
+    /// * `request_ids` - Request IDs (sent in batches of up to 100)
     ///
     /// # Returns
     /// Web3 transaction records
@@ -459,11 +511,37 @@ impl Web3Api {
             .into());
         }
 
-        let mut data = HashMap::new();
-        data.insert("ids".to_string(), Value::String(request_ids.join(",")));
+        Ok(self
+            .lookup_web3_trans_records(request_ids, LookupOptions::default())?
+            .found)
+    }
 
-        let response = self.base.get("/api/mpc/web3/trans_list", Some(&data))?;
-        self.base.validate_response(response)
+    /// Gets Web3 transaction records for any number of request IDs
+    ///
+    /// The IDs are sent in batches of up to 100 (see [`LookupOptions`]); the
+    /// result also lists the IDs no record was returned for.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = web3_api.lookup_web3_trans_records(&ids, LookupOptions::new().with_concurrency(4))?;
+    /// println!("{} found, missing: {:?}", result.found.len(), result.missing);
+    /// ```
+    pub fn lookup_web3_trans_records(
+        &self,
+        request_ids: &[&str],
+        options: LookupOptions,
+    ) -> Result<LookupResult<Web3TransRecord>> {
+        lookup(
+            request_ids,
+            options,
+            |chunk| {
+                let mut data = HashMap::new();
+                data.insert("ids".to_string(), Value::String(chunk.join(",")));
+                self.base
+                    .request("GET", "/api/mpc/web3/trans_list", Some(&data))
+            },
+            |record: &Web3TransRecord| record.request_id.as_ref().map(ToString::to_string),
+        )
     }
 
     /// Synchronizes Web3 transaction records
@@ -485,9 +563,50 @@ impl Web3Api {
         let mut data = HashMap::new();
         data.insert("max_id".to_string(), Value::Number(max_id.into()));
 
-        let response = self
-            .base
-            .get("/api/mpc/web3/sync_trans_list", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("GET", "/api/mpc/web3/sync_trans_list", Some(&data))
+    }
+
+    /// Iterates over all Web3 transaction records after `max_id`, fetching pages as needed
+    ///
+    /// # Example
+    /// ```ignore
+    /// for record in web3_api.sync_web3_trans_records_iter(0).with_page_size(100) {
+    ///     println!("Record: {:?}", record?);
+    /// }
+    /// ```
+    pub fn sync_web3_trans_records_iter(&self, max_id: i64) -> SyncIter<'_, Web3TransRecord> {
+        SyncIter::new(max_id, move |max_id| self.sync_web3_trans_records(max_id))
+    }
+
+    /// Iterates over Web3 transaction records page by page, resuming from a stored checkpoint
+    ///
+    /// The checkpoint is keyed by the endpoint path and only advances when
+    /// a page is acknowledged; see [`CheckpointedSync`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut sync = web3_api.sync_web3_trans_records_checkpointed(&store)?;
+    /// while let Some(page) = sync.next_page() {
+    ///     save_to_db(&page?)?;
+    ///     sync.ack()?;
+    /// }
+    /// ```
+    pub fn sync_web3_trans_records_checkpointed<'a>(
+        &'a self,
+        store: &'a dyn CheckpointStore,
+    ) -> Result<CheckpointedSync<'a, Web3TransRecord>> {
+        CheckpointedSync::new(store, "/api/mpc/web3/sync_trans_list", |max_id| {
+            self.sync_web3_trans_records_iter(max_id)
+        })
+    }
+
+    /// Async stream over all Web3 transaction records after `max_id`
+    ///
+    /// See [`SyncStream`] for how pages are fetched.
+    #[cfg(feature = "async")]
+    pub fn sync_web3_trans_records_stream(&self, max_id: i64) -> SyncStream<Web3TransRecord> {
+        let api = Self::new(self.base.config.clone(), self.base.crypto_provider.clone());
+        SyncStream::new(max_id, move |max_id| api.sync_web3_trans_records(max_id))
     }
 }
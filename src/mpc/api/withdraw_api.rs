@@ -10,12 +10,22 @@ use serde_json::Value;
 
 use rust_decimal::Decimal;
 
+use crate::checkpoint::{CheckpointStore, CheckpointedSync};
 use crate::crypto::CryptoProvider;
+use crate::decode::impl_unknown_fields;
+use crate::enums::MpcWithdrawStatus;
 use crate::error::{Result, ValidationError};
+use crate::ids::{RecordId, RequestId, SubWalletId, Symbol};
+use crate::lookup::{lookup, LookupOptions, LookupResult};
 use crate::mpc::api::base_api::MpcBaseApi;
 use crate::mpc::config::MpcConfig;
 use crate::mpc::sign_util::{MpcSignUtil, WithdrawSignParams};
-use crate::utils::serde_helpers::deserialize_optional_i64;
+use crate::pagination::SyncIter;
+#[cfg(feature = "async")]
+use crate::pagination::SyncStream;
+use crate::timestamp::NumericTime;
+use crate::utils::amount::format_amount;
+use crate::utils::serde_helpers::{deserialize_optional_numeric_time, deserialize_optional_status};
 
 // ============================================================================
 // Request parameter types
@@ -25,11 +35,11 @@ use crate::utils::serde_helpers::deserialize_optional_i64;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WithdrawParams {
     /// Unique request ID
-    pub request_id: String,
+    pub request_id: RequestId,
     /// Sub-wallet ID
-    pub sub_wallet_id: i64,
+    pub sub_wallet_id: SubWalletId,
     /// Coin symbol (e.g., "USDTERC20")
-    pub symbol: String,
+    pub symbol: Symbol,
     /// Withdrawal amount
     pub amount: String,
     /// Destination address
@@ -54,15 +64,15 @@ pub struct WithdrawParams {
 impl WithdrawParams {
     /// Creates new parameters
     pub fn new(
-        request_id: impl Into<String>,
-        sub_wallet_id: i64,
-        symbol: impl Into<String>,
+        request_id: impl Into<RequestId>,
+        sub_wallet_id: impl Into<SubWalletId>,
+        symbol: impl Into<Symbol>,
         amount: impl Into<String>,
         address_to: impl Into<String>,
     ) -> Self {
         Self {
             request_id: request_id.into(),
-            sub_wallet_id,
+            sub_wallet_id: sub_wallet_id.into(),
             symbol: symbol.into(),
             amount: amount.into(),
             address_to: address_to.into(),
@@ -74,6 +84,27 @@ impl WithdrawParams {
         }
     }
 
+    /// Creates new parameters from a `Decimal` amount
+    ///
+    /// The amount is formatted with [`format_amount`], so the request and the
+    /// signature use the same string. Check it first with
+    /// `CoinDetails::withdraw_amount_rules` to catch precision errors locally.
+    pub fn from_decimal(
+        request_id: impl Into<RequestId>,
+        sub_wallet_id: impl Into<SubWalletId>,
+        symbol: impl Into<Symbol>,
+        amount: Decimal,
+        address_to: impl Into<String>,
+    ) -> Self {
+        Self::new(
+            request_id,
+            sub_wallet_id,
+            symbol,
+            format_amount(amount),
+            address_to,
+        )
+    }
+
     /// Sets the from address
     pub fn with_from(mut self, from: impl Into<String>) -> Self {
         self.from = Some(from.into());
@@ -108,13 +139,13 @@ impl WithdrawParams {
         let mut map = HashMap::new();
         map.insert(
             "request_id".to_string(),
-            Value::String(self.request_id.clone()),
+            Value::String(self.request_id.to_string()),
         );
         map.insert(
             "sub_wallet_id".to_string(),
-            Value::Number(self.sub_wallet_id.into()),
+            Value::Number(self.sub_wallet_id.value().into()),
         );
-        map.insert("symbol".to_string(), Value::String(self.symbol.clone()));
+        map.insert("symbol".to_string(), Value::String(self.symbol.to_string()));
         map.insert("amount".to_string(), Value::String(self.amount.clone()));
         map.insert(
             "address_to".to_string(),
@@ -136,6 +167,7 @@ impl WithdrawParams {
         map
     }
 
+    /// The signed subset of these parameters
     fn to_sign_params(&self) -> WithdrawSignParams {
         WithdrawSignParams {
             request_id: self.request_id.clone(),
@@ -159,23 +191,28 @@ pub struct WithdrawResponse {
     /// Withdrawal ID
     #[serde(default)]
     pub withdraw_id: Option<i64>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(WithdrawResponse);
+
 /// Withdrawal record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WithdrawRecord {
     /// Record ID
     #[serde(default)]
-    pub id: Option<i64>,
+    pub id: Option<RecordId>,
     /// Request ID
     #[serde(default)]
-    pub request_id: Option<String>,
+    pub request_id: Option<RequestId>,
     /// Sub-wallet ID
     #[serde(default)]
-    pub sub_wallet_id: Option<i64>,
+    pub sub_wallet_id: Option<SubWalletId>,
     /// Cryptocurrency symbol
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Base cryptocurrency symbol
     #[serde(default)]
     pub base_symbol: Option<String>,
@@ -195,14 +232,14 @@ pub struct WithdrawRecord {
     #[serde(default)]
     pub txid: Option<String>,
     /// Status
-    #[serde(default)]
-    pub status: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_optional_status")]
+    pub status: Option<MpcWithdrawStatus>,
     /// Creation time (timestamp in milliseconds)
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub created_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub created_at: Option<NumericTime>,
     /// Update time (timestamp in milliseconds)
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub updated_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub updated_at: Option<NumericTime>,
     /// Estimated miner fee
     #[serde(default, with = "rust_decimal::serde::str_option")]
     pub fee: Option<Decimal>,
@@ -227,16 +264,26 @@ pub struct WithdrawRecord {
     /// Withdrawal source (1: app, 2: openapi, 3: web, 10: collect, 11: collect-fee)
     #[serde(default)]
     pub withdraw_source: Option<i32>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(WithdrawRecord);
+
 /// Response for sync withdraw records
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncWithdrawRecordsResponse {
     /// List of withdrawal records
     #[serde(default)]
     pub list: Vec<WithdrawRecord>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(SyncWithdrawRecordsResponse, list);
+
 // ============================================================================
 // Withdraw API Implementation
 // ============================================================================
@@ -309,21 +356,19 @@ impl WithdrawApi {
 
         // Generate signature if needed
         if need_sign {
-            let sign_params = params.to_sign_params();
             let signature =
-                MpcSignUtil::generate_withdraw_sign(&sign_params, self.crypto_provider.as_ref())?;
-
+                MpcSignUtil::sign(&params.to_sign_params(), self.crypto_provider.as_ref())?;
             data.insert("sign".to_string(), Value::String(signature));
         }
 
-        let response = self.base.post("/api/mpc/billing/withdraw", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/api/mpc/billing/withdraw", Some(&data))
     }
 
     /// Gets transfer records
     ///
     /// # Arguments
-    /// * `request_ids` - Request IDs (list of strings, up to 100)
+    /// * `request_ids` - Request IDs (sent in batches of up to 100)
     ///
     /// # Returns
     /// Withdrawal records
@@ -340,13 +385,37 @@ impl WithdrawApi {
             .into());
         }
 
-        let mut data = HashMap::new();
-        data.insert("ids".to_string(), Value::String(request_ids.join(",")));
+        Ok(self
+            .lookup_withdraw_records(request_ids, LookupOptions::default())?
+            .found)
+    }
 
-        let response = self
-            .base
-            .get("/api/mpc/billing/withdraw_list", Some(&data))?;
-        self.base.validate_response(response)
+    /// Gets withdrawal records for any number of request IDs
+    ///
+    /// The IDs are sent in batches of up to 100 (see [`LookupOptions`]); the
+    /// result also lists the IDs no record was returned for.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = withdraw_api.lookup_withdraw_records(&ids, LookupOptions::new().with_concurrency(4))?;
+    /// println!("{} found, missing: {:?}", result.found.len(), result.missing);
+    /// ```
+    pub fn lookup_withdraw_records(
+        &self,
+        request_ids: &[&str],
+        options: LookupOptions,
+    ) -> Result<LookupResult<WithdrawRecord>> {
+        lookup(
+            request_ids,
+            options,
+            |chunk| {
+                let mut data = HashMap::new();
+                data.insert("ids".to_string(), Value::String(chunk.join(",")));
+                self.base
+                    .request("GET", "/api/mpc/billing/withdraw_list", Some(&data))
+            },
+            |record: &WithdrawRecord| record.request_id.as_ref().map(ToString::to_string),
+        )
     }
 
     /// Synchronizes transfer (withdraw) records
@@ -368,9 +437,50 @@ impl WithdrawApi {
         let mut data = HashMap::new();
         data.insert("max_id".to_string(), Value::Number(max_id.into()));
 
-        let response = self
-            .base
-            .get("/api/mpc/billing/sync_withdraw_list", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("GET", "/api/mpc/billing/sync_withdraw_list", Some(&data))
+    }
+
+    /// Iterates over all withdrawal records after `max_id`, fetching pages as needed
+    ///
+    /// # Example
+    /// ```ignore
+    /// for record in withdraw_api.sync_withdraw_records_iter(0).with_page_size(100) {
+    ///     println!("Record: {:?}", record?);
+    /// }
+    /// ```
+    pub fn sync_withdraw_records_iter(&self, max_id: i64) -> SyncIter<'_, WithdrawRecord> {
+        SyncIter::new(max_id, move |max_id| self.sync_withdraw_records(max_id))
+    }
+
+    /// Iterates over withdrawal records page by page, resuming from a stored checkpoint
+    ///
+    /// The checkpoint is keyed by the endpoint path and only advances when
+    /// a page is acknowledged; see [`CheckpointedSync`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut sync = withdraw_api.sync_withdraw_records_checkpointed(&store)?;
+    /// while let Some(page) = sync.next_page() {
+    ///     save_to_db(&page?)?;
+    ///     sync.ack()?;
+    /// }
+    /// ```
+    pub fn sync_withdraw_records_checkpointed<'a>(
+        &'a self,
+        store: &'a dyn CheckpointStore,
+    ) -> Result<CheckpointedSync<'a, WithdrawRecord>> {
+        CheckpointedSync::new(store, "/api/mpc/billing/sync_withdraw_list", |max_id| {
+            self.sync_withdraw_records_iter(max_id)
+        })
+    }
+
+    /// Async stream over all withdrawal records after `max_id`
+    ///
+    /// See [`SyncStream`] for how pages are fetched.
+    #[cfg(feature = "async")]
+    pub fn sync_withdraw_records_stream(&self, max_id: i64) -> SyncStream<WithdrawRecord> {
+        let api = Self::new(self.base.config.clone(), self.base.crypto_provider.clone());
+        SyncStream::new(max_id, move |max_id| api.sync_withdraw_records(max_id))
     }
 }
@@ -9,9 +9,12 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::crypto::CryptoProvider;
+use crate::decode::impl_unknown_fields;
 use crate::error::{Result, ValidationError};
+use crate::ids::Symbol;
 use crate::mpc::api::base_api::MpcBaseApi;
 use crate::mpc::config::MpcConfig;
+use crate::utils::amount::{decimals_from, AmountRules};
 use crate::utils::serde_helpers::deserialize_optional_i32;
 
 // ============================================================================
@@ -22,7 +25,7 @@ use crate::utils::serde_helpers::deserialize_optional_i32;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetCoinDetailsParams {
     /// Coin symbol (required)
-    pub symbol: String,
+    pub symbol: Symbol,
     /// Main chain symbol (optional, required for tokens)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub main_chain_symbol: Option<String>,
@@ -30,7 +33,7 @@ pub struct GetCoinDetailsParams {
 
 impl GetCoinDetailsParams {
     /// Creates new parameters with just symbol
-    pub fn new(symbol: impl Into<String>) -> Self {
+    pub fn new(symbol: impl Into<Symbol>) -> Self {
         Self {
             symbol: symbol.into(),
             main_chain_symbol: None,
@@ -39,7 +42,7 @@ impl GetCoinDetailsParams {
 
     /// Creates new parameters with symbol and main chain symbol
     pub fn with_main_chain(
-        symbol: impl Into<String>,
+        symbol: impl Into<Symbol>,
         main_chain_symbol: impl Into<String>,
     ) -> Self {
         Self {
@@ -50,7 +53,7 @@ impl GetCoinDetailsParams {
 
     fn to_map(&self) -> HashMap<String, Value> {
         let mut map = HashMap::new();
-        map.insert("symbol".to_string(), Value::String(self.symbol.clone()));
+        map.insert("symbol".to_string(), Value::String(self.symbol.to_string()));
         if let Some(ref main_chain_symbol) = self.main_chain_symbol {
             map.insert(
                 "main_chain_symbol".to_string(),
@@ -101,7 +104,7 @@ pub struct CoinDetails {
     pub coin_net: Option<String>,
     /// Coin symbol
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Real symbol
     #[serde(default)]
     pub real_symbol: Option<String>,
@@ -165,6 +168,26 @@ pub struct CoinDetails {
     /// Withdraw confirmation count
     #[serde(default)]
     pub withdraw_confirmation: Option<String>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl_unknown_fields!(CoinDetails);
+
+impl CoinDetails {
+    /// Precision and minimum to check withdrawal amounts against
+    ///
+    /// # Example
+    /// ```ignore
+    /// let amount = coin.withdraw_amount_rules().check(dec!(0.15))?;
+    /// ```
+    pub fn withdraw_amount_rules(&self) -> AmountRules {
+        AmountRules {
+            decimals: decimals_from(self.decimals),
+            min_amount: self.min_withdraw.as_deref().and_then(|m| m.parse().ok()),
+        }
+    }
 }
 
 /// Block height information
@@ -173,8 +196,13 @@ pub struct BlockHeightInfo {
     /// Latest block height (API returns "height")
     #[serde(default, alias = "height")]
     pub block_height: Option<i64>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(BlockHeightInfo);
+
 /// Supported coin/chain information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SupportedCoin {
@@ -183,7 +211,7 @@ pub struct SupportedCoin {
     pub coin_net: Option<String>,
     /// Coin symbol (e.g., "ETH", "BTC", "TRX")
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Whether memo is supported (0 = no, 1 = yes)
     #[serde(default)]
     pub is_support_memo: Option<i32>,
@@ -214,8 +242,13 @@ pub struct SupportedCoin {
     /// Display order (only in support_main_chain)
     #[serde(default)]
     pub display_order: Option<i32>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(SupportedCoin);
+
 /// Response for get supported coins
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetSupportedCoinsResponse {
@@ -225,8 +258,17 @@ pub struct GetSupportedCoinsResponse {
     /// List of supported main chains
     #[serde(default)]
     pub support_main_chain: Vec<SupportedCoin>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(
+    GetSupportedCoinsResponse,
+    open_main_chain,
+    support_main_chain
+);
+
 // ============================================================================
 // Workspace API Implementation
 // ============================================================================
@@ -267,8 +309,7 @@ impl WorkspaceApi {
         }
 
         let data = params.to_map();
-        let response = self.base.get("/api/mpc/coin_list", Some(&data))?;
-        self.base.validate_response(response)
+        self.base.request("GET", "/api/mpc/coin_list", Some(&data))
     }
 
     /// Gets the latest block height for a blockchain
@@ -293,8 +334,8 @@ impl WorkspaceApi {
         }
 
         let data = params.to_map();
-        let response = self.base.get("/api/mpc/chain_height", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("GET", "/api/mpc/chain_height", Some(&data))
     }
 
     /// Gets list of supported coins
@@ -310,7 +351,6 @@ impl WorkspaceApi {
     /// }
     /// ```
     pub fn get_supported_coins(&self) -> Result<GetSupportedCoinsResponse> {
-        let response = self.base.get("/api/mpc/wallet/open_coin", None)?;
-        self.base.validate_response(response)
+        self.base.request("GET", "/api/mpc/wallet/open_coin", None)
     }
 }
@@ -4,10 +4,14 @@
 
 use std::sync::Arc;
 
+use serde_json::Value;
+
 use crate::crypto::{CryptoProvider, RsaCryptoProvider};
+use crate::decode::DecodeMode;
+use crate::endpoint::Endpoint;
 use crate::error::Result;
 use crate::mpc::api::{
-    AutoSweepApi, DepositApi, NotifyApi, TronResourceApi, WalletApi, Web3Api,
+    AutoSweepApi, DepositApi, MpcBaseApi, NotifyApi, TronResourceApi, WalletApi, Web3Api,
     WithdrawApi, WorkspaceApi,
 };
 use crate::mpc::config::MpcConfig;
@@ -153,6 +157,75 @@ impl MpcClient {
         TronResourceApi::new(self.config.clone(), self.crypto_provider.clone())
     }
 
+    /// Shorthand for [`Self::get_wallet_api`]
+    pub fn wallet(&self) -> WalletApi {
+        self.get_wallet_api()
+    }
+
+    /// Shorthand for [`Self::get_deposit_api`]
+    pub fn deposit(&self) -> DepositApi {
+        self.get_deposit_api()
+    }
+
+    /// Shorthand for [`Self::get_withdraw_api`]
+    pub fn withdraw(&self) -> WithdrawApi {
+        self.get_withdraw_api()
+    }
+
+    /// Shorthand for [`Self::get_web3_api`]
+    pub fn web3(&self) -> Web3Api {
+        self.get_web3_api()
+    }
+
+    /// Shorthand for [`Self::get_auto_sweep_api`]
+    pub fn auto_sweep(&self) -> AutoSweepApi {
+        self.get_auto_sweep_api()
+    }
+
+    /// Shorthand for [`Self::get_notify_api`]
+    pub fn notify(&self) -> NotifyApi {
+        self.get_notify_api()
+    }
+
+    /// Shorthand for [`Self::get_workspace_api`]
+    pub fn workspace(&self) -> WorkspaceApi {
+        self.get_workspace_api()
+    }
+
+    /// Shorthand for [`Self::get_tron_resource_api`]
+    pub fn tron_resource(&self) -> TronResourceApi {
+        self.get_tron_resource_api()
+    }
+
+    /// Calls an [`Endpoint`] that has no dedicated API method yet
+    ///
+    /// Uses the same encryption and response validation as the wrapped APIs.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = client.call::<MyEndpoint>(&params)?;
+    /// ```
+    pub fn call<E: Endpoint>(&self, params: &E::Params) -> Result<E::Response> {
+        self.base_api().call::<E>(params)
+    }
+
+    /// Calls an API path with raw JSON parameters
+    ///
+    /// # Arguments
+    /// * `path` - API path
+    /// * `method` - `"POST"` or `"GET"`
+    /// * `params` - JSON object of request parameters (`Value::Null` for none)
+    ///
+    /// # Returns
+    /// The validated response `data` field
+    pub fn raw_request(&self, path: &str, method: &str, params: Value) -> Result<Value> {
+        self.base_api().raw_request(path, method, params)
+    }
+
+    fn base_api(&self) -> MpcBaseApi {
+        MpcBaseApi::new(self.config.clone(), self.crypto_provider.clone())
+    }
+
     /// Gets the configuration
     pub fn config(&self) -> &MpcConfig {
         &self.config
@@ -172,6 +245,7 @@ pub struct MpcClientBuilder {
     api_key: Option<String>,
     crypto_provider: Option<Arc<dyn CryptoProvider>>,
     debug: bool,
+    decode_mode: DecodeMode,
 }
 
 impl MpcClientBuilder {
@@ -228,6 +302,12 @@ impl MpcClientBuilder {
         self
     }
 
+    /// Sets how unknown response fields are reported (default: lenient)
+    pub fn set_decode_mode(mut self, decode_mode: DecodeMode) -> Self {
+        self.decode_mode = decode_mode;
+        self
+    }
+
     /// Builds the MpcClient instance
     ///
     /// # Returns
@@ -245,6 +325,7 @@ impl MpcClientBuilder {
             api_key: self.api_key.unwrap_or_default(),
             crypto_provider: self.crypto_provider,
             debug: self.debug,
+            decode_mode: self.decode_mode,
         };
 
         // Normalize domain URL
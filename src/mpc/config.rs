@@ -3,6 +3,7 @@
 //! Stores configuration parameters for MPC API client.
 
 use crate::crypto::CryptoProvider;
+use crate::decode::DecodeMode;
 use crate::error::{ConfigError, Result};
 use std::sync::Arc;
 
@@ -36,6 +37,8 @@ pub struct MpcConfig {
     pub crypto_provider: Option<Arc<dyn CryptoProvider>>,
     /// Enable debug mode
     pub debug: bool,
+    /// How unknown response fields are reported
+    pub decode_mode: DecodeMode,
 }
 
 impl MpcConfig {
@@ -59,6 +62,7 @@ impl MpcConfig {
             api_key: String::new(),
             crypto_provider: None,
             debug: false,
+            decode_mode: DecodeMode::default(),
         }
     }
 
@@ -73,6 +77,7 @@ impl MpcConfig {
             api_key: String::new(),
             crypto_provider: Some(crypto_provider),
             debug: false,
+            decode_mode: DecodeMode::default(),
         }
     }
 
@@ -132,6 +137,7 @@ impl Default for MpcConfig {
             api_key: String::new(),
             crypto_provider: None,
             debug: false,
+            decode_mode: DecodeMode::default(),
         }
     }
 }
@@ -0,0 +1,1043 @@
+//! Unified ledger feed over the MPC `sync_*` endpoints
+//!
+//! Deposits, withdrawals, Web3 transactions, auto-collect sweeps and Tron
+//! delegations each come from their own sync endpoint with their own record
+//! type. [`LedgerSync`] polls all of them and reports what changed as one
+//! list of [`LedgerEvent`]s:
+//!
+//! - [`LedgerEvent::Created`] the first time a record is seen
+//! - [`LedgerEvent::StatusChanged`] each time a known record moves to another
+//!   status, e.g. confirming → success or pending audit → failed
+//! - [`LedgerEvent::StatusRegressed`] when a record moves backwards, e.g.
+//!   success (2000) → failed (2400) or out of a final status
+//! - [`LedgerEvent::TxidChanged`] when a record's transaction hash changes,
+//!   which happens after chain reorganisations
+//! - [`LedgerEvent::IdGap`] for record IDs a sync skipped and that could not
+//!   be fetched again, when enabled with [`LedgerSync::with_gap_reports`]
+//!
+//! Sync endpoints only return records newer than their cursor, so records
+//! without a final status are remembered and looked up again on every poll
+//! until they reach one, and for a few polls after that
+//! ([`LedgerSync::with_recheck_polls`]) to catch regressions. Auto-collect
+//! and Tron delegation statuses have no documented code table, so their final
+//! statuses cannot be told apart; those records are reported once.
+//!
+//! Gap reports are off by default: record IDs can be shared across tenants,
+//! so one app's IDs are not necessarily consecutive. When enabled, IDs
+//! skipped by a sync are fetched again by ID ([`LedgerFeed::fetch_by_ids`]);
+//! the ones that still cannot be found are reported as gaps.
+//!
+//! Events of one poll are ordered by record time, so filtering on
+//! [`LedgerEvent::sub_wallet_id`] (or using [`LedgerSync::poll_by_sub_wallet`])
+//! gives each sub-wallet's events in order.
+//!
+//! # Example
+//! ```ignore
+//! let mut ledger = LedgerSync::new(&client);
+//! loop {
+//!     for event in ledger.poll()? {
+//!         accounting.apply(&event)?;
+//!     }
+//!     std::thread::sleep(Duration::from_secs(10));
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::error::Result;
+use crate::ids::SubWalletId;
+use crate::lookup::LookupOptions;
+use crate::mpc::api::types::{
+    AutoCollectRecord, DepositRecord, GetDepositRecordsParams, TronResourceRecord, Web3TransRecord,
+    WithdrawRecord,
+};
+use crate::mpc::MpcClient;
+use crate::pagination::{SyncIter, SyncRecord};
+use crate::timestamp::{AsTimestamp, Timestamp};
+
+/// Status codes from this value on are final
+///
+/// Holds for the deposit, withdrawal and Web3 status tables
+/// ([`MpcDepositStatus`](crate::enums::MpcDepositStatus),
+/// [`MpcWithdrawStatus`](crate::enums::MpcWithdrawStatus)): in-progress codes
+/// are 1xxx (pending audit, processing, confirming, ...) and final ones 2xxx
+/// (success, cancelled, rejected, failed).
+const FINAL_STATUS_CODE: i32 = 2000;
+
+/// Kind of MPC ledger record, one per sync endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LedgerKind {
+    /// Deposits (`sync_deposit_records`)
+    Deposit,
+    /// Withdrawals (`sync_withdraw_records`)
+    Withdraw,
+    /// Web3 transactions (`sync_web3_trans_records`)
+    Web3,
+    /// Auto-collect sweeps (`sync_auto_collect_records`)
+    AutoCollect,
+    /// Tron resource delegations (`sync_buy_resource_records`)
+    TronDelegate,
+}
+
+impl LedgerKind {
+    /// All kinds, in the order they are polled
+    pub const ALL: &'static [LedgerKind] = &[
+        LedgerKind::Deposit,
+        LedgerKind::Withdraw,
+        LedgerKind::Web3,
+        LedgerKind::AutoCollect,
+        LedgerKind::TronDelegate,
+    ];
+
+    /// Get the string representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LedgerKind::Deposit => "deposit",
+            LedgerKind::Withdraw => "withdraw",
+            LedgerKind::Web3 => "web3",
+            LedgerKind::AutoCollect => "auto_collect",
+            LedgerKind::TronDelegate => "tron_delegate",
+        }
+    }
+
+    /// Whether records of this kind can be looked up again after syncing
+    pub fn is_refreshable(&self) -> bool {
+        !matches!(self, LedgerKind::AutoCollect)
+    }
+
+    /// Whether the status codes of this kind have a documented table, telling
+    /// final statuses and regressions apart
+    ///
+    /// Auto-collect and Tron delegation statuses do not: their records are
+    /// never final, and are not watched for status changes.
+    pub fn has_status_table(&self) -> bool {
+        matches!(
+            self,
+            LedgerKind::Deposit | LedgerKind::Withdraw | LedgerKind::Web3
+        )
+    }
+
+    /// Whether records of this kind are looked up again until final
+    fn is_watchable(&self) -> bool {
+        self.is_refreshable() && self.has_status_table()
+    }
+}
+
+/// A record from any of the MPC sync endpoints
+#[derive(Debug, Clone)]
+pub enum LedgerRecord {
+    /// Deposit record
+    Deposit(DepositRecord),
+    /// Withdrawal record
+    Withdraw(WithdrawRecord),
+    /// Web3 transaction record
+    Web3(Web3TransRecord),
+    /// Auto-collect record
+    AutoCollect(AutoCollectRecord),
+    /// Tron resource delegation record
+    TronDelegate(TronResourceRecord),
+}
+
+impl LedgerRecord {
+    /// Kind of the record
+    pub fn kind(&self) -> LedgerKind {
+        match self {
+            LedgerRecord::Deposit(_) => LedgerKind::Deposit,
+            LedgerRecord::Withdraw(_) => LedgerKind::Withdraw,
+            LedgerRecord::Web3(_) => LedgerKind::Web3,
+            LedgerRecord::AutoCollect(_) => LedgerKind::AutoCollect,
+            LedgerRecord::TronDelegate(_) => LedgerKind::TronDelegate,
+        }
+    }
+
+    /// Record ID, unique within its kind
+    pub fn id(&self) -> Option<i64> {
+        match self {
+            LedgerRecord::Deposit(r) => r.sync_id(),
+            LedgerRecord::Withdraw(r) => r.sync_id(),
+            LedgerRecord::Web3(r) => r.sync_id(),
+            LedgerRecord::AutoCollect(r) => r.sync_id(),
+            LedgerRecord::TronDelegate(r) => r.sync_id(),
+        }
+    }
+
+    /// Sub-wallet the record belongs to; `None` for Tron delegations
+    pub fn sub_wallet_id(&self) -> Option<SubWalletId> {
+        match self {
+            LedgerRecord::Deposit(r) => r.sub_wallet_id,
+            LedgerRecord::Withdraw(r) => r.sub_wallet_id,
+            LedgerRecord::Web3(r) => r.sub_wallet_id,
+            LedgerRecord::AutoCollect(r) => Some(r.sub_wallet_id),
+            LedgerRecord::TronDelegate(_) => None,
+        }
+    }
+
+    /// Numeric status code
+    pub fn status_code(&self) -> Option<i32> {
+        match self {
+            LedgerRecord::Deposit(r) => r.status.map(|s| s.code()),
+            LedgerRecord::Withdraw(r) => r.status.map(|s| s.code()),
+            LedgerRecord::Web3(r) => r.status.map(|s| s.code()),
+            LedgerRecord::AutoCollect(r) => r.status.and_then(|s| i32::try_from(s).ok()),
+            LedgerRecord::TronDelegate(r) => r.status,
+        }
+    }
+
+    /// Whether the record will not change status any more
+    ///
+    /// Always `false` for kinds without a status table; see
+    /// [`LedgerKind::has_status_table`].
+    pub fn is_final(&self) -> bool {
+        self.kind().has_status_table()
+            && self
+                .status_code()
+                .is_some_and(|code| code >= FINAL_STATUS_CODE)
+    }
+
+    /// Time of the record's last change
+    ///
+    /// `updated_at`, falling back to `created_at`; for Tron delegations the
+    /// latest of the delegation and reclaim times.
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        match self {
+            LedgerRecord::Deposit(r) => r.updated_at.as_timestamp().or(r.created_at.as_timestamp()),
+            LedgerRecord::Withdraw(r) => {
+                r.updated_at.as_timestamp().or(r.created_at.as_timestamp())
+            }
+            LedgerRecord::Web3(r) => r.updated_at.as_timestamp().or(r.created_at.as_timestamp()),
+            LedgerRecord::AutoCollect(r) => {
+                r.updated_at.as_timestamp().or(r.created_at.as_timestamp())
+            }
+            LedgerRecord::TronDelegate(r) => [
+                r.energy_time,
+                r.net_time,
+                r.reclaim_energy_time,
+                r.reclaim_net_time,
+            ]
+            .iter()
+            .filter_map(AsTimestamp::as_timestamp)
+            .max(),
+        }
+    }
+
+    /// On-chain transaction hash
+    ///
+    /// For Tron delegations the energy delegation hash, falling back to the
+    /// bandwidth one.
+    pub fn txid(&self) -> Option<&str> {
+        match self {
+            LedgerRecord::Deposit(r) => r.txid.as_deref(),
+            LedgerRecord::Withdraw(r) => r.txid.as_deref(),
+            LedgerRecord::Web3(r) => r.txid.as_deref(),
+            LedgerRecord::AutoCollect(r) => r.txid.as_deref(),
+            LedgerRecord::TronDelegate(r) => r.energy_txid.as_deref().or(r.net_txid.as_deref()),
+        }
+    }
+
+    /// Request ID used to look the record up again, if its kind uses one
+    fn request_id(&self) -> Option<&str> {
+        match self {
+            LedgerRecord::Withdraw(r) => r.request_id.as_deref(),
+            LedgerRecord::Web3(r) => r.request_id.as_deref(),
+            LedgerRecord::TronDelegate(r) => r.request_id.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl SyncRecord for LedgerRecord {
+    fn sync_id(&self) -> Option<i64> {
+        self.id()
+    }
+}
+
+/// A change to the MPC ledger
+#[derive(Debug, Clone)]
+pub enum LedgerEvent {
+    /// A record seen for the first time
+    Created(LedgerRecord),
+    /// A known record moved from status `from` to its current status
+    StatusChanged {
+        /// The record, as it is now
+        record: LedgerRecord,
+        /// Status code before the change
+        from: Option<i32>,
+    },
+    /// A known record moved back from status `from`
+    ///
+    /// Either it left a final status (e.g. success → failed) or it went to a
+    /// lower in-progress status. The record is watched again if it is no
+    /// longer final.
+    StatusRegressed {
+        /// The record, as it is now
+        record: LedgerRecord,
+        /// Status code before the change
+        from: Option<i32>,
+    },
+    /// A known record's transaction hash changed, e.g. after a reorg
+    TxidChanged {
+        /// The record, as it is now
+        record: LedgerRecord,
+        /// Transaction hash before the change
+        previous_txid: String,
+    },
+    /// Record IDs `first_id..=last_id` were skipped by the sync and could not
+    /// be fetched by ID; see [`LedgerSync::with_gap_reports`]
+    IdGap {
+        /// Kind of the missing records
+        kind: LedgerKind,
+        /// First missing ID
+        first_id: i64,
+        /// Last missing ID
+        last_id: i64,
+    },
+}
+
+impl LedgerEvent {
+    /// The record the event is about; `None` for [`LedgerEvent::IdGap`]
+    pub fn record(&self) -> Option<&LedgerRecord> {
+        match self {
+            LedgerEvent::Created(record)
+            | LedgerEvent::StatusChanged { record, .. }
+            | LedgerEvent::StatusRegressed { record, .. }
+            | LedgerEvent::TxidChanged { record, .. } => Some(record),
+            LedgerEvent::IdGap { .. } => None,
+        }
+    }
+
+    /// Consumes the event, returning its record
+    pub fn into_record(self) -> Option<LedgerRecord> {
+        match self {
+            LedgerEvent::Created(record)
+            | LedgerEvent::StatusChanged { record, .. }
+            | LedgerEvent::StatusRegressed { record, .. }
+            | LedgerEvent::TxidChanged { record, .. } => Some(record),
+            LedgerEvent::IdGap { .. } => None,
+        }
+    }
+
+    /// Kind of the record
+    pub fn kind(&self) -> LedgerKind {
+        match self {
+            LedgerEvent::Created(record)
+            | LedgerEvent::StatusChanged { record, .. }
+            | LedgerEvent::StatusRegressed { record, .. }
+            | LedgerEvent::TxidChanged { record, .. } => record.kind(),
+            LedgerEvent::IdGap { kind, .. } => *kind,
+        }
+    }
+
+    /// Sub-wallet the record belongs to
+    pub fn sub_wallet_id(&self) -> Option<SubWalletId> {
+        self.record().and_then(LedgerRecord::sub_wallet_id)
+    }
+
+    /// Status code before the event, for status events
+    pub fn previous_status(&self) -> Option<i32> {
+        match self {
+            LedgerEvent::StatusChanged { from, .. } | LedgerEvent::StatusRegressed { from, .. } => {
+                *from
+            }
+            _ => None,
+        }
+    }
+
+    /// Status code after the event
+    pub fn status(&self) -> Option<i32> {
+        self.record().and_then(LedgerRecord::status_code)
+    }
+
+    /// Whether the event is an anomaly to look into rather than progress
+    pub fn is_anomaly(&self) -> bool {
+        matches!(
+            self,
+            LedgerEvent::StatusRegressed { .. }
+                | LedgerEvent::TxidChanged { .. }
+                | LedgerEvent::IdGap { .. }
+        )
+    }
+
+    fn sort_key(&self) -> (Option<Timestamp>, LedgerKind, Option<i64>) {
+        match self {
+            LedgerEvent::IdGap { kind, first_id, .. } => (None, *kind, Some(*first_id)),
+            _ => {
+                let record = self.record();
+                (
+                    record.and_then(LedgerRecord::timestamp),
+                    self.kind(),
+                    record.and_then(LedgerRecord::id),
+                )
+            }
+        }
+    }
+}
+
+/// Source of ledger records for [`LedgerSync`]
+///
+/// Implemented for [`MpcClient`]; other implementations are mainly useful
+/// in tests.
+pub trait LedgerFeed {
+    /// Returns one page of records of `kind` with an ID greater than `max_id`
+    fn sync(&self, kind: LedgerKind, max_id: i64) -> Result<Vec<LedgerRecord>>;
+
+    /// Looks up the current state of `records`, which are all of `kind`
+    ///
+    /// Records that cannot be found may be left out of the result.
+    fn refresh(&self, kind: LedgerKind, records: &[&LedgerRecord]) -> Result<Vec<LedgerRecord>>;
+
+    /// Fetches records of `kind` by record ID, to fill gaps in a sync
+    ///
+    /// Unknown IDs are left out of the result. The default finds nothing, so
+    /// every gap is reported.
+    fn fetch_by_ids(&self, _kind: LedgerKind, _ids: &[i64]) -> Result<Vec<LedgerRecord>> {
+        Ok(Vec::new())
+    }
+}
+
+impl<F: LedgerFeed + ?Sized> LedgerFeed for &F {
+    fn sync(&self, kind: LedgerKind, max_id: i64) -> Result<Vec<LedgerRecord>> {
+        (**self).sync(kind, max_id)
+    }
+
+    fn refresh(&self, kind: LedgerKind, records: &[&LedgerRecord]) -> Result<Vec<LedgerRecord>> {
+        (**self).refresh(kind, records)
+    }
+
+    fn fetch_by_ids(&self, kind: LedgerKind, ids: &[i64]) -> Result<Vec<LedgerRecord>> {
+        (**self).fetch_by_ids(kind, ids)
+    }
+}
+
+impl LedgerFeed for MpcClient {
+    fn sync(&self, kind: LedgerKind, max_id: i64) -> Result<Vec<LedgerRecord>> {
+        Ok(match kind {
+            LedgerKind::Deposit => wrap(
+                self.deposit().sync_deposit_records(max_id)?,
+                LedgerRecord::Deposit,
+            ),
+            LedgerKind::Withdraw => wrap(
+                self.withdraw().sync_withdraw_records(max_id)?,
+                LedgerRecord::Withdraw,
+            ),
+            LedgerKind::Web3 => wrap(
+                self.web3().sync_web3_trans_records(max_id)?,
+                LedgerRecord::Web3,
+            ),
+            LedgerKind::AutoCollect => wrap(
+                self.auto_sweep().sync_auto_collect_records(max_id)?,
+                LedgerRecord::AutoCollect,
+            ),
+            LedgerKind::TronDelegate => wrap(
+                self.tron_resource().sync_buy_resource_records(max_id)?,
+                LedgerRecord::TronDelegate,
+            ),
+        })
+    }
+
+    fn refresh(&self, kind: LedgerKind, records: &[&LedgerRecord]) -> Result<Vec<LedgerRecord>> {
+        let options = LookupOptions::default();
+        let request_ids: Vec<&str> = records.iter().filter_map(|r| r.request_id()).collect();
+        Ok(match kind {
+            LedgerKind::Deposit => {
+                let ids: Vec<i64> = records.iter().filter_map(|r| r.id()).collect();
+                self.fetch_by_ids(kind, &ids)?
+            }
+            LedgerKind::Withdraw => wrap(
+                self.withdraw()
+                    .lookup_withdraw_records(&request_ids, options)?
+                    .found,
+                LedgerRecord::Withdraw,
+            ),
+            LedgerKind::Web3 => wrap(
+                self.web3()
+                    .lookup_web3_trans_records(&request_ids, options)?
+                    .found,
+                LedgerRecord::Web3,
+            ),
+            LedgerKind::TronDelegate => wrap(
+                self.tron_resource()
+                    .lookup_buy_resource_records(&request_ids, options)?
+                    .found,
+                LedgerRecord::TronDelegate,
+            ),
+            LedgerKind::AutoCollect => Vec::new(),
+        })
+    }
+
+    /// Only deposits can be looked up by record ID; the other endpoints take
+    /// request IDs, which skipped records are not known by
+    fn fetch_by_ids(&self, kind: LedgerKind, ids: &[i64]) -> Result<Vec<LedgerRecord>> {
+        if kind != LedgerKind::Deposit || ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let params = GetDepositRecordsParams::new(ids.iter().copied());
+        Ok(wrap(
+            self.deposit()
+                .lookup_deposit_records(params, LookupOptions::default())?
+                .found,
+            LedgerRecord::Deposit,
+        ))
+    }
+}
+
+fn wrap<T>(records: Vec<T>, variant: fn(T) -> LedgerRecord) -> Vec<LedgerRecord> {
+    records.into_iter().map(variant).collect()
+}
+
+/// Number of polls a record is looked up again after reaching a final status
+const DEFAULT_RECHECK_POLLS: u32 = 3;
+
+/// Longest ID gap that is fetched again by ID; longer gaps are only reported
+const DEFAULT_GAP_FETCH_LIMIT: usize = 1000;
+
+/// Polls the MPC sync endpoints and reports changes as [`LedgerEvent`]s
+///
+/// Cursors and the records still waiting for a final status live in memory.
+/// To resume after a restart, restore the cursors with
+/// [`with_cursor`](Self::with_cursor) and the unfinished records with
+/// [`track`](Self::track).
+pub struct LedgerSync<F> {
+    feed: F,
+    kinds: Vec<LedgerKind>,
+    cursors: HashMap<LedgerKind, i64>,
+    report_gaps: bool,
+    gap_fetch_limit: usize,
+    tracker: Tracker,
+}
+
+impl<F: LedgerFeed> LedgerSync<F> {
+    /// Creates a sync over every [`LedgerKind`], starting from `max_id` 0
+    pub fn new(feed: F) -> Self {
+        Self {
+            feed,
+            kinds: LedgerKind::ALL.to_vec(),
+            cursors: HashMap::new(),
+            report_gaps: false,
+            gap_fetch_limit: DEFAULT_GAP_FETCH_LIMIT,
+            tracker: Tracker {
+                watched: HashMap::new(),
+                events: Vec::new(),
+                polls: 0,
+                recheck_polls: DEFAULT_RECHECK_POLLS,
+            },
+        }
+    }
+
+    /// Limits the sync to `kinds`
+    pub fn with_kinds(mut self, kinds: &[LedgerKind]) -> Self {
+        self.kinds = kinds.to_vec();
+        self
+    }
+
+    /// Starts syncing `kind` after `max_id`
+    pub fn with_cursor(mut self, kind: LedgerKind, max_id: i64) -> Self {
+        self.cursors.insert(kind, max_id);
+        self
+    }
+
+    /// Sets how many polls final records are still looked up for (default 3)
+    ///
+    /// 0 stops watching records as soon as they are final, which saves
+    /// lookups but misses regressions and txid changes.
+    pub fn with_recheck_polls(mut self, polls: u32) -> Self {
+        self.tracker.recheck_polls = polls;
+        self
+    }
+
+    /// Reports record IDs skipped by a sync as [`LedgerEvent::IdGap`]s (off
+    /// by default)
+    ///
+    /// Only meaningful when the app's record IDs are consecutive, which does
+    /// not hold when IDs are shared across tenants. [`MpcClient`] can only
+    /// fetch deposits by ID, so for the other kinds every skipped ID is
+    /// reported.
+    pub fn with_gap_reports(mut self, enabled: bool) -> Self {
+        self.report_gaps = enabled;
+        self
+    }
+
+    /// Sets the longest ID gap fetched again by ID (default 1000)
+    ///
+    /// Longer gaps are reported as a single [`LedgerEvent::IdGap`].
+    pub fn with_gap_fetch_limit(mut self, limit: usize) -> Self {
+        self.gap_fetch_limit = limit;
+        self
+    }
+
+    /// The `max_id` the next poll of `kind` starts after
+    pub fn cursor(&self, kind: LedgerKind) -> i64 {
+        self.cursors.get(&kind).copied().unwrap_or(0)
+    }
+
+    /// Watches a record for status changes without reporting it as created
+    ///
+    /// Records that are already final, cannot be looked up, or have no
+    /// status table ([`LedgerKind::has_status_table`]) are ignored.
+    pub fn track(&mut self, record: LedgerRecord) {
+        if let Some(id) = record.id() {
+            if !record.is_final() && record.kind().is_watchable() {
+                self.tracker.watched.insert(
+                    (record.kind(), id),
+                    Watched {
+                        record,
+                        final_since: None,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Records still waiting for a final status
+    pub fn pending(&self) -> impl Iterator<Item = &LedgerRecord> {
+        self.tracker
+            .watched
+            .values()
+            .map(|watched| &watched.record)
+            .filter(|record| !record.is_final())
+    }
+
+    /// Looks up watched records, fetches new ones and returns the changes
+    ///
+    /// Events are ordered by record time, with gaps first. If a request
+    /// fails, the error is returned and the events found before it are kept
+    /// for the next poll.
+    pub fn poll(&mut self) -> Result<Vec<LedgerEvent>> {
+        for kind in self.kinds.clone() {
+            // Refresh first, so records created by this poll are not looked
+            // up straight away
+            self.refresh_kind(kind)?;
+            self.sync_kind(kind)?;
+        }
+        self.tracker.finish_poll();
+
+        let mut events = std::mem::take(&mut self.tracker.events);
+        events.sort_by_key(LedgerEvent::sort_key);
+        Ok(events)
+    }
+
+    /// Like [`poll`](Self::poll), with the events split per sub-wallet
+    ///
+    /// Tron delegations and ID gaps belong to no sub-wallet and are listed
+    /// under `None`.
+    pub fn poll_by_sub_wallet(
+        &mut self,
+    ) -> Result<BTreeMap<Option<SubWalletId>, Vec<LedgerEvent>>> {
+        let mut by_wallet: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for event in self.poll()? {
+            by_wallet
+                .entry(event.sub_wallet_id())
+                .or_default()
+                .push(event);
+        }
+        Ok(by_wallet)
+    }
+
+    fn sync_kind(&mut self, kind: LedgerKind) -> Result<()> {
+        let feed = &self.feed;
+        let mut last_id = self.cursor(kind);
+        let mut pages = SyncIter::new(last_id, |max_id| feed.sync(kind, max_id));
+        while let Some(page) = pages.next_page() {
+            let mut page = page?;
+            page.sort_by_key(LedgerRecord::id);
+            for record in page {
+                if let Some(id) = record.id() {
+                    // A cursor of 0 means nothing was synced yet, so there is
+                    // no previous ID to compare with
+                    if self.report_gaps && last_id > 0 && id > last_id + 1 {
+                        fill_gap(
+                            feed,
+                            &mut self.tracker,
+                            kind,
+                            (last_id + 1, id - 1),
+                            self.gap_fetch_limit,
+                        );
+                    }
+                    last_id = last_id.max(id);
+                }
+                self.tracker.observe(record);
+            }
+            self.cursors.insert(kind, pages.max_id());
+        }
+        Ok(())
+    }
+
+    fn refresh_kind(&mut self, kind: LedgerKind) -> Result<()> {
+        if !kind.is_refreshable() {
+            return Ok(());
+        }
+        let records: Vec<&LedgerRecord> = self
+            .tracker
+            .watched
+            .values()
+            .map(|watched| &watched.record)
+            .filter(|record| record.kind() == kind)
+            .collect();
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        for record in self.feed.refresh(kind, &records)? {
+            self.tracker.observe(record);
+        }
+        Ok(())
+    }
+}
+
+/// Fetches the records of a skipped ID range and reports what is still missing
+///
+/// Ranges longer than `limit` are reported as one gap without being fetched.
+/// A failed fetch is logged and the whole range reported, so one bad lookup
+/// does not hold up the sync.
+fn fill_gap(
+    feed: &impl LedgerFeed,
+    tracker: &mut Tracker,
+    kind: LedgerKind,
+    (first_id, last_id): (i64, i64),
+    limit: usize,
+) {
+    let gap = |first_id, last_id| LedgerEvent::IdGap {
+        kind,
+        first_id,
+        last_id,
+    };
+    // IDs can be shared across tenants, so a skipped range may be far too
+    // long to list
+    if usize::try_from(last_id - first_id).map_or(true, |span| span >= limit) {
+        tracker.events.push(gap(first_id, last_id));
+        return;
+    }
+
+    let ids: Vec<i64> = (first_id..=last_id).collect();
+    let mut found = HashSet::new();
+    match feed.fetch_by_ids(kind, &ids) {
+        Ok(records) => {
+            for record in records {
+                found.extend(record.id());
+                tracker.observe(record);
+            }
+        }
+        Err(e) => log::warn!(
+            "Could not fetch {} records {}..={}: {}",
+            kind.as_str(),
+            first_id,
+            last_id,
+            e
+        ),
+    }
+
+    let mut missing = ids.into_iter().filter(|id| !found.contains(id)).peekable();
+    while let Some(first_id) = missing.next() {
+        let mut last_id = first_id;
+        while missing.next_if_eq(&(last_id + 1)).is_some() {
+            last_id += 1;
+        }
+        tracker.events.push(gap(first_id, last_id));
+    }
+}
+
+/// A record looked up again on every poll
+struct Watched {
+    record: LedgerRecord,
+    /// Poll in which the record reached its current final status
+    final_since: Option<u64>,
+}
+
+/// Known record states and the events found since the last poll returned
+struct Tracker {
+    watched: HashMap<(LedgerKind, i64), Watched>,
+    events: Vec<LedgerEvent>,
+    polls: u64,
+    recheck_polls: u32,
+}
+
+impl Tracker {
+    /// Compares a fetched record with what is known about it and records the
+    /// events
+    fn observe(&mut self, record: LedgerRecord) {
+        let Some(id) = record.id() else {
+            self.events.push(LedgerEvent::Created(record));
+            return;
+        };
+        let key = (record.kind(), id);
+
+        let Some(known) = self.watched.remove(&key) else {
+            self.watch(key, &record, None);
+            self.events.push(LedgerEvent::Created(record));
+            return;
+        };
+
+        let from = known.record.status_code();
+        let to = record.status_code();
+        let final_since = known.final_since.filter(|_| from == to);
+        self.watch(key, &record, final_since);
+
+        if let (Some(previous), Some(current)) = (known.record.txid(), record.txid()) {
+            if previous != current {
+                self.events.push(LedgerEvent::TxidChanged {
+                    record: record.clone(),
+                    previous_txid: previous.to_string(),
+                });
+            }
+        }
+        if from != to {
+            self.events.push(if is_regression(key.0, from, to) {
+                LedgerEvent::StatusRegressed { record, from }
+            } else {
+                LedgerEvent::StatusChanged { record, from }
+            });
+        }
+    }
+
+    /// Keeps looking `record` up if it is not final or only recently final
+    fn watch(&mut self, key: (LedgerKind, i64), record: &LedgerRecord, final_since: Option<u64>) {
+        if !record.kind().is_watchable() {
+            return;
+        }
+        let final_since = match (record.is_final(), final_since) {
+            (false, _) => None,
+            (true, Some(poll)) => Some(poll),
+            (true, None) => Some(self.polls),
+        };
+        if final_since.is_some() && self.recheck_polls == 0 {
+            return;
+        }
+        self.watched.insert(
+            key,
+            Watched {
+                record: record.clone(),
+                final_since,
+            },
+        );
+    }
+
+    /// Stops watching records that have been final for long enough
+    fn finish_poll(&mut self) {
+        let (polls, recheck_polls) = (self.polls, u64::from(self.recheck_polls));
+        self.watched.retain(|_, watched| {
+            watched
+                .final_since
+                .is_none_or(|since| polls - since < recheck_polls)
+        });
+        self.polls += 1;
+    }
+}
+
+/// Whether moving from status `from` to `to` goes backwards
+///
+/// Final statuses never change, and in-progress ones only go up. Kinds
+/// without a status table never regress, and neither does a record whose
+/// status is missing from a response (reported as a plain change).
+fn is_regression(kind: LedgerKind, from: Option<i32>, to: Option<i32>) -> bool {
+    if !kind.has_status_table() {
+        return false;
+    }
+    match (from, to) {
+        (Some(from), Some(to)) if from >= FINAL_STATUS_CODE => to != from,
+        (Some(from), Some(to)) => to < from,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    struct FakeFeed {
+        records: RefCell<Vec<LedgerRecord>>,
+        /// Records left out of `sync` but found by `fetch_by_ids`
+        skipped: Vec<LedgerRecord>,
+    }
+
+    impl LedgerFeed for FakeFeed {
+        fn sync(&self, kind: LedgerKind, max_id: i64) -> Result<Vec<LedgerRecord>> {
+            Ok(self
+                .records
+                .borrow()
+                .iter()
+                .filter(|r| r.kind() == kind && r.id().unwrap() > max_id)
+                .cloned()
+                .collect())
+        }
+
+        fn refresh(
+            &self,
+            kind: LedgerKind,
+            records: &[&LedgerRecord],
+        ) -> Result<Vec<LedgerRecord>> {
+            let ids: Vec<_> = records.iter().map(|r| r.id()).collect();
+            Ok(self
+                .records
+                .borrow()
+                .iter()
+                .filter(|r| r.kind() == kind && ids.contains(&r.id()))
+                .cloned()
+                .collect())
+        }
+
+        fn fetch_by_ids(&self, kind: LedgerKind, ids: &[i64]) -> Result<Vec<LedgerRecord>> {
+            Ok(self
+                .skipped
+                .iter()
+                .filter(|r| r.kind() == kind && ids.contains(&r.id().unwrap()))
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn deposit(status: i32, updated_at: i64) -> LedgerRecord {
+        LedgerRecord::Deposit(
+            serde_json::from_value(serde_json::json!({
+                "id": 1, "sub_wallet_id": 7, "status": status, "updated_at": updated_at
+            }))
+            .unwrap(),
+        )
+    }
+
+    fn withdraw(status: i32, updated_at: i64) -> LedgerRecord {
+        LedgerRecord::Withdraw(
+            serde_json::from_value(serde_json::json!({
+                "id": 5, "request_id": "w-5", "sub_wallet_id": 7, "status": status,
+                "updated_at": updated_at
+            }))
+            .unwrap(),
+        )
+    }
+
+    fn deposit_with_id(id: i64, status: i32, txid: &str) -> LedgerRecord {
+        LedgerRecord::Deposit(
+            serde_json::from_value(serde_json::json!({
+                "id": id, "sub_wallet_id": 7, "status": status, "txid": txid,
+                "updated_at": 1_700_000_000_000i64 + id
+            }))
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_ledger_sync_reports_status_transitions() {
+        let feed = FakeFeed {
+            records: RefCell::new(vec![
+                deposit(1900, 1_700_000_002_000),
+                withdraw(1000, 1_700_000_001_000),
+            ]),
+            skipped: Vec::new(),
+        };
+        let mut ledger = LedgerSync::new(&feed);
+
+        let events = ledger.poll().unwrap();
+        let kinds: Vec<_> = events
+            .iter()
+            .map(|e| (e.kind(), e.previous_status(), e.status()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (LedgerKind::Withdraw, None, Some(1000)),
+                (LedgerKind::Deposit, None, Some(1900)),
+            ]
+        );
+        assert_eq!(ledger.cursor(LedgerKind::Deposit), 1);
+        assert_eq!(ledger.pending().count(), 2);
+
+        *feed.records.borrow_mut() = vec![
+            deposit(2000, 1_700_000_003_000),
+            withdraw(2400, 1_700_000_004_000),
+        ];
+        let events = ledger.poll().unwrap();
+        let kinds: Vec<_> = events
+            .iter()
+            .map(|e| (e.kind(), e.previous_status(), e.status()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (LedgerKind::Deposit, Some(1900), Some(2000)),
+                (LedgerKind::Withdraw, Some(1000), Some(2400)),
+            ]
+        );
+        assert_eq!(ledger.pending().count(), 0);
+        assert!(ledger.poll().unwrap().is_empty());
+
+        // Tron delegation codes are not documented, so 2000 means nothing
+        let tron = LedgerRecord::TronDelegate(
+            serde_json::from_value(serde_json::json!({"id": 9, "status": 2000})).unwrap(),
+        );
+        assert!(!tron.is_final());
+        assert!(!is_regression(
+            LedgerKind::TronDelegate,
+            Some(2000),
+            Some(1000)
+        ));
+        assert!(is_regression(LedgerKind::Withdraw, Some(2000), Some(1000)));
+        assert!(!is_regression(LedgerKind::Withdraw, Some(2000), None));
+    }
+
+    #[test]
+    fn test_ledger_sync_reports_gaps_and_regressions() {
+        let feed = FakeFeed {
+            records: RefCell::new(vec![deposit_with_id(1, 2000, "0xa")]),
+            skipped: vec![deposit_with_id(3, 2000, "0xc")],
+        };
+        let mut ledger = LedgerSync::new(&feed)
+            .with_kinds(&[LedgerKind::Deposit])
+            .with_gap_reports(true);
+        let mut quiet = LedgerSync::new(&feed).with_kinds(&[LedgerKind::Deposit]);
+        assert_eq!(ledger.poll().unwrap().len(), 1);
+        assert_eq!(quiet.poll().unwrap().len(), 1);
+
+        // IDs 2 to 4 were skipped; only 3 can be fetched by ID
+        feed.records.borrow_mut().extend([
+            deposit_with_id(5, 1900, "0xe"),
+            deposit_with_id(7, 1900, "0xg"),
+        ]);
+        assert_eq!(quiet.poll().unwrap().len(), 2);
+        let events = ledger.poll().unwrap();
+        let gaps: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                LedgerEvent::IdGap {
+                    first_id, last_id, ..
+                } => Some((*first_id, *last_id)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(gaps, vec![(2, 2), (4, 4), (6, 6)]);
+        let created: Vec<_> = events
+            .iter()
+            .filter(|e| matches!(e, LedgerEvent::Created(_)))
+            .filter_map(|e| e.record().and_then(LedgerRecord::id))
+            .collect();
+        assert_eq!(created, vec![3, 5, 7]);
+
+        // Record 1 is final but still rechecked: a reorg replaces its
+        // transaction and the deposit fails
+        feed.records.borrow_mut()[0] = deposit_with_id(1, 2400, "0xb");
+        let events = ledger.poll().unwrap();
+        assert!(events.iter().all(LedgerEvent::is_anomaly));
+        assert!(matches!(
+            &events[..],
+            [
+                LedgerEvent::TxidChanged { previous_txid, .. },
+                LedgerEvent::StatusRegressed { from: Some(2000), .. },
+            ] if previous_txid == "0xa"
+        ));
+
+        // A gap too long to fetch is reported whole, without listing its IDs
+        feed.records
+            .borrow_mut()
+            .push(deposit_with_id(5_000_000_000, 1900, "0xz"));
+        let events = ledger.poll().unwrap();
+        assert!(matches!(
+            &events[..],
+            [
+                LedgerEvent::IdGap {
+                    first_id: 8,
+                    last_id: 4_999_999_999,
+                    ..
+                },
+                LedgerEvent::Created(_),
+            ]
+        ));
+    }
+}
@@ -5,8 +5,9 @@
 pub mod api;
 mod client;
 mod config;
+pub mod ledger;
 mod sign_util;
 
 pub use client::{MpcClient, MpcClientBuilder};
 pub use config::MpcConfig;
-pub use sign_util::MpcSignUtil;
+pub use sign_util::{MpcSignUtil, SignableRequest, Web3SignParams, WithdrawSignParams};
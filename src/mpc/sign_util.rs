@@ -1,18 +1,92 @@
 //! MPC Sign Utility
 //!
-//! Provides signature generation for MPC transactions.
+//! Provides signature generation and verification for MPC transactions.
 
 use std::collections::BTreeMap;
 
-use crate::crypto::CryptoProvider;
+use serde_json::value::RawValue;
+
+use crate::crypto::{CryptoProvider, RsaCryptoProvider};
 use crate::error::Result;
+use crate::ids::{RequestId, SubWalletId, Symbol};
+
+/// A request whose parameters are signed with the MPC sign private key
+///
+/// Implementors only declare which fields are signed; the canonical string
+/// (sorting, empty filtering, `k=v&` joining and lowercasing) is always built
+/// by [`MpcSignUtil::canonical_string`], so every signed endpoint follows the
+/// same rules.
+pub trait SignableRequest {
+    /// Returns the signed fields as `(name, value)` pairs
+    ///
+    /// `name` must match the request parameter name sent to ChainUp.
+    /// `None` and empty values do not participate in signing.
+    fn signed_fields(&self) -> Vec<(&'static str, Option<String>)>;
+}
 
 /// MPC Sign Utility
 ///
-/// Provides signature generation for MPC transactions.
+/// Provides signature generation and verification for MPC transactions.
 pub struct MpcSignUtil;
 
 impl MpcSignUtil {
+    /// Builds the canonical sign string for a request
+    ///
+    /// - Parameters are sorted by key in ASCII ascending order
+    /// - Missing and empty values are filtered out
+    /// - Pairs are joined as `k1=v1&k2=v2`
+    /// - The whole string is lowercased
+    ///
+    /// Useful for debugging signature mismatches against ChainUp's expectation.
+    pub fn canonical_string<R: SignableRequest + ?Sized>(request: &R) -> String {
+        let sign_map: BTreeMap<&str, String> = request
+            .signed_fields()
+            .into_iter()
+            .filter_map(|(k, v)| v.map(|v| (k, v)))
+            .collect();
+
+        Self::build_sign_string(&sign_map).to_lowercase()
+    }
+
+    /// Signs a request with the crypto provider's sign key
+    ///
+    /// # Arguments
+    /// * `request` - Request to sign
+    /// * `crypto_provider` - Crypto provider for signing
+    ///
+    /// # Returns
+    /// Base64 encoded signature
+    pub fn sign<R: SignableRequest + ?Sized>(
+        request: &R,
+        crypto_provider: &dyn CryptoProvider,
+    ) -> Result<String> {
+        crypto_provider.sign(&Self::canonical_string(request))
+    }
+
+    /// Verifies a request signature
+    ///
+    /// Rebuilds the canonical sign string from `request` and checks it against
+    /// `signature` using the MD5 → SHA256 scheme of [`Self::sign`].
+    ///
+    /// # Arguments
+    /// * `request` - Request parameters as stored
+    /// * `signature` - Base64 encoded signature to check
+    /// * `public_key` - Public key of the signing key pair (PEM or raw base64)
+    ///
+    /// # Returns
+    /// True if the signature was made by the matching sign private key
+    pub fn verify<R: SignableRequest + ?Sized>(
+        request: &R,
+        signature: &str,
+        public_key: &str,
+    ) -> Result<bool> {
+        RsaCryptoProvider::verify_sign_with_key(
+            public_key,
+            &Self::canonical_string(request),
+            signature,
+        )
+    }
+
     /// Generates signature for withdrawal transaction
     ///
     /// # Arguments
@@ -25,27 +99,24 @@ impl MpcSignUtil {
         params: &WithdrawSignParams,
         crypto_provider: &dyn CryptoProvider,
     ) -> Result<String> {
-        // Build sign string from params in sorted order (BTreeMap auto-sorts by key ASCII ascending)
-        // Empty values will be filtered out and not participate in signing
-        let mut sign_map: BTreeMap<&str, String> = BTreeMap::new();
-        sign_map.insert("request_id", params.request_id.clone());
-        sign_map.insert("sub_wallet_id", params.sub_wallet_id.to_string());
-        sign_map.insert("symbol", params.symbol.clone());
-        sign_map.insert("address_to", params.address_to.clone());
-        sign_map.insert("amount", params.amount.clone());
-
-        if let Some(ref memo) = params.memo {
-            sign_map.insert("memo", memo.clone());
-        }
-        if let Some(ref outputs) = params.outputs {
-            sign_map.insert("outputs", outputs.clone());
-        }
-
-        // Build sign string
-        let sign_string = Self::build_sign_string(&sign_map).to_lowercase();
+        Self::sign(params, crypto_provider)
+    }
 
-        // Sign the string
-        crypto_provider.sign(&sign_string)
+    /// Verifies a withdrawal signature
+    ///
+    /// # Arguments
+    /// * `params` - Transaction parameters as stored
+    /// * `signature` - Base64 encoded signature to check
+    /// * `public_key` - Public key of the signing key pair (PEM or raw base64)
+    ///
+    /// # Returns
+    /// True if the signature was made by the matching sign private key
+    pub fn verify_withdraw_sign(
+        params: &WithdrawSignParams,
+        signature: &str,
+        public_key: &str,
+    ) -> Result<bool> {
+        Self::verify(params, signature, public_key)
     }
 
     /// Generates signature for Web3 transaction
@@ -60,21 +131,62 @@ impl MpcSignUtil {
         params: &Web3SignParams,
         crypto_provider: &dyn CryptoProvider,
     ) -> Result<String> {
-        // Build sign string from params in sorted order (BTreeMap auto-sorts by key ASCII ascending)
-        // Empty values will be filtered out and not participate in signing
-        let mut sign_map: BTreeMap<&str, String> = BTreeMap::new();
-        sign_map.insert("request_id", params.request_id.clone());
-        sign_map.insert("sub_wallet_id", params.sub_wallet_id.to_string());
-        sign_map.insert("main_chain_symbol", params.main_chain_symbol.clone());
-        sign_map.insert("interactive_contract", params.interactive_contract.clone());
-        sign_map.insert("amount", params.amount.clone());
-        sign_map.insert("input_data", params.input_data.clone());
-
-        // Build sign string
-        let sign_string = Self::build_sign_string(&sign_map).to_lowercase();
-
-        // Sign the string
-        crypto_provider.sign(&sign_string)
+        Self::sign(params, crypto_provider)
+    }
+
+    /// Verifies a Web3 transaction signature
+    ///
+    /// # Arguments
+    /// * `params` - Transaction parameters as stored
+    /// * `signature` - Base64 encoded signature to check
+    /// * `public_key` - Public key of the signing key pair (PEM or raw base64)
+    ///
+    /// # Returns
+    /// True if the signature was made by the matching sign private key
+    pub fn verify_web3_sign(
+        params: &Web3SignParams,
+        signature: &str,
+        public_key: &str,
+    ) -> Result<bool> {
+        Self::verify(params, signature, public_key)
+    }
+
+    /// Builds the canonical sign string for a decrypted notification
+    ///
+    /// Every top-level field of the JSON object `decrypted` except `sign`
+    /// participates, following the rules of [`Self::canonical_string`].
+    /// Non-string values are signed exactly as written in `decrypted`, so
+    /// `1.50` stays `1.50`; `null` values are skipped like missing ones.
+    pub fn notification_canonical_string(decrypted: &str) -> Result<String> {
+        let payload: BTreeMap<String, Box<RawValue>> = serde_json::from_str(decrypted)?;
+        let mut sign_map = BTreeMap::new();
+        for (key, raw) in &payload {
+            let value = match raw.get() {
+                "null" => continue,
+                text if text.starts_with('"') => serde_json::from_str::<String>(text)?,
+                text => text.to_string(),
+            };
+            if key != "sign" {
+                sign_map.insert(key.as_str(), value);
+            }
+        }
+
+        Ok(Self::build_sign_string(&sign_map).to_lowercase())
+    }
+
+    /// Verifies the `sign` field of a decrypted notification
+    ///
+    /// # Arguments
+    /// * `decrypted` - Decrypted notification JSON, including `sign`
+    /// * `signature` - The `sign` value to verify
+    /// * `public_key` - Public key the notification was signed with
+    pub fn verify_notification_sign(
+        decrypted: &str,
+        signature: &str,
+        public_key: &str,
+    ) -> Result<bool> {
+        let sign_string = Self::notification_canonical_string(decrypted)?;
+        RsaCryptoProvider::verify_sign_with_key(public_key, &sign_string, signature)
     }
 
     /// Builds a sign string from sorted parameters
@@ -95,11 +207,11 @@ impl MpcSignUtil {
 #[derive(Debug, Clone)]
 pub struct WithdrawSignParams {
     /// Request ID
-    pub request_id: String,
+    pub request_id: RequestId,
     /// Sub-wallet ID
-    pub sub_wallet_id: i64,
+    pub sub_wallet_id: SubWalletId,
     /// Coin symbol
-    pub symbol: String,
+    pub symbol: Symbol,
     /// Destination address
     pub address_to: String,
     /// Amount
@@ -114,9 +226,9 @@ pub struct WithdrawSignParams {
 #[derive(Debug, Clone)]
 pub struct Web3SignParams {
     /// Request ID
-    pub request_id: String,
+    pub request_id: RequestId,
     /// Sub-wallet ID
-    pub sub_wallet_id: i64,
+    pub sub_wallet_id: SubWalletId,
     /// Main chain symbol
     pub main_chain_symbol: String,
     /// Interactive contract address
@@ -126,3 +238,81 @@ pub struct Web3SignParams {
     /// Input data
     pub input_data: String,
 }
+
+impl SignableRequest for WithdrawSignParams {
+    fn signed_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("request_id", Some(self.request_id.to_string())),
+            ("sub_wallet_id", Some(self.sub_wallet_id.to_string())),
+            ("symbol", Some(self.symbol.to_string())),
+            ("address_to", Some(self.address_to.clone())),
+            ("amount", Some(self.amount.clone())),
+            ("memo", self.memo.clone()),
+            ("outputs", self.outputs.clone()),
+        ]
+    }
+}
+
+impl SignableRequest for Web3SignParams {
+    fn signed_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("request_id", Some(self.request_id.to_string())),
+            ("sub_wallet_id", Some(self.sub_wallet_id.to_string())),
+            ("main_chain_symbol", Some(self.main_chain_symbol.clone())),
+            (
+                "interactive_contract",
+                Some(self.interactive_contract.clone()),
+            ),
+            ("amount", Some(self.amount.clone())),
+            ("input_data", Some(self.input_data.clone())),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::keygen::RsaKeyPair;
+
+    fn withdraw_params() -> WithdrawSignParams {
+        WithdrawSignParams {
+            request_id: "req-1".into(),
+            sub_wallet_id: SubWalletId(1000537),
+            symbol: "ETH".into(),
+            address_to: "0xAbC".to_string(),
+            amount: "0.1".to_string(),
+            memo: None,
+            outputs: None,
+        }
+    }
+
+    #[test]
+    fn test_canonical_string() {
+        let mut params = withdraw_params();
+        params.memo = Some(String::new());
+        assert_eq!(
+            MpcSignUtil::canonical_string(&params),
+            "address_to=0xabc&amount=0.1&request_id=req-1&sub_wallet_id=1000537&symbol=eth"
+        );
+    }
+
+    #[test]
+    fn test_verify_withdraw_sign_roundtrip() {
+        let pair = RsaKeyPair::generate_with_bits(1024).unwrap();
+        let provider = RsaCryptoProvider::new(None, None, Some(pair.private_key_pem())).unwrap();
+
+        let params = withdraw_params();
+        let signature = MpcSignUtil::generate_withdraw_sign(&params, &provider).unwrap();
+        assert!(
+            MpcSignUtil::verify_withdraw_sign(&params, &signature, &pair.public_key_base64())
+                .unwrap()
+        );
+
+        let mut tampered = params;
+        tampered.amount = "1.1".to_string();
+        assert!(
+            !MpcSignUtil::verify_withdraw_sign(&tampered, &signature, pair.public_key_pem())
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Checks on decrypted notifications
+//!
+//! Decrypting a notification only proves it was encrypted with the ChainUp
+//! key; it does not prove the notification is meant for this app, or that it
+//! is not an old delivery replayed later. [`NotifyValidation`] adds those
+//! checks, all optional:
+//!
+//! - `app_id` must match the configured app ID
+//! - `notify_time` must fall within a window around the local clock (MPC
+//!   only; WaaS notifications carry no delivery time)
+//! - when the notification carries a `sign` field, it must verify against the
+//!   configured public key
+//!
+//! Attach it with `with_validation` on the MPC `NotifyApi` or the WaaS
+//! `AsyncNotifyApi`; every failure is returned as
+//! [`ChainUpError::Signature`](crate::error::ChainUpError::Signature).
+//!
+//! # Example
+//! ```ignore
+//! let validation = NotifyValidation::for_mpc(&config)
+//!     .with_window(Duration::from_secs(600));
+//! let notify_api = client.get_notify_api().with_validation(validation);
+//! ```
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::{Map, Value};
+
+use crate::error::{Result, SignatureError};
+use crate::mpc::{MpcConfig, MpcSignUtil};
+use crate::timestamp::Timestamp;
+use crate::utils::serde_helpers::deserialize_optional_timestamp;
+
+/// Optional checks run on every decrypted notification
+///
+/// [`NotifyValidation::new`] checks nothing; enable each check with its
+/// `with_*` method.
+#[derive(Debug, Clone, Default)]
+pub struct NotifyValidation {
+    app_id: Option<String>,
+    window: Option<Duration>,
+    sign_public_key: Option<String>,
+}
+
+impl NotifyValidation {
+    /// Creates a validation that checks nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a validation for MPC notifications
+    ///
+    /// Checks `app_id` against `config.app_id` and verifies `sign` with
+    /// `config.waas_public_key`. The replay window stays off; set it with
+    /// [`Self::with_window`].
+    pub fn for_mpc(config: &MpcConfig) -> Self {
+        Self::new()
+            .with_app_id(config.app_id.clone())
+            .with_sign_verification(config.waas_public_key.clone())
+    }
+
+    /// Rejects notifications whose `app_id` is missing or differs
+    pub fn with_app_id(mut self, app_id: impl Into<String>) -> Self {
+        self.app_id = Some(app_id.into());
+        self
+    }
+
+    /// Rejects notifications whose `notify_time` is more than `window` away
+    /// from now
+    ///
+    /// The window applies both ways, so it also absorbs clock skew.
+    /// Notifications without `notify_time` are rejected.
+    ///
+    /// MPC only: WaaS notifications have no delivery time (their
+    /// `created_at` / `updated_at` belong to the record, and stay the same on
+    /// ChainUp's retries), so `AsyncNotifyApi::with_validation` ignores the
+    /// window.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    /// Verifies the `sign` field, when present, with `public_key`
+    pub fn with_sign_verification(mut self, public_key: impl Into<String>) -> Self {
+        self.sign_public_key = Some(public_key.into());
+        self
+    }
+
+    /// Drops the replay window, keeping the other checks
+    pub(crate) fn without_window(mut self) -> Self {
+        self.window = None;
+        self
+    }
+
+    /// Whether a replay window is set
+    pub(crate) fn has_window(&self) -> bool {
+        self.window.is_some()
+    }
+
+    /// Checks a decrypted notification, given as its JSON text
+    pub fn check(&self, decrypted: &str) -> Result<()> {
+        self.check_at(decrypted, now())
+    }
+
+    fn check_at(&self, decrypted: &str, now: Timestamp) -> Result<()> {
+        let payload: Map<String, Value> = serde_json::from_str(decrypted)?;
+        if let Some(expected) = &self.app_id {
+            match payload.get("app_id").and_then(Value::as_str) {
+                Some(app_id) if app_id == expected => {}
+                Some(app_id) => {
+                    return Err(rejected(format!(
+                        "Notification addressed to app_id {:?}, expected {:?}",
+                        app_id, expected
+                    )))
+                }
+                None => return Err(rejected("Notification has no app_id")),
+            }
+        }
+
+        if let Some(window) = self.window {
+            let time = notification_time(&payload)
+                .ok_or_else(|| rejected("Notification has no notify_time"))?;
+            let age = now.as_millis().abs_diff(time.as_millis());
+            if u128::from(age) > window.as_millis() {
+                return Err(rejected(format!(
+                    "Notification time {} is outside the {}s replay window",
+                    time.as_millis(),
+                    window.as_secs()
+                )));
+            }
+        }
+
+        if let Some(public_key) = &self.sign_public_key {
+            let sign = payload.get("sign").and_then(Value::as_str).unwrap_or("");
+            if !sign.is_empty()
+                && !MpcSignUtil::verify_notification_sign(decrypted, sign, public_key)?
+            {
+                return Err(rejected("Notification sign does not match"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn notification_time(payload: &Map<String, Value>) -> Option<Timestamp> {
+    payload
+        .get("notify_time")
+        .and_then(|value| deserialize_optional_timestamp(value).ok().flatten())
+}
+
+fn rejected(message: impl Into<String>) -> crate::error::ChainUpError {
+    SignatureError::new(message).into()
+}
+
+fn now() -> Timestamp {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    Timestamp::from_millis(millis as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::keygen::RsaKeyPair;
+    use crate::crypto::{CryptoProvider, RsaCryptoProvider};
+    use crate::error::ChainUpError;
+
+    #[test]
+    fn test_rejects_foreign_stale_and_forged_notifications() {
+        let pair = RsaKeyPair::generate_with_bits(1024).unwrap();
+        let signer = RsaCryptoProvider::new(None, None, Some(pair.private_key_pem())).unwrap();
+        let now = Timestamp::from_millis(1_700_000_000_000);
+
+        // Numbers are signed as sent, not as serde_json would print them
+        let fields = concat!(
+            r#""app_id":"app-1","id":11,"side":"deposit","amount":1.50,"#,
+            r#""fee":0.00000001,"charset":"utf-8","notify_time":1700000030000,"memo":null"#
+        );
+        let sign_string =
+            MpcSignUtil::notification_canonical_string(&format!("{{{}}}", fields)).unwrap();
+        assert_eq!(
+            sign_string,
+            "amount=1.50&app_id=app-1&charset=utf-8&fee=0.00000001&id=11\
+             &notify_time=1700000030000&side=deposit"
+        );
+        let sign = signer.sign(&sign_string).unwrap();
+        let signed = |fields: &str| format!(r#"{{{},"sign":"{}"}}"#, fields, sign);
+
+        let validation = NotifyValidation::new()
+            .with_app_id("app-1")
+            .with_window(Duration::from_secs(60))
+            .with_sign_verification(pair.public_key_base64());
+        validation.check_at(&signed(fields), now).unwrap();
+
+        let stale = Timestamp::from_millis(now.as_millis() + 91_000);
+        let err = validation.check_at(&signed(fields), stale).unwrap_err();
+        assert!(matches!(err, ChainUpError::Signature(_)), "{}", err);
+
+        // Record times are not delivery times
+        let waas = r#"{"app_id":"app-1","updated_at":1700000030000}"#;
+        let err = validation.check_at(waas, now).unwrap_err();
+        assert!(err.to_string().contains("notify_time"), "{}", err);
+
+        let foreign = signed(&fields.replace("app-1", "app-2"));
+        let err = validation.check_at(&foreign, now).unwrap_err();
+        assert!(err.to_string().contains("app-2"), "{}", err);
+
+        let forged = fields.replace("1.50", "15");
+        let err = validation.check_at(&signed(&forged), now).unwrap_err();
+        assert!(matches!(err, ChainUpError::Signature(_)), "{}", err);
+
+        // Unsigned notifications only go through the other checks
+        validation
+            .check_at(&format!("{{{}}}", forged), now)
+            .unwrap();
+    }
+}
@@ -0,0 +1,357 @@
+//! Auto-paginating adapters for the `sync_*` endpoints
+//!
+//! Every `sync_*` endpoint returns one page of records with an ID greater
+//! than `max_id`. [`SyncIter`] (blocking) and `SyncStream` (async, behind
+//! the `async` feature) call the endpoint again with the largest ID of the
+//! previous page until an empty page comes back.
+//!
+//! Both adapters only fetch the next page once every record of the current
+//! page has been consumed, so a slow consumer never has more than one page
+//! buffered.
+//!
+//! # Example
+//! ```ignore
+//! for record in withdraw_api.sync_withdraw_records_iter(last_seen_id).with_page_size(100) {
+//!     let record = record?;
+//!     store(&record)?;
+//! }
+//! ```
+
+use std::collections::VecDeque;
+
+use crate::error::{Result, ValidationError};
+use crate::ids::{RecordId, Uid};
+
+/// A record returned by a `sync_*` endpoint
+pub trait SyncRecord {
+    /// The ID that `max_id` cursors refer to, if present
+    fn sync_id(&self) -> Option<i64>;
+}
+
+macro_rules! impl_sync_record {
+    ($($ty:ty => $field:ident),* $(,)?) => {
+        $(
+            impl SyncRecord for $ty {
+                fn sync_id(&self) -> Option<i64> {
+                    SyncId::sync_id(&self.$field)
+                }
+            }
+        )*
+    };
+}
+
+/// ID field types that can act as a cursor
+trait SyncId {
+    fn sync_id(&self) -> Option<i64>;
+}
+
+impl SyncId for RecordId {
+    fn sync_id(&self) -> Option<i64> {
+        Some(self.value())
+    }
+}
+
+impl SyncId for Uid {
+    fn sync_id(&self) -> Option<i64> {
+        Some(self.value())
+    }
+}
+
+impl<T: SyncId> SyncId for Option<T> {
+    fn sync_id(&self) -> Option<i64> {
+        self.as_ref().and_then(SyncId::sync_id)
+    }
+}
+
+impl_sync_record!(
+    crate::mpc::api::types::WithdrawRecord => id,
+    crate::mpc::api::types::DepositRecord => id,
+    crate::mpc::api::types::Web3TransRecord => id,
+    crate::mpc::api::types::AutoCollectRecord => id,
+    crate::mpc::api::types::TronResourceRecord => id,
+    crate::waas::api::types::UserInfo => uid,
+    crate::waas::api::types::UserAddressInfo => id,
+    crate::waas::api::types::WithdrawRecord => id,
+    crate::waas::api::types::DepositRecord => id,
+    crate::waas::api::types::MinerFeeRecord => id,
+    crate::waas::api::types::TransferRecord => id,
+);
+
+/// Cursor state shared by the blocking and async adapters
+#[derive(Debug)]
+struct Cursor<T> {
+    max_id: i64,
+    page_size: Option<usize>,
+    buffer: VecDeque<T>,
+    done: bool,
+}
+
+impl<T: SyncRecord> Cursor<T> {
+    fn new(max_id: i64) -> Self {
+        Self {
+            max_id,
+            page_size: None,
+            buffer: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Buffers a fetched page and advances the cursor past it
+    fn accept(&mut self, page: Vec<T>) -> Result<()> {
+        if page.is_empty() {
+            self.done = true;
+            return Ok(());
+        }
+        if self.page_size.is_some_and(|size| page.len() < size) {
+            self.done = true;
+        }
+
+        let mut next = self.max_id;
+        for record in &page {
+            let id = record.sync_id().ok_or_else(|| {
+                ValidationError::new("Sync record has no ID; cannot advance the max_id cursor")
+            })?;
+            next = next.max(id);
+        }
+        // A page that does not move the cursor would be fetched forever
+        if next == self.max_id {
+            self.done = true;
+        }
+        self.max_id = next;
+        self.buffer.extend(page);
+        Ok(())
+    }
+}
+
+/// Blocking iterator over every record of a `sync_*` endpoint
+///
+/// Yields `Err` once and then stops if a page request fails.
+pub struct SyncIter<'a, T> {
+    fetch: Box<dyn FnMut(i64) -> Result<Vec<T>> + 'a>,
+    cursor: Cursor<T>,
+}
+
+impl<'a, T: SyncRecord> SyncIter<'a, T> {
+    /// Creates an iterator that fetches pages with `fetch`, starting after `max_id`
+    pub fn new(max_id: i64, fetch: impl FnMut(i64) -> Result<Vec<T>> + 'a) -> Self {
+        Self {
+            fetch: Box::new(fetch),
+            cursor: Cursor::new(max_id),
+        }
+    }
+
+    /// Sets the endpoint's page size
+    ///
+    /// A page shorter than this is taken as the last one, which saves the
+    /// final request that would return an empty page.
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.cursor.page_size = Some(page_size).filter(|&size| size > 0);
+        self
+    }
+
+    /// The `max_id` to resume from: the largest ID fetched so far
+    ///
+    /// Records still buffered are included, so only persist this once they
+    /// have all been consumed.
+    pub fn max_id(&self) -> i64 {
+        self.cursor.max_id
+    }
+
+    /// Returns the next whole page instead of single records
+    ///
+    /// Records already buffered by [`Iterator::next`] come back first.
+    /// Returns `None` when the endpoint is exhausted.
+    pub fn next_page(&mut self) -> Option<Result<Vec<T>>> {
+        if self.cursor.buffer.is_empty() {
+            if let Err(e) = self.fetch_page()? {
+                return Some(Err(e));
+            }
+            if self.cursor.buffer.is_empty() {
+                return None;
+            }
+        }
+        Some(Ok(self.cursor.buffer.drain(..).collect()))
+    }
+
+    /// Fetches the next page into the buffer
+    ///
+    /// Returns `None` if the endpoint is already exhausted. A failed request
+    /// ends the iteration.
+    fn fetch_page(&mut self) -> Option<Result<()>> {
+        if self.cursor.done {
+            return None;
+        }
+        let result = (self.fetch)(self.cursor.max_id).and_then(|page| self.cursor.accept(page));
+        if result.is_err() {
+            self.cursor.done = true;
+        }
+        Some(result)
+    }
+}
+
+impl<T: SyncRecord> Iterator for SyncIter<'_, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cursor.buffer.is_empty() {
+            if let Err(e) = self.fetch_page()? {
+                return Some(Err(e));
+            }
+        }
+        self.cursor.buffer.pop_front().map(Ok)
+    }
+}
+
+#[cfg(feature = "async")]
+pub use self::stream::SyncStream;
+
+#[cfg(feature = "async")]
+mod stream {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+
+    use futures_core::Stream;
+    use tokio::task::JoinHandle;
+
+    use super::{Cursor, SyncRecord};
+    use crate::error::{ChainUpError, NetworkError, Result};
+
+    type Fetch<T> = Arc<dyn Fn(i64) -> Result<Vec<T>> + Send + Sync>;
+
+    /// Async stream over every record of a `sync_*` endpoint
+    ///
+    /// Pages are fetched with the blocking HTTP client on tokio's blocking
+    /// thread pool, one page at a time and only when the stream is polled
+    /// with an empty buffer. Must be polled inside a tokio runtime.
+    pub struct SyncStream<T> {
+        fetch: Fetch<T>,
+        cursor: Cursor<T>,
+        pending: Option<JoinHandle<Result<Vec<T>>>>,
+    }
+
+    impl<T: SyncRecord + Send + 'static> SyncStream<T> {
+        /// Creates a stream that fetches pages with `fetch`, starting after `max_id`
+        ///
+        /// `fetch` is kept behind an `Arc` and shared by every page fetch, so
+        /// anything it owns (such as the API client) is built once.
+        pub fn new(
+            max_id: i64,
+            fetch: impl Fn(i64) -> Result<Vec<T>> + Send + Sync + 'static,
+        ) -> Self {
+            Self {
+                fetch: Arc::new(fetch),
+                cursor: Cursor::new(max_id),
+                pending: None,
+            }
+        }
+
+        /// Sets the endpoint's page size; see [`super::SyncIter::with_page_size`]
+        pub fn with_page_size(mut self, page_size: usize) -> Self {
+            self.cursor.page_size = Some(page_size).filter(|&size| size > 0);
+            self
+        }
+
+        /// The `max_id` to resume from: the largest ID fetched so far
+        pub fn max_id(&self) -> i64 {
+            self.cursor.max_id
+        }
+    }
+
+    impl<T> Unpin for SyncStream<T> {}
+
+    impl<T: SyncRecord + Send + 'static> Stream for SyncStream<T> {
+        type Item = Result<T>;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            loop {
+                if let Some(record) = self.cursor.buffer.pop_front() {
+                    return Poll::Ready(Some(Ok(record)));
+                }
+                if self.cursor.done {
+                    return Poll::Ready(None);
+                }
+
+                let this = &mut *self;
+                let handle = this.pending.get_or_insert_with(|| {
+                    let fetch = this.fetch.clone();
+                    let max_id = this.cursor.max_id;
+                    tokio::task::spawn_blocking(move || fetch(max_id))
+                });
+
+                let page = match Pin::new(handle).poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(joined) => joined.unwrap_or_else(|e| {
+                        Err(ChainUpError::from(NetworkError::new(format!(
+                            "Sync page task failed: {}",
+                            e
+                        ))))
+                    }),
+                };
+                this.pending = None;
+
+                if let Err(e) = page.and_then(|page| this.cursor.accept(page)) {
+                    this.cursor.done = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpc::api::types::AutoCollectRecord;
+
+    fn record(id: i64) -> AutoCollectRecord {
+        serde_json::from_value(serde_json::json!({ "id": id })).unwrap()
+    }
+
+    #[test]
+    fn test_sync_iter_pages_until_short_page() {
+        let mut requested = Vec::new();
+        let ids: Vec<i64> = SyncIter::new(10, |max_id| {
+            requested.push(max_id);
+            Ok(match max_id {
+                10 => vec![record(11), record(12)],
+                12 => vec![record(13)],
+                _ => vec![],
+            })
+        })
+        .with_page_size(2)
+        .map(|r| r.unwrap().id.value())
+        .collect();
+
+        assert_eq!(ids, vec![11, 12, 13]);
+        // The short second page ends iteration without an extra request
+        assert_eq!(requested, vec![10, 12]);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_sync_stream() {
+        use futures_core::Stream;
+        use std::pin::Pin;
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let ids = runtime.block_on(async {
+            let mut stream = SyncStream::new(0, |max_id| {
+                Ok(if max_id == 0 {
+                    vec![record(1), record(2)]
+                } else {
+                    vec![]
+                })
+            });
+            let mut ids = Vec::new();
+            while let Some(record) =
+                std::future::poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await
+            {
+                ids.push(record.unwrap().id.value());
+            }
+            ids
+        });
+        assert_eq!(ids, vec![1, 2]);
+    }
+}
@@ -0,0 +1,562 @@
+//! Reconciliation of an internal ledger against ChainUp records
+//!
+//! [`Reconciliation`] pairs the transactions your ledger expects
+//! ([`ExpectedTransaction`]) with ChainUp's deposit, withdrawal and transfer
+//! records ([`ChainUpTransaction`]) and sorts every entry into a
+//! [`ReconciliationReport`]: matched, missing on our side, missing at
+//! ChainUp, amount mismatch and status mismatch. Amounts are compared as
+//! `Decimal` values, so `1.5` and `1.50` agree and nothing is rounded.
+//!
+//! Entries are paired on kind and reference: the `request_id` for
+//! withdrawals and transfers, and the `txid` for deposits, which have no
+//! request ID at ChainUp. When several records share a reference, the one
+//! with the expected address is preferred.
+//!
+//! ChainUp records can be added directly or fetched with
+//! [`Reconciliation::fetch_mpc`] / [`Reconciliation::fetch_waas`], which run
+//! the sync endpoints and then look up expected withdrawals and transfers
+//! the sync did not return.
+//!
+//! Statuses are compared as [`SettlementStatus`]. MPC statuses are
+//! classified by the SDK; WaaS records only report a status when an audit
+//! rejected them, so set [`ChainUpTransaction::status`] yourself to compare
+//! other WaaS statuses.
+//!
+//! # Example
+//! ```ignore
+//! let mut recon = Reconciliation::new()
+//!     .with_window(Timestamp::parse("2024-05-01").unwrap(), Timestamp::parse("2024-05-02").unwrap());
+//! recon.add_expected(ExpectedTransaction::new(
+//!     ReconcileKind::Withdraw, "wd-1001", "12.5".parse()?, "USDT",
+//! ));
+//! recon.fetch_mpc(&client, SyncStart::default())?;
+//! let report = recon.run();
+//! println!("{}", report.to_json_pretty()?);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::enums::WaasAuditStatus;
+use crate::error::Result;
+use crate::lookup::LookupOptions;
+use crate::mpc::MpcClient;
+use crate::timestamp::{AsTimestamp, Timestamp};
+use crate::waas::api::types::GetAccountTransferListParams;
+use crate::waas::WaasClient;
+
+/// Kind of transaction being reconciled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReconcileKind {
+    /// Incoming deposit, referenced by `txid`
+    Deposit,
+    /// Withdrawal, referenced by `request_id`
+    Withdraw,
+    /// Internal account transfer, referenced by `request_id`
+    Transfer,
+}
+
+/// Whether a transaction has settled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettlementStatus {
+    /// Still in progress
+    Pending,
+    /// Completed successfully
+    Success,
+    /// Ended without moving funds (failed, cancelled or rejected)
+    Failed,
+}
+
+/// A transaction as recorded in the internal ledger
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectedTransaction {
+    /// Transaction kind
+    pub kind: ReconcileKind,
+    /// `request_id` for withdrawals and transfers, `txid` for deposits
+    pub reference: String,
+    /// Amount
+    pub amount: Decimal,
+    /// Coin symbol, compared case-insensitively
+    pub symbol: String,
+    /// Destination address; not compared when `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// Status the ledger holds; not compared when `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<SettlementStatus>,
+}
+
+impl ExpectedTransaction {
+    /// Creates an expected transaction without address or status
+    pub fn new(
+        kind: ReconcileKind,
+        reference: impl Into<String>,
+        amount: Decimal,
+        symbol: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            reference: reference.into(),
+            amount,
+            symbol: symbol.into(),
+            address: None,
+            status: None,
+        }
+    }
+
+    /// Sets the destination address
+    pub fn with_address(mut self, address: impl Into<String>) -> Self {
+        self.address = Some(address.into());
+        self
+    }
+
+    /// Sets the expected status
+    pub fn with_status(mut self, status: SettlementStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+}
+
+/// A ChainUp record, reduced to the fields that are reconciled
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainUpTransaction {
+    /// Transaction kind
+    pub kind: ReconcileKind,
+    /// `request_id` for withdrawals and transfers, `txid` for deposits
+    pub reference: Option<String>,
+    /// ChainUp record ID
+    pub record_id: Option<i64>,
+    /// Amount
+    pub amount: Option<Decimal>,
+    /// Coin symbol
+    pub symbol: Option<String>,
+    /// Destination address (for transfers, the receiving user ID)
+    pub address: Option<String>,
+    /// Settlement status, when it could be classified
+    pub status: Option<SettlementStatus>,
+    /// Raw ChainUp status code
+    pub status_code: Option<i32>,
+    /// Creation time
+    pub created_at: Option<Timestamp>,
+}
+
+fn settlement(is_success: bool, is_failed: bool) -> SettlementStatus {
+    if is_success {
+        SettlementStatus::Success
+    } else if is_failed {
+        SettlementStatus::Failed
+    } else {
+        SettlementStatus::Pending
+    }
+}
+
+impl From<&crate::mpc::api::types::DepositRecord> for ChainUpTransaction {
+    fn from(r: &crate::mpc::api::types::DepositRecord) -> Self {
+        Self {
+            kind: ReconcileKind::Deposit,
+            reference: r.txid.clone(),
+            record_id: r.id.map(|id| id.value()),
+            amount: r.amount,
+            symbol: r.symbol.as_ref().map(ToString::to_string),
+            address: r.address_to.clone(),
+            status: r.status.map(|s| settlement(s.is_success(), s.is_failed())),
+            status_code: r.status.map(|s| s.code()),
+            created_at: r.created_at.as_timestamp(),
+        }
+    }
+}
+
+impl From<&crate::mpc::api::types::WithdrawRecord> for ChainUpTransaction {
+    fn from(r: &crate::mpc::api::types::WithdrawRecord) -> Self {
+        Self {
+            kind: ReconcileKind::Withdraw,
+            reference: r.request_id.as_ref().map(ToString::to_string),
+            record_id: r.id.map(|id| id.value()),
+            amount: r.amount,
+            symbol: r.symbol.as_ref().map(ToString::to_string),
+            address: r.address_to.clone(),
+            status: r.status.map(|s| settlement(s.is_success(), s.is_failed())),
+            status_code: r.status.map(|s| s.code()),
+            created_at: r.created_at.as_timestamp(),
+        }
+    }
+}
+
+impl From<&crate::waas::api::types::DepositRecord> for ChainUpTransaction {
+    fn from(r: &crate::waas::api::types::DepositRecord) -> Self {
+        Self {
+            kind: ReconcileKind::Deposit,
+            reference: r.txid.clone(),
+            record_id: r.id.map(|id| id.value()),
+            amount: r.amount,
+            symbol: r.symbol.as_ref().map(ToString::to_string),
+            address: r.address_to.clone(),
+            status: None,
+            status_code: r.status,
+            created_at: r.created_at.as_timestamp(),
+        }
+    }
+}
+
+impl From<&crate::waas::api::types::WithdrawRecord> for ChainUpTransaction {
+    fn from(r: &crate::waas::api::types::WithdrawRecord) -> Self {
+        let rejected = [r.saas_status, r.company_status]
+            .into_iter()
+            .flatten()
+            .any(|s: WaasAuditStatus| s.is_failed());
+        Self {
+            kind: ReconcileKind::Withdraw,
+            reference: r.request_id.as_ref().map(ToString::to_string),
+            record_id: r.id.map(|id| id.value()),
+            amount: r.amount,
+            symbol: r.symbol.as_ref().map(ToString::to_string),
+            address: r.address_to.clone(),
+            status: rejected.then_some(SettlementStatus::Failed),
+            status_code: r.status,
+            created_at: r.created_at.as_timestamp(),
+        }
+    }
+}
+
+impl From<&crate::waas::api::types::TransferRecord> for ChainUpTransaction {
+    fn from(r: &crate::waas::api::types::TransferRecord) -> Self {
+        Self {
+            kind: ReconcileKind::Transfer,
+            reference: r.request_id.as_ref().map(ToString::to_string),
+            record_id: r.id.map(|id| id.value()),
+            amount: r.amount,
+            symbol: r.symbol.as_ref().map(ToString::to_string),
+            address: r.to.clone(),
+            status: None,
+            status_code: r.status,
+            created_at: r.created_at.as_timestamp(),
+        }
+    }
+}
+
+/// An expected transaction and the ChainUp record it was paired with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReconciledPair {
+    /// Entry from the internal ledger
+    pub expected: ExpectedTransaction,
+    /// Entry from ChainUp
+    pub actual: ChainUpTransaction,
+}
+
+/// Outcome of a reconciliation
+///
+/// A pair with both an amount and a status mismatch is listed in both.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReconciliationReport {
+    /// Pairs that agree on amount, symbol, address and status
+    pub matched: Vec<ReconciledPair>,
+    /// ChainUp records with no entry in the internal ledger
+    pub missing_on_our_side: Vec<ChainUpTransaction>,
+    /// Ledger entries with no ChainUp record
+    pub missing_at_chainup: Vec<ExpectedTransaction>,
+    /// Pairs whose amount, symbol or address differ
+    pub amount_mismatches: Vec<ReconciledPair>,
+    /// Pairs whose settlement status differs
+    pub status_mismatches: Vec<ReconciledPair>,
+}
+
+impl ReconciliationReport {
+    /// Whether every entry was matched
+    pub fn is_balanced(&self) -> bool {
+        self.missing_on_our_side.is_empty()
+            && self.missing_at_chainup.is_empty()
+            && self.amount_mismatches.is_empty()
+            && self.status_mismatches.is_empty()
+    }
+
+    /// Serializes the report as pretty-printed JSON
+    pub fn to_json_pretty(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// `max_id` to start each sync endpoint from in the `fetch_*` methods
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncStart {
+    /// Start of the deposit sync
+    pub deposit_max_id: i64,
+    /// Start of the withdrawal sync
+    pub withdraw_max_id: i64,
+    /// Start of the transfer sync (WaaS only)
+    pub transfer_max_id: i64,
+}
+
+/// Collects both sides of a reconciliation and compares them
+#[derive(Debug, Clone, Default)]
+pub struct Reconciliation {
+    expected: Vec<ExpectedTransaction>,
+    actual: Vec<ChainUpTransaction>,
+    window: Option<(Timestamp, Timestamp)>,
+}
+
+impl Reconciliation {
+    /// Creates an empty reconciliation
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only keeps synced ChainUp records created in `[from, to)`
+    ///
+    /// Records without a creation time and records looked up for an
+    /// expected transaction are always kept.
+    pub fn with_window(mut self, from: Timestamp, to: Timestamp) -> Self {
+        self.window = Some((from, to));
+        self
+    }
+
+    /// Adds transactions from the internal ledger
+    pub fn add_expected(&mut self, expected: impl IntoIterator<Item = ExpectedTransaction>) {
+        self.expected.extend(expected);
+    }
+
+    /// Adds ChainUp records
+    pub fn add_chainup(&mut self, actual: impl IntoIterator<Item = ChainUpTransaction>) {
+        self.actual.extend(actual);
+    }
+
+    /// Fetches MPC deposits and withdrawals
+    ///
+    /// Syncs both endpoints from `start`, then looks up expected withdrawals
+    /// the sync did not return.
+    pub fn fetch_mpc(&mut self, client: &MpcClient, start: SyncStart) -> Result<()> {
+        for record in client
+            .deposit()
+            .sync_deposit_records_iter(start.deposit_max_id)
+        {
+            self.add_synced(ChainUpTransaction::from(&record?));
+        }
+        let withdraw_api = client.withdraw();
+        for record in withdraw_api.sync_withdraw_records_iter(start.withdraw_max_id) {
+            self.add_synced(ChainUpTransaction::from(&record?));
+        }
+
+        let unseen = self.unseen(ReconcileKind::Withdraw);
+        let unseen: Vec<&str> = unseen.iter().map(String::as_str).collect();
+        let found = withdraw_api
+            .lookup_withdraw_records(&unseen, LookupOptions::default())?
+            .found;
+        self.actual
+            .extend(found.iter().map(ChainUpTransaction::from));
+        Ok(())
+    }
+
+    /// Fetches WaaS deposits, withdrawals and transfers
+    ///
+    /// Syncs the three endpoints from `start`, then looks up expected
+    /// withdrawals and transfers the sync did not return.
+    pub fn fetch_waas(&mut self, client: &WaasClient, start: SyncStart) -> Result<()> {
+        let billing_api = client.billing();
+        let transfer_api = client.transfer();
+        for record in billing_api.sync_deposit_list_iter(start.deposit_max_id) {
+            self.add_synced(ChainUpTransaction::from(&record?));
+        }
+        for record in billing_api.sync_withdraw_list_iter(start.withdraw_max_id) {
+            self.add_synced(ChainUpTransaction::from(&record?));
+        }
+        for record in transfer_api.sync_account_transfer_list_iter(start.transfer_max_id) {
+            self.add_synced(ChainUpTransaction::from(&record?));
+        }
+
+        let unseen = self.unseen(ReconcileKind::Withdraw);
+        let unseen: Vec<&str> = unseen.iter().map(String::as_str).collect();
+        let found = billing_api
+            .lookup_withdraw_list(&unseen, LookupOptions::default())?
+            .found;
+        self.actual
+            .extend(found.iter().map(ChainUpTransaction::from));
+
+        let unseen = self.unseen(ReconcileKind::Transfer);
+        if !unseen.is_empty() {
+            let unseen: Vec<&str> = unseen.iter().map(String::as_str).collect();
+            let found = transfer_api
+                .lookup_account_transfer_list(
+                    GetAccountTransferListParams::by_request_id(&unseen),
+                    LookupOptions::default(),
+                )?
+                .found;
+            self.actual
+                .extend(found.iter().map(ChainUpTransaction::from));
+        }
+        Ok(())
+    }
+
+    fn add_synced(&mut self, actual: ChainUpTransaction) {
+        let in_window = match (self.window, actual.created_at) {
+            (Some((from, to)), Some(created_at)) => from <= created_at && created_at < to,
+            _ => true,
+        };
+        if in_window {
+            self.actual.push(actual);
+        }
+    }
+
+    /// References of expected transactions of `kind` with no ChainUp record yet
+    fn unseen(&self, kind: ReconcileKind) -> Vec<String> {
+        let seen: HashSet<&str> = self
+            .actual
+            .iter()
+            .filter(|a| a.kind == kind)
+            .filter_map(|a| a.reference.as_deref())
+            .collect();
+        self.expected
+            .iter()
+            .filter(|e| e.kind == kind && !seen.contains(e.reference.as_str()))
+            .map(|e| e.reference.clone())
+            .collect()
+    }
+
+    /// Pairs up both sides and classifies every entry
+    pub fn run(&self) -> ReconciliationReport {
+        let mut report = ReconciliationReport::default();
+
+        let mut by_reference: HashMap<(ReconcileKind, &str), Vec<usize>> = HashMap::new();
+        for (index, actual) in self.actual.iter().enumerate() {
+            if let Some(reference) = actual.reference.as_deref() {
+                by_reference
+                    .entry((actual.kind, reference))
+                    .or_default()
+                    .push(index);
+            }
+        }
+
+        let mut claimed = vec![false; self.actual.len()];
+        for expected in &self.expected {
+            let candidates = by_reference
+                .get_mut(&(expected.kind, expected.reference.as_str()))
+                .filter(|candidates| !candidates.is_empty());
+            let Some(candidates) = candidates else {
+                report.missing_at_chainup.push(expected.clone());
+                continue;
+            };
+            let position = candidates
+                .iter()
+                .position(|&i| {
+                    expected.address.is_some() && self.actual[i].address == expected.address
+                })
+                .unwrap_or(0);
+            let index = candidates.remove(position);
+            claimed[index] = true;
+            let pair = ReconciledPair {
+                expected: expected.clone(),
+                actual: self.actual[index].clone(),
+            };
+
+            let amount_ok = amounts_match(&pair);
+            let status_ok = match (pair.expected.status, pair.actual.status) {
+                (Some(expected), Some(actual)) => expected == actual,
+                _ => true,
+            };
+            if !amount_ok {
+                report.amount_mismatches.push(pair.clone());
+            }
+            if !status_ok {
+                report.status_mismatches.push(pair.clone());
+            }
+            if amount_ok && status_ok {
+                report.matched.push(pair);
+            }
+        }
+
+        report.missing_on_our_side = self
+            .actual
+            .iter()
+            .zip(claimed)
+            .filter(|(_, claimed)| !claimed)
+            .map(|(actual, _)| actual.clone())
+            .collect();
+        report
+    }
+}
+
+/// Compares amount, symbol and (when both are known) address exactly
+fn amounts_match(pair: &ReconciledPair) -> bool {
+    let (expected, actual) = (&pair.expected, &pair.actual);
+    let symbol_ok = actual
+        .symbol
+        .as_deref()
+        .is_some_and(|symbol| symbol.eq_ignore_ascii_case(&expected.symbol));
+    let address_ok = match (&expected.address, &actual.address) {
+        (Some(expected), Some(actual)) => expected == actual,
+        _ => true,
+    };
+    actual.amount == Some(expected.amount) && symbol_ok && address_ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn mpc_withdraw(request_id: &str, amount: &str, status: i32) -> ChainUpTransaction {
+        let record: crate::mpc::api::types::WithdrawRecord = serde_json::from_value(json!({
+            "id": 1, "request_id": request_id, "amount": amount, "symbol": "USDT",
+            "address_to": "0xabc", "status": status
+        }))
+        .unwrap();
+        ChainUpTransaction::from(&record)
+    }
+
+    #[test]
+    fn test_reconciliation_report() {
+        let mut recon = Reconciliation::new();
+        recon.add_expected([
+            ExpectedTransaction::new(
+                ReconcileKind::Withdraw,
+                "wd-1",
+                "12.5".parse().unwrap(),
+                "usdt",
+            )
+            .with_status(SettlementStatus::Success),
+            ExpectedTransaction::new(
+                ReconcileKind::Withdraw,
+                "wd-2",
+                "1.000001".parse().unwrap(),
+                "USDT",
+            ),
+            ExpectedTransaction::new(
+                ReconcileKind::Withdraw,
+                "wd-3",
+                "3".parse().unwrap(),
+                "USDT",
+            )
+            .with_status(SettlementStatus::Success),
+            ExpectedTransaction::new(
+                ReconcileKind::Withdraw,
+                "wd-4",
+                "4".parse().unwrap(),
+                "USDT",
+            ),
+        ]);
+        recon.add_chainup([
+            // Same value, different scale
+            mpc_withdraw("wd-1", "12.50", 2000),
+            mpc_withdraw("wd-2", "1.000002", 2000),
+            mpc_withdraw("wd-3", "3", 2400),
+            mpc_withdraw("wd-9", "9", 2000),
+        ]);
+
+        let report = recon.run();
+        let refs = |pairs: &[ReconciledPair]| -> Vec<String> {
+            pairs.iter().map(|p| p.expected.reference.clone()).collect()
+        };
+        assert_eq!(refs(&report.matched), ["wd-1"]);
+        assert_eq!(refs(&report.amount_mismatches), ["wd-2"]);
+        assert_eq!(refs(&report.status_mismatches), ["wd-3"]);
+        assert_eq!(report.missing_at_chainup[0].reference, "wd-4");
+        assert_eq!(report.missing_on_our_side.len(), 1);
+        assert_eq!(
+            report.missing_on_our_side[0].reference.as_deref(),
+            Some("wd-9")
+        );
+        assert!(!report.is_balanced());
+    }
+}
@@ -0,0 +1,374 @@
+//! Record and notification timestamps
+//!
+//! ChainUp returns times in several shapes depending on the endpoint:
+//! millisecond numbers (`1700000000000`), the same number quoted
+//! (`"1700000000000"`), and formatted strings (`"2023-11-14 22:13:20"`).
+//! [`Timestamp`] accepts all of them and stores the instant as milliseconds
+//! since the Unix epoch, so every record exposes times the same way.
+//!
+//! Record and notification fields that predate [`Timestamp`] keep their
+//! original types unless the `typed-timestamps` feature is enabled, see
+//! [`NumericTime`] and [`TextTime`]. [`AsTimestamp`] reads either form.
+//!
+//! Conversions to a datetime type are opt-in:
+//!
+//! - `chrono` feature: [`Timestamp::to_chrono`] and `TryFrom<Timestamp>` for
+//!   `chrono::DateTime<Utc>`
+//! - `time` feature: [`Timestamp::to_offset_date_time`] and
+//!   `TryFrom<Timestamp>` for `time::OffsetDateTime`
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Numbers below this are taken as seconds rather than milliseconds
+///
+/// 10^11 ms is March 1973, while 10^11 s is far beyond any real record.
+const SECONDS_THRESHOLD: i64 = 100_000_000_000;
+
+/// A point in time, in milliseconds since the Unix epoch (UTC)
+///
+/// Serializes as a millisecond number. Deserializes from:
+///
+/// - integer or float numbers, as milliseconds (values below 10^11 are
+///   taken as seconds)
+/// - numeric strings, with the same rules
+/// - `YYYY-MM-DD HH:MM:SS`, `YYYY-MM-DDTHH:MM:SS` (optionally with
+///   fractional seconds and a `Z` or `±HH:MM` offset) and `YYYY-MM-DD`
+///
+/// Formatted strings without an offset are read as UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize)]
+#[serde(transparent)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    /// Creates a timestamp from milliseconds since the Unix epoch
+    pub fn from_millis(millis: i64) -> Self {
+        Timestamp(millis)
+    }
+
+    /// Creates a timestamp from seconds since the Unix epoch
+    pub fn from_secs(secs: i64) -> Self {
+        Timestamp(secs.saturating_mul(1000))
+    }
+
+    /// Milliseconds since the Unix epoch
+    pub fn as_millis(self) -> i64 {
+        self.0
+    }
+
+    /// Whole seconds since the Unix epoch
+    pub fn as_secs(self) -> i64 {
+        self.0.div_euclid(1000)
+    }
+
+    /// Parses any of the accepted timestamp forms
+    ///
+    /// Returns `None` for empty or unrecognized input.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            return None;
+        }
+        if let Ok(n) = s.parse::<i64>() {
+            return Some(Self::from_number(n));
+        }
+        if let Ok(f) = s.parse::<f64>() {
+            return Self::from_float(f);
+        }
+        parse_datetime(s).map(Timestamp)
+    }
+
+    fn from_number(n: i64) -> Self {
+        if n.abs() < SECONDS_THRESHOLD {
+            Self::from_secs(n)
+        } else {
+            Timestamp(n)
+        }
+    }
+
+    fn from_float(f: f64) -> Option<Self> {
+        if !f.is_finite() {
+            return None;
+        }
+        let millis = if f.abs() < SECONDS_THRESHOLD as f64 {
+            f * 1000.0
+        } else {
+            f
+        };
+        Some(Timestamp(millis.round() as i64))
+    }
+
+    /// Converts to a `chrono::DateTime<Utc>`
+    ///
+    /// Returns `None` if the value is outside chrono's supported range.
+    #[cfg(feature = "chrono")]
+    pub fn to_chrono(self) -> Option<chrono::DateTime<chrono::Utc>> {
+        chrono::DateTime::from_timestamp_millis(self.0)
+    }
+
+    /// Converts to a `time::OffsetDateTime` in UTC
+    ///
+    /// Returns `None` if the value is outside time's supported range.
+    #[cfg(feature = "time")]
+    pub fn to_offset_date_time(self) -> Option<time::OffsetDateTime> {
+        time::OffsetDateTime::from_unix_timestamp_nanos(i128::from(self.0) * 1_000_000).ok()
+    }
+}
+
+impl From<i64> for Timestamp {
+    fn from(millis: i64) -> Self {
+        Timestamp(millis)
+    }
+}
+
+impl From<Timestamp> for i64 {
+    fn from(ts: Timestamp) -> Self {
+        ts.0
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid timestamp: {:?}", s))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<Timestamp> for chrono::DateTime<chrono::Utc> {
+    type Error = String;
+
+    fn try_from(ts: Timestamp) -> Result<Self, Self::Error> {
+        ts.to_chrono()
+            .ok_or_else(|| format!("timestamp out of range: {}", ts.0))
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<Timestamp> for time::OffsetDateTime {
+    type Error = String;
+
+    fn try_from(ts: Timestamp) -> Result<Self, Self::Error> {
+        ts.to_offset_date_time()
+            .ok_or_else(|| format!("timestamp out of range: {}", ts.0))
+    }
+}
+
+/// Type of time fields that ChainUp sends as numbers, such as `created_at`
+///
+/// [`Timestamp`] with the `typed-timestamps` feature, `i64` without it.
+#[cfg(feature = "typed-timestamps")]
+pub type NumericTime = Timestamp;
+
+/// Type of time fields that ChainUp sends as numbers, such as `created_at`
+///
+/// [`Timestamp`] with the `typed-timestamps` feature, `i64` without it.
+#[cfg(not(feature = "typed-timestamps"))]
+pub type NumericTime = i64;
+
+/// Type of time fields that ChainUp sends as strings, such as `notify_time`
+///
+/// [`Timestamp`] with the `typed-timestamps` feature, `String` without it.
+#[cfg(feature = "typed-timestamps")]
+pub type TextTime = Timestamp;
+
+/// Type of time fields that ChainUp sends as strings, such as `notify_time`
+///
+/// [`Timestamp`] with the `typed-timestamps` feature, `String` without it.
+#[cfg(not(feature = "typed-timestamps"))]
+pub type TextTime = String;
+
+/// Reads a time field as a [`Timestamp`], whatever its type
+///
+/// Implemented for [`Timestamp`], `i64` and `String` (the types of
+/// [`NumericTime`] and [`TextTime`]), and for `Option`s of them.
+pub trait AsTimestamp {
+    /// The time, or `None` if it is unset, `0` or unparseable
+    fn as_timestamp(&self) -> Option<Timestamp>;
+}
+
+impl AsTimestamp for Timestamp {
+    fn as_timestamp(&self) -> Option<Timestamp> {
+        Some(*self).filter(|ts| ts.0 != 0)
+    }
+}
+
+impl AsTimestamp for i64 {
+    fn as_timestamp(&self) -> Option<Timestamp> {
+        Timestamp::from_number(*self).as_timestamp()
+    }
+}
+
+impl AsTimestamp for String {
+    fn as_timestamp(&self) -> Option<Timestamp> {
+        Timestamp::parse(self)?.as_timestamp()
+    }
+}
+
+impl<T: AsTimestamp> AsTimestamp for Option<T> {
+    fn as_timestamp(&self) -> Option<Timestamp> {
+        self.as_ref()?.as_timestamp()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum RawTimestamp {
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl RawTimestamp {
+    pub(crate) fn into_timestamp(self) -> Option<Timestamp> {
+        match self {
+            RawTimestamp::Int(n) => Some(Timestamp::from_number(n)),
+            RawTimestamp::Float(f) => Timestamp::from_float(f),
+            RawTimestamp::String(s) => Timestamp::parse(&s),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawTimestamp::deserialize(deserializer)?;
+        let shown = match &raw {
+            RawTimestamp::String(s) => format!("{:?}", s),
+            RawTimestamp::Int(n) => n.to_string(),
+            RawTimestamp::Float(f) => f.to_string(),
+        };
+        raw.into_timestamp()
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid timestamp: {}", shown)))
+    }
+}
+
+/// Parses `YYYY-MM-DD[( |T)HH:MM:SS[.fff]][Z|±HH:MM]` into epoch milliseconds
+fn parse_datetime(s: &str) -> Option<i64> {
+    let (date, rest) = match (s.get(..10), s.get(10..)) {
+        (Some(date), Some(rest)) => (date, rest),
+        _ => (s, ""),
+    };
+
+    let mut parts = date.split('-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let mut millis = days_from_civil(year, month, day) * 86_400_000;
+
+    if rest.is_empty() {
+        return Some(millis);
+    }
+    let rest = rest.strip_prefix(' ').or_else(|| rest.strip_prefix('T'))?;
+
+    // Split off the UTC offset, if any
+    let (time, offset_millis) = if let Some(time) = rest.strip_suffix('Z') {
+        (time, 0)
+    } else if let Some(pos) = rest.rfind(['+', '-']) {
+        let (time, offset) = rest.split_at(pos);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (h, m) = offset[1..].split_once(':').unwrap_or((&offset[1..], "0"));
+        let h: i64 = h.parse().ok()?;
+        let m: i64 = m.parse().ok()?;
+        if h > 23 || m > 59 {
+            return None;
+        }
+        (time, sign * (h * 3_600_000 + m * 60_000))
+    } else {
+        (rest, 0)
+    };
+
+    let (hms, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut fields = hms.split(':');
+    let hour: i64 = fields.next()?.parse().ok()?;
+    let minute: i64 = fields.next()?.parse().ok()?;
+    let second: i64 = fields.next().map_or(Some(0), |v| v.parse().ok())?;
+    if fields.next().is_some() || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    millis += hour * 3_600_000 + minute * 60_000 + second * 1000;
+
+    if !fraction.is_empty() {
+        if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let digits: String = fraction.chars().chain("000".chars()).take(3).collect();
+        millis += digits.parse::<i64>().ok()?;
+    }
+
+    Some(millis - offset_millis)
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let month = i64::from(month);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamp_forms() {
+        let expected = Timestamp::from_millis(1_700_000_000_000);
+        for input in [
+            "1700000000000",
+            "\"1700000000000\"",
+            "1700000000",
+            "\"2023-11-14 22:13:20\"",
+            "\"2023-11-14T22:13:20.000Z\"",
+            "\"2023-11-15T06:13:20+08:00\"",
+        ] {
+            let ts: Timestamp = serde_json::from_str(input).unwrap();
+            assert_eq!(ts, expected, "input: {}", input);
+        }
+
+        assert_eq!(
+            Timestamp::parse("1970-01-02"),
+            Some(Timestamp::from_secs(86_400))
+        );
+        assert_eq!(Timestamp::parse("2023-13-01 00:00:00"), None);
+        assert_eq!(
+            Timestamp::parse("2024-01-01T00:00:00+99999999999999:00"),
+            None
+        );
+        assert!(serde_json::from_str::<Timestamp>("\"yesterday\"").is_err());
+        assert_eq!(serde_json::to_string(&expected).unwrap(), "1700000000000");
+
+        assert_eq!(1_700_000_000_000_i64.as_timestamp(), Some(expected));
+        assert_eq!(
+            "2023-11-14 22:13:20".to_string().as_timestamp(),
+            Some(expected)
+        );
+        assert_eq!(Some(0_i64).as_timestamp(), None);
+
+        #[cfg(feature = "chrono")]
+        assert_eq!(
+            expected.to_chrono().unwrap().to_rfc3339(),
+            "2023-11-14T22:13:20+00:00"
+        );
+        #[cfg(feature = "time")]
+        assert_eq!(
+            expected.to_offset_date_time().unwrap().unix_timestamp(),
+            1_700_000_000
+        );
+    }
+}
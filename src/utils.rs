@@ -4,6 +4,8 @@
 pub mod serde_helpers {
     use serde::{Deserialize, Deserializer};
 
+    use crate::timestamp::{NumericTime, RawTimestamp, TextTime, Timestamp};
+
     /// Deserialize a string or number as Option<i32>
     pub fn deserialize_optional_i32<'de, D>(deserializer: D) -> Result<Option<i32>, D::Error>
     where
@@ -44,6 +46,103 @@ pub mod serde_helpers {
         })
     }
 
+    /// Deserialize a string or number as an optional integer ID newtype
+    ///
+    /// Like [`deserialize_optional_i64`], unparseable values become `None`.
+    pub fn deserialize_optional_id<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: From<i64>,
+    {
+        deserialize_optional_i64(deserializer).map(|opt| opt.map(T::from))
+    }
+
+    /// Deserialize a string or number as an optional status enum
+    ///
+    /// Like [`deserialize_optional_i32`], unparseable values such as `""`
+    /// become `None`; codes unknown to the enum keep their `Unknown` variant.
+    pub fn deserialize_optional_status<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: From<i32>,
+    {
+        deserialize_optional_i32(deserializer).map(|opt| opt.map(T::from))
+    }
+
+    /// Deserialize any accepted timestamp form as Option<Timestamp>
+    ///
+    /// Unparseable values and `0` (used by ChainUp for events that have not
+    /// happened yet) become `None`.
+    pub fn deserialize_optional_timestamp<'de, D>(
+        deserializer: D,
+    ) -> Result<Option<Timestamp>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<RawTimestamp>::deserialize(deserializer).map(|opt| {
+            opt.and_then(RawTimestamp::into_timestamp)
+                .filter(|ts| ts.as_millis() != 0)
+        })
+    }
+
+    /// Deserialize a string or number as Option<[`NumericTime`]>
+    #[cfg(feature = "typed-timestamps")]
+    pub fn deserialize_optional_numeric_time<'de, D>(
+        deserializer: D,
+    ) -> Result<Option<NumericTime>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_optional_timestamp(deserializer)
+    }
+
+    /// Deserialize a string or number as Option<[`NumericTime`]>
+    #[cfg(not(feature = "typed-timestamps"))]
+    pub fn deserialize_optional_numeric_time<'de, D>(
+        deserializer: D,
+    ) -> Result<Option<NumericTime>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_optional_i64(deserializer)
+    }
+
+    /// Deserialize a string or number as Option<[`TextTime`]>
+    #[cfg(feature = "typed-timestamps")]
+    pub fn deserialize_optional_text_time<'de, D>(
+        deserializer: D,
+    ) -> Result<Option<TextTime>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_optional_timestamp(deserializer)
+    }
+
+    /// Deserialize a string or number as Option<[`TextTime`]>
+    ///
+    /// Numbers are kept as their decimal text.
+    #[cfg(not(feature = "typed-timestamps"))]
+    pub fn deserialize_optional_text_time<'de, D>(
+        deserializer: D,
+    ) -> Result<Option<TextTime>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum StringOrNumber {
+            String(String),
+            Number(serde_json::Number),
+        }
+
+        Option::<StringOrNumber>::deserialize(deserializer).map(|opt| {
+            opt.map(|v| match v {
+                StringOrNumber::String(s) => s,
+                StringOrNumber::Number(n) => n.to_string(),
+            })
+        })
+    }
+
     /// Deserialize a string or bool as Option<bool>
     /// Handles: true, false, 0, 1, "0", "1", "true", "false"
     pub fn deserialize_optional_bool<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
@@ -75,3 +174,122 @@ pub mod serde_helpers {
         deserialize_optional_bool(deserializer)
     }
 }
+
+/// Amount formatting and coin-precision checks for request parameters
+pub mod amount {
+    use rust_decimal::Decimal;
+
+    use crate::enums::ApiCode;
+    use crate::error::{ApiError, Result, ValidationError};
+
+    /// Formats an amount as sent to ChainUp and used in signatures
+    ///
+    /// Trailing zeros are stripped and no exponent is used, so `1.500`
+    /// becomes `"1.5"` and `100` stays `"100"`. The same string goes into
+    /// the request and into the `MpcSignUtil` sign string.
+    pub fn format_amount(amount: Decimal) -> String {
+        amount.normalize().to_string()
+    }
+
+    /// Precision and minimum of a coin, used to check amounts before sending
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct AmountRules {
+        /// Maximum number of decimal places
+        pub decimals: Option<u32>,
+        /// Smallest accepted amount
+        pub min_amount: Option<Decimal>,
+    }
+
+    impl AmountRules {
+        /// Creates rules allowing at most `decimals` decimal places
+        pub fn new(decimals: u32) -> Self {
+            Self {
+                decimals: Some(decimals),
+                min_amount: None,
+            }
+        }
+
+        /// Sets the smallest accepted amount
+        pub fn with_min_amount(mut self, min_amount: Decimal) -> Self {
+            self.min_amount = Some(min_amount);
+            self
+        }
+
+        /// Checks an amount and returns its request string
+        ///
+        /// Errors mirror the server's rejections, so they can be handled the
+        /// same way: too many decimal places yields an `ApiError` with
+        /// [`ApiCode::PrecisionExceeded`], an amount under the minimum one with
+        /// [`ApiCode::AmountBelowMin`]. Non-positive amounts are a `ValidationError`.
+        pub fn check(&self, amount: Decimal) -> Result<String> {
+            if amount <= Decimal::ZERO {
+                return Err(ValidationError::new(format!(
+                    "Amount must be positive, got {}",
+                    amount
+                ))
+                .into());
+            }
+
+            let amount = amount.normalize();
+            if let Some(decimals) = self.decimals {
+                if amount.scale() > decimals {
+                    return Err(ApiError::new(
+                        ApiCode::PrecisionExceeded.code(),
+                        format!(
+                            "Amount {} has {} decimal places, coin supports {}",
+                            amount,
+                            amount.scale(),
+                            decimals
+                        ),
+                    )
+                    .into());
+                }
+            }
+            if let Some(min_amount) = self.min_amount {
+                if amount < min_amount {
+                    return Err(ApiError::new(
+                        ApiCode::AmountBelowMin.code(),
+                        format!("Amount {} is below the minimum {}", amount, min_amount),
+                    )
+                    .into());
+                }
+            }
+
+            Ok(format_amount(amount))
+        }
+    }
+
+    /// Converts the `decimals` value reported by the coin endpoints
+    pub(crate) fn decimals_from(decimals: Option<i32>) -> Option<u32> {
+        decimals.and_then(|d| u32::try_from(d).ok())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::str::FromStr;
+
+        #[test]
+        fn test_amount_rules() {
+            let rules = AmountRules::new(6).with_min_amount(Decimal::from_str("0.01").unwrap());
+
+            assert_eq!(
+                rules.check(Decimal::from_str("1.500").unwrap()).unwrap(),
+                "1.5"
+            );
+            assert_eq!(rules.check(Decimal::from(100)).unwrap(), "100");
+
+            let err = rules
+                .check(Decimal::from_str("0.1234567").unwrap())
+                .unwrap_err();
+            assert_eq!(err.api_code(), Some(ApiCode::PrecisionExceeded));
+
+            let err = rules
+                .check(Decimal::from_str("0.005").unwrap())
+                .unwrap_err();
+            assert_eq!(err.api_code(), Some(ApiCode::AmountBelowMin));
+
+            assert!(rules.check(Decimal::ZERO).is_err());
+        }
+    }
+}
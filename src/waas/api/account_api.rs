@@ -8,8 +8,14 @@ use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::checkpoint::{CheckpointStore, CheckpointedSync};
 use crate::crypto::CryptoProvider;
+use crate::decode::impl_unknown_fields;
 use crate::error::Result;
+use crate::ids::{RecordId, Symbol, Uid};
+use crate::pagination::SyncIter;
+#[cfg(feature = "async")]
+use crate::pagination::SyncStream;
 use crate::waas::api::base_api::BaseApi;
 use crate::waas::config::WaasConfig;
 
@@ -21,24 +27,24 @@ use crate::waas::config::WaasConfig;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetUserAccountParams {
     /// User ID
-    pub uid: i64,
+    pub uid: Uid,
     /// Cryptocurrency symbol (e.g., "ETH", "BTC")
-    pub symbol: String,
+    pub symbol: Symbol,
 }
 
 impl GetUserAccountParams {
     /// Creates new parameters
-    pub fn new(uid: i64, symbol: impl Into<String>) -> Self {
+    pub fn new(uid: impl Into<Uid>, symbol: impl Into<Symbol>) -> Self {
         Self {
-            uid,
+            uid: uid.into(),
             symbol: symbol.into(),
         }
     }
 
     fn to_map(&self) -> HashMap<String, Value> {
         let mut map = HashMap::new();
-        map.insert("uid".to_string(), Value::Number(self.uid.into()));
-        map.insert("symbol".to_string(), Value::String(self.symbol.clone()));
+        map.insert("uid".to_string(), Value::Number(self.uid.value().into()));
+        map.insert("symbol".to_string(), Value::String(self.symbol.to_string()));
         map
     }
 }
@@ -47,24 +53,24 @@ impl GetUserAccountParams {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetUserAddressParams {
     /// User ID
-    pub uid: i64,
+    pub uid: Uid,
     /// Cryptocurrency symbol (e.g., "ETH", "BTC")
-    pub symbol: String,
+    pub symbol: Symbol,
 }
 
 impl GetUserAddressParams {
     /// Creates new parameters
-    pub fn new(uid: i64, symbol: impl Into<String>) -> Self {
+    pub fn new(uid: impl Into<Uid>, symbol: impl Into<Symbol>) -> Self {
         Self {
-            uid,
+            uid: uid.into(),
             symbol: symbol.into(),
         }
     }
 
     fn to_map(&self) -> HashMap<String, Value> {
         let mut map = HashMap::new();
-        map.insert("uid".to_string(), Value::Number(self.uid.into()));
-        map.insert("symbol".to_string(), Value::String(self.symbol.clone()));
+        map.insert("uid".to_string(), Value::Number(self.uid.value().into()));
+        map.insert("symbol".to_string(), Value::String(self.symbol.to_string()));
         map
     }
 }
@@ -95,12 +101,12 @@ impl GetUserAddressInfoParams {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetCompanyAccountParams {
     /// Cryptocurrency symbol (e.g., "ETH", "BTC")
-    pub symbol: String,
+    pub symbol: Symbol,
 }
 
 impl GetCompanyAccountParams {
     /// Creates new parameters
-    pub fn new(symbol: impl Into<String>) -> Self {
+    pub fn new(symbol: impl Into<Symbol>) -> Self {
         Self {
             symbol: symbol.into(),
         }
@@ -108,7 +114,7 @@ impl GetCompanyAccountParams {
 
     fn to_map(&self) -> HashMap<String, Value> {
         let mut map = HashMap::new();
-        map.insert("symbol".to_string(), Value::String(self.symbol.clone()));
+        map.insert("symbol".to_string(), Value::String(self.symbol.to_string()));
         map
     }
 }
@@ -119,17 +125,17 @@ impl GetCompanyAccountParams {
 
 use rust_decimal::Decimal;
 
-use crate::utils::serde_helpers::deserialize_optional_i64;
+use crate::utils::serde_helpers::deserialize_optional_id;
 
 /// User account information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserAccountInfo {
     /// User ID
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub uid: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_id")]
+    pub uid: Option<Uid>,
     /// Cryptocurrency symbol
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Available balance
     #[serde(default, with = "rust_decimal::serde::str_option")]
     pub balance: Option<Decimal>,
@@ -137,49 +143,69 @@ pub struct UserAccountInfo {
     #[serde(default, with = "rust_decimal::serde::str_option")]
     pub frozen: Option<Decimal>,
     /// Address ID (from sync list)
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub id: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_id")]
+    pub id: Option<RecordId>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(UserAccountInfo);
+
 /// User deposit address information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserAddressInfo {
     /// Address ID (from sync list)
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub id: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_id")]
+    pub id: Option<RecordId>,
     /// User ID
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub uid: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_id")]
+    pub uid: Option<Uid>,
     /// Cryptocurrency symbol
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Deposit address
     #[serde(default)]
     pub address: Option<String>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(UserAddressInfo);
+
 /// Company account information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompanyAccountInfo {
     /// Cryptocurrency symbol
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Available balance
     #[serde(default, with = "rust_decimal::serde::str_option")]
     pub balance: Option<Decimal>,
     /// Frozen balance
     #[serde(default, with = "rust_decimal::serde::str_option")]
     pub frozen: Option<Decimal>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(CompanyAccountInfo);
+
 /// Response for sync user address list
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncUserAddressListResponse {
     /// List of user addresses
     #[serde(default)]
     pub list: Vec<UserAddressInfo>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(SyncUserAddressListResponse, list);
+
 // ============================================================================
 // Account API Implementation
 // ============================================================================
@@ -213,8 +239,8 @@ impl AccountApi {
     /// ```
     pub fn get_user_account(&self, params: GetUserAccountParams) -> Result<UserAccountInfo> {
         let data = params.to_map();
-        let response = self.base.post("/account/getByUidAndSymbol", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/account/getByUidAndSymbol", Some(&data))
     }
 
     /// Gets user deposit address
@@ -231,8 +257,8 @@ impl AccountApi {
     /// ```
     pub fn get_user_address(&self, params: GetUserAddressParams) -> Result<UserAddressInfo> {
         let data = params.to_map();
-        let response = self.base.post("/account/getDepositAddress", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/account/getDepositAddress", Some(&data))
     }
 
     /// Gets user address info by deposit address
@@ -252,10 +278,8 @@ impl AccountApi {
         params: GetUserAddressInfoParams,
     ) -> Result<UserAddressInfo> {
         let data = params.to_map();
-        let response = self
-            .base
-            .post("/account/getDepositAddressInfo", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/account/getDepositAddressInfo", Some(&data))
     }
 
     /// Gets company account balance
@@ -275,8 +299,8 @@ impl AccountApi {
         params: GetCompanyAccountParams,
     ) -> Result<CompanyAccountInfo> {
         let data = params.to_map();
-        let response = self.base.post("/account/getCompanyBySymbol", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/account/getCompanyBySymbol", Some(&data))
     }
 
     /// Syncs user address list by max ID (pagination)
@@ -295,7 +319,49 @@ impl AccountApi {
         let mut data = HashMap::new();
         data.insert("max_id".to_string(), Value::Number(max_id.into()));
 
-        let response = self.base.post("/address/syncList", Some(&data))?;
-        self.base.validate_response(response)
+        self.base.request("POST", "/address/syncList", Some(&data))
+    }
+
+    /// Iterates over all user addresses after `max_id`, fetching pages as needed
+    ///
+    /// # Example
+    /// ```ignore
+    /// for record in account_api.sync_user_address_list_iter(0).with_page_size(100) {
+    ///     println!("Record: {:?}", record?);
+    /// }
+    /// ```
+    pub fn sync_user_address_list_iter(&self, max_id: i64) -> SyncIter<'_, UserAddressInfo> {
+        SyncIter::new(max_id, move |max_id| self.sync_user_address_list(max_id))
+    }
+
+    /// Iterates over user addresses page by page, resuming from a stored checkpoint
+    ///
+    /// The checkpoint is keyed by the endpoint path and only advances when
+    /// a page is acknowledged; see [`CheckpointedSync`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut sync = account_api.sync_user_address_list_checkpointed(&store)?;
+    /// while let Some(page) = sync.next_page() {
+    ///     save_to_db(&page?)?;
+    ///     sync.ack()?;
+    /// }
+    /// ```
+    pub fn sync_user_address_list_checkpointed<'a>(
+        &'a self,
+        store: &'a dyn CheckpointStore,
+    ) -> Result<CheckpointedSync<'a, UserAddressInfo>> {
+        CheckpointedSync::new(store, "/address/syncList", |max_id| {
+            self.sync_user_address_list_iter(max_id)
+        })
+    }
+
+    /// Async stream over all user addresses after `max_id`
+    ///
+    /// See [`SyncStream`] for how pages are fetched.
+    #[cfg(feature = "async")]
+    pub fn sync_user_address_list_stream(&self, max_id: i64) -> SyncStream<UserAddressInfo> {
+        let api = Self::new(self.base.config.clone(), self.base.crypto_provider.clone());
+        SyncStream::new(max_id, move |max_id| api.sync_user_address_list(max_id))
     }
 }
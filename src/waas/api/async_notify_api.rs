@@ -2,15 +2,26 @@
 //!
 //! Provides methods for decrypting and managing webhook notifications.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use rust_decimal::Decimal;
 
 use crate::crypto::CryptoProvider;
+use crate::decode::impl_unknown_fields;
+use crate::dedup::{DedupStore, Delivery, NotificationKey};
+use crate::enums::WaasAuditStatus;
 use crate::error::{CryptoError, Result};
-use crate::utils::serde_helpers::{deserialize_optional_i32, deserialize_optional_i64};
+use crate::ids::{RecordId, RequestId, Symbol, Uid};
+use crate::notify_validation::NotifyValidation;
+use crate::timestamp::NumericTime;
+use crate::utils::serde_helpers::{
+    deserialize_optional_i32, deserialize_optional_id, deserialize_optional_numeric_time,
+    deserialize_optional_status,
+};
 use crate::waas::api::base_api::BaseApi;
 use crate::waas::api::billing_api::WithdrawParams;
 use crate::waas::config::WaasConfig;
@@ -26,17 +37,17 @@ pub struct NotifyData {
     #[serde(default)]
     pub side: Option<String>,
     /// Transaction ID
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub id: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_id")]
+    pub id: Option<RecordId>,
     /// User ID
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub uid: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_id")]
+    pub uid: Option<Uid>,
     /// User email
     #[serde(default)]
     pub email: Option<String>,
     /// Cryptocurrency symbol
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Base symbol (main chain)
     #[serde(default)]
     pub base_symbol: Option<String>,
@@ -65,14 +76,14 @@ pub struct NotifyData {
     #[serde(default, deserialize_with = "deserialize_optional_i32")]
     pub status: Option<i32>,
     /// SaaS status (for withdrawals)
-    #[serde(default, deserialize_with = "deserialize_optional_i32")]
-    pub saas_status: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_optional_status")]
+    pub saas_status: Option<WaasAuditStatus>,
     /// Company status (for withdrawals)
-    #[serde(default, deserialize_with = "deserialize_optional_i32")]
-    pub company_status: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_optional_status")]
+    pub company_status: Option<WaasAuditStatus>,
     /// Request ID (for withdrawals)
     #[serde(default)]
-    pub request_id: Option<String>,
+    pub request_id: Option<RequestId>,
     /// Withdrawal fee (for withdrawals)
     #[serde(default, with = "rust_decimal::serde::str_option")]
     pub withdraw_fee: Option<Decimal>,
@@ -92,13 +103,18 @@ pub struct NotifyData {
     #[serde(default, deserialize_with = "deserialize_optional_i32")]
     pub is_mining: Option<i32>,
     /// Creation time (milliseconds timestamp)
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub created_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub created_at: Option<NumericTime>,
     /// Update time (milliseconds timestamp)
-    #[serde(default, deserialize_with = "deserialize_optional_i64")]
-    pub updated_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub updated_at: Option<NumericTime>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(NotifyData);
+
 // ============================================================================
 // Async Notify API Implementation
 // ============================================================================
@@ -111,6 +127,7 @@ pub struct AsyncNotifyApi {
     base: BaseApi,
     crypto_provider: Arc<dyn CryptoProvider>,
     debug: bool,
+    validation: Option<NotifyValidation>,
 }
 
 impl AsyncNotifyApi {
@@ -121,9 +138,27 @@ impl AsyncNotifyApi {
             base: BaseApi::new(config, crypto_provider.clone()),
             crypto_provider,
             debug,
+            validation: None,
         }
     }
 
+    /// Checks every decrypted deposit and withdrawal notification with
+    /// `validation`
+    ///
+    /// Rejected notifications fail with a signature error; see
+    /// [`crate::notify_validation`]. WaaS notifications carry no
+    /// `notify_time`, so a replay window set with
+    /// [`NotifyValidation::with_window`] is ignored with a warning.
+    pub fn with_validation(mut self, validation: NotifyValidation) -> Self {
+        if validation.has_window() {
+            log::warn!(
+                "[AsyncNotify] WaaS notifications have no notify_time; ignoring the replay window"
+            );
+        }
+        self.validation = Some(validation.without_window());
+        self
+    }
+
     /// Decrypts deposit and withdrawal notification parameters
     ///
     /// Used to decrypt encrypted notification data received from WaaS callbacks.
@@ -140,6 +175,13 @@ impl AsyncNotifyApi {
     /// println!("Notify type: {:?}", notify_data.side);
     /// ```
     pub fn notify_request(&self, cipher: &str) -> Result<NotifyData> {
+        // Parse JSON to notification arguments
+        let notify: NotifyData = serde_json::from_str(&self.decrypt_text(cipher)?)?;
+        Ok(notify)
+    }
+
+    /// Decrypts and validates a notification, returning its JSON text
+    pub(crate) fn decrypt_text(&self, cipher: &str) -> Result<String> {
         if cipher.is_empty() {
             if self.debug {
                 log::debug!("[AsyncNotify] Cipher cannot be empty");
@@ -154,9 +196,25 @@ impl AsyncNotifyApi {
             log::debug!("[AsyncNotify] Decrypted data: {}", raw);
         }
 
-        // Parse JSON to notification arguments
-        let notify: NotifyData = serde_json::from_str(&raw)?;
-        Ok(notify)
+        if let Some(validation) = &self.validation {
+            validation.check(&raw)?;
+        }
+        Ok(raw)
+    }
+
+    /// Decrypts a deposit or withdrawal notification and checks it against
+    /// `store`
+    ///
+    /// A state handled before comes back as [`Delivery::Duplicate`]. Nothing
+    /// is stored: call [`Delivery::commit`] once a [`Delivery::New`]
+    /// notification was handled; see [`crate::dedup`].
+    pub fn notify_request_once(
+        &self,
+        cipher: &str,
+        store: &dyn DedupStore,
+    ) -> Result<Delivery<NotifyData>> {
+        let notify = self.notify_request(cipher)?;
+        Delivery::check(store, NotificationKey::from(&notify), notify)
     }
 
     /// Decrypts withdrawal secondary verification request parameters
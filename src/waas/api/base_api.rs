@@ -4,13 +4,15 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use serde::de::DeserializeOwned;
 use serde_json::Value;
 
-use crate::crypto::CryptoProvider;
-use crate::error::{ApiError, Result};
+use crate::crypto::{self, CryptoProvider};
+use crate::decode::{check_unknown_fields, UnknownFields};
+use crate::endpoint::{normalize_method, params_to_map, Endpoint};
+use crate::error::{ApiError, ErrorContext, Result};
 use crate::http_client::HttpClient;
 use crate::waas::config::WaasConfig;
 
@@ -76,6 +78,78 @@ impl BaseApi {
         method: &str,
         path: &str,
         data: Option<&HashMap<String, Value>>,
+    ) -> Result<Value> {
+        self.execute_request_with_context(method, path, data)
+            .map(|(response, _)| response)
+    }
+
+    /// Executes a request and validates its response
+    ///
+    /// Errors from any stage carry an [`ErrorContext`] with the endpoint,
+    /// method, request_id, elapsed time and (for API errors) the response body.
+    ///
+    /// Unknown response fields are reported according to the configured
+    /// [`DecodeMode`](crate::decode::DecodeMode).
+    pub(crate) fn request<T: DeserializeOwned + UnknownFields>(
+        &self,
+        method: &str,
+        path: &str,
+        data: Option<&HashMap<String, Value>>,
+    ) -> Result<T> {
+        let (response, context) = self.execute_request_with_context(method, path, data)?;
+        self.parse_response(&response)
+            .and_then(|parsed: T| {
+                check_unknown_fields(self.config.decode_mode, path, &parsed)?;
+                Ok(parsed)
+            })
+            .map_err(|e| e.with_context(context.with_body(&response.to_string())))
+    }
+
+    /// Like [`Self::request`], for response types that cannot report
+    /// unknown fields (user-defined [`Endpoint`]s and raw calls)
+    fn request_unchecked<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        path: &str,
+        data: Option<&HashMap<String, Value>>,
+    ) -> Result<T> {
+        let (response, context) = self.execute_request_with_context(method, path, data)?;
+        self.parse_response(&response)
+            .map_err(|e| e.with_context(context.with_body(&response.to_string())))
+    }
+
+    /// Executes a request, returning the response with its context
+    ///
+    /// Errors are returned with the context already attached.
+    fn execute_request_with_context(
+        &self,
+        method: &str,
+        path: &str,
+        data: Option<&HashMap<String, Value>>,
+    ) -> Result<(Value, ErrorContext)> {
+        let started = Instant::now();
+        let mut context = ErrorContext::new().with_endpoint(method, path);
+        if let Some(request_id) = data
+            .and_then(|d| d.get("request_id"))
+            .and_then(Value::as_str)
+        {
+            context = context.with_request_id(request_id);
+        }
+
+        let result = self.send_request(method, path, data);
+        let context = context.with_elapsed(started.elapsed());
+        match result {
+            Ok(response) => Ok((response, context)),
+            Err(e) => Err(e.with_context(context)),
+        }
+    }
+
+    /// Encrypts, sends and decrypts a single request
+    fn send_request(
+        &self,
+        method: &str,
+        path: &str,
+        data: Option<&HashMap<String, Value>>,
     ) -> Result<Value> {
         // Step 1: Build request args JSON
         let raw_json = self.build_request_args_json(data);
@@ -112,35 +186,16 @@ impl BaseApi {
 
         let response: Value = serde_json::from_str(&response_body)?;
 
-        // Step 4: Check if response has encrypted data field and decrypt
-        if let Some(Value::String(encrypted_response_data)) = response.get("data") {
-            // Decrypt the data field
-            match self
-                .crypto_provider
-                .decrypt_with_public_key(encrypted_response_data)
-            {
-                Ok(decrypted) => {
-                    if self.config.debug {
-                        println!("[WaaS Decrypted]: {}", decrypted);
-                    }
-                    // Parse decrypted JSON and return the complete decrypted response
-                    match serde_json::from_str::<Value>(&decrypted) {
-                        Ok(decrypted_data) => return Ok(decrypted_data),
-                        Err(_) => {
-                            // If parsing fails, return the original response
-                            if self.config.debug {
-                                println!("[WaaS] Failed to parse decrypted data as JSON");
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    if self.config.debug {
-                        println!("[WaaS Decrypt error]: {}", e);
-                    }
-                    // If decryption fails, might be an error response, return as-is
-                }
-            }
+        // Step 4: Decrypt the response envelope
+        let response = crypto::decrypt_response(
+            self.crypto_provider.as_ref(),
+            path,
+            response,
+            "public_key",
+            &self.config.app_id,
+        )?;
+        if self.config.debug {
+            println!("[WaaS Decrypted]: {}", response);
         }
 
         Ok(response)
@@ -170,6 +225,37 @@ impl BaseApi {
         self.execute_request("GET", path, data)
     }
 
+    /// Calls an [`Endpoint`] through the encrypted request pipeline
+    ///
+    /// # Arguments
+    /// * `params` - Endpoint parameters, serialized as a JSON object
+    ///
+    /// # Returns
+    /// The validated response `data`, parsed as `E::Response`
+    pub fn call<E: Endpoint>(&self, params: &E::Params) -> Result<E::Response> {
+        let method = normalize_method(E::METHOD)?;
+        let data = params_to_map(serde_json::to_value(params)?)?;
+        self.request_unchecked(method, E::PATH, data.as_ref())
+    }
+
+    /// Calls an arbitrary API path with raw JSON parameters
+    ///
+    /// The request is encrypted and the response decrypted and validated
+    /// exactly like the wrapped endpoints.
+    ///
+    /// # Arguments
+    /// * `path` - API path
+    /// * `method` - `"POST"` or `"GET"`
+    /// * `params` - JSON object of request parameters (`null` for none)
+    ///
+    /// # Returns
+    /// The validated response `data` field (`null` if absent)
+    pub fn raw_request(&self, path: &str, method: &str, params: Value) -> Result<Value> {
+        let method = normalize_method(method)?;
+        let data = params_to_map(params)?;
+        self.request_unchecked(method, path, data.as_ref())
+    }
+
     /// Validates API response and extracts data
     ///
     /// The response has already been decrypted in execute_request.
@@ -181,6 +267,10 @@ impl BaseApi {
     /// # Returns
     /// Parsed response data
     pub fn validate_response<T: DeserializeOwned>(&self, response: Value) -> Result<T> {
+        self.parse_response(&response)
+    }
+
+    fn parse_response<T: DeserializeOwned>(&self, response: &Value) -> Result<T> {
         // Check response code (can be int or string)
         let code = match response.get("code") {
             Some(Value::Number(n)) => n.as_i64().unwrap_or(-1) as i32,
@@ -216,7 +306,7 @@ impl BaseApi {
                 if let Ok(parsed) = serde_json::from_value::<T>(Value::Null) {
                     return Ok(parsed);
                 }
-                let parsed: T = serde_json::from_value(response)?;
+                let parsed: T = serde_json::from_value(response.clone())?;
                 Ok(parsed)
             }
         }
@@ -10,8 +10,19 @@ use serde_json::Value;
 
 use rust_decimal::Decimal;
 
+use crate::checkpoint::{CheckpointStore, CheckpointedSync};
 use crate::crypto::CryptoProvider;
+use crate::decode::impl_unknown_fields;
+use crate::enums::WaasAuditStatus;
 use crate::error::Result;
+use crate::ids::{RecordId, RequestId, Symbol, Uid};
+use crate::lookup::{lookup, LookupOptions, LookupResult};
+use crate::pagination::SyncIter;
+#[cfg(feature = "async")]
+use crate::pagination::SyncStream;
+use crate::timestamp::NumericTime;
+use crate::utils::amount::format_amount;
+use crate::utils::serde_helpers::{deserialize_optional_numeric_time, deserialize_optional_status};
 use crate::waas::api::base_api::BaseApi;
 use crate::waas::config::WaasConfig;
 
@@ -23,15 +34,15 @@ use crate::waas::config::WaasConfig;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WithdrawParams {
     /// Unique request ID (merchant generated)
-    pub request_id: String,
+    pub request_id: RequestId,
     /// Source user ID
-    pub from_uid: i64,
+    pub from_uid: Uid,
     /// Destination address or (address_memo for XRP)
     pub to_address: String,
     /// Withdrawal amount
     pub amount: String,
     /// Cryptocurrency symbol (e.g., "BTC", "ETH")
-    pub symbol: String,
+    pub symbol: Symbol,
     /// Withdrawal check_sum, for callback (provided by ChainUp) (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub check_sum: Option<String>,
@@ -40,15 +51,15 @@ pub struct WithdrawParams {
 impl WithdrawParams {
     /// Creates new parameters
     pub fn new(
-        request_id: impl Into<String>,
-        from_uid: i64,
+        request_id: impl Into<RequestId>,
+        from_uid: impl Into<Uid>,
         to_address: impl Into<String>,
         amount: impl Into<String>,
-        symbol: impl Into<String>,
+        symbol: impl Into<Symbol>,
     ) -> Self {
         Self {
             request_id: request_id.into(),
-            from_uid,
+            from_uid: from_uid.into(),
             to_address: to_address.into(),
             amount: amount.into(),
             symbol: symbol.into(),
@@ -56,6 +67,26 @@ impl WithdrawParams {
         }
     }
 
+    /// Creates new parameters from a `Decimal` amount
+    ///
+    /// Check the amount first with `CoinInfo::amount_rules` to catch
+    /// precision errors locally.
+    pub fn from_decimal(
+        request_id: impl Into<RequestId>,
+        from_uid: impl Into<Uid>,
+        to_address: impl Into<String>,
+        amount: Decimal,
+        symbol: impl Into<Symbol>,
+    ) -> Self {
+        Self::new(
+            request_id,
+            from_uid,
+            to_address,
+            format_amount(amount),
+            symbol,
+        )
+    }
+
     /// Sets the check_sum
     pub fn with_check_sum(mut self, check_sum: impl Into<String>) -> Self {
         self.check_sum = Some(check_sum.into());
@@ -66,15 +97,18 @@ impl WithdrawParams {
         let mut map = HashMap::new();
         map.insert(
             "request_id".to_string(),
-            Value::String(self.request_id.clone()),
+            Value::String(self.request_id.to_string()),
+        );
+        map.insert(
+            "from_uid".to_string(),
+            Value::Number(self.from_uid.value().into()),
         );
-        map.insert("from_uid".to_string(), Value::Number(self.from_uid.into()));
         map.insert(
             "to_address".to_string(),
             Value::String(self.to_address.clone()),
         );
         map.insert("amount".to_string(), Value::String(self.amount.clone()));
-        map.insert("symbol".to_string(), Value::String(self.symbol.clone()));
+        map.insert("symbol".to_string(), Value::String(self.symbol.to_string()));
         map
     }
 }
@@ -88,30 +122,35 @@ impl WithdrawParams {
 pub struct WithdrawResponse {
     /// Withdrawal ID
     #[serde(default)]
-    pub id: Option<i64>,
+    pub id: Option<RecordId>,
     /// Request ID
     #[serde(default)]
-    pub request_id: Option<String>,
+    pub request_id: Option<RequestId>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(WithdrawResponse);
+
 /// Withdrawal record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WithdrawRecord {
     /// Record ID
     #[serde(default)]
-    pub id: Option<i64>,
+    pub id: Option<RecordId>,
     /// Request ID
     #[serde(default)]
-    pub request_id: Option<String>,
+    pub request_id: Option<RequestId>,
     /// User ID
     #[serde(default)]
-    pub uid: Option<i64>,
+    pub uid: Option<Uid>,
     /// User email
     #[serde(default)]
     pub email: Option<String>,
     /// Cryptocurrency symbol
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Base symbol (main chain)
     #[serde(default)]
     pub base_symbol: Option<String>,
@@ -140,11 +179,11 @@ pub struct WithdrawRecord {
     #[serde(default)]
     pub status: Option<i32>,
     /// SaaS status
-    #[serde(default)]
-    pub saas_status: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_optional_status")]
+    pub saas_status: Option<WaasAuditStatus>,
     /// Company status
-    #[serde(default)]
-    pub company_status: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_optional_status")]
+    pub company_status: Option<WaasAuditStatus>,
     /// Withdrawal fee
     #[serde(default, with = "rust_decimal::serde::str_option")]
     pub withdraw_fee: Option<Decimal>,
@@ -161,28 +200,33 @@ pub struct WithdrawRecord {
     #[serde(default, with = "rust_decimal::serde::str_option")]
     pub real_fee: Option<Decimal>,
     /// Creation time (milliseconds timestamp)
-    #[serde(default)]
-    pub created_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub created_at: Option<NumericTime>,
     /// Update time (milliseconds timestamp)
-    #[serde(default)]
-    pub updated_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub updated_at: Option<NumericTime>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(WithdrawRecord);
+
 /// Deposit record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DepositRecord {
     /// Record ID
     #[serde(default)]
-    pub id: Option<i64>,
+    pub id: Option<RecordId>,
     /// User ID
     #[serde(default)]
-    pub uid: Option<i64>,
+    pub uid: Option<Uid>,
     /// User email
     #[serde(default)]
     pub email: Option<String>,
     /// Cryptocurrency symbol
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Base symbol (main chain)
     #[serde(default)]
     pub base_symbol: Option<String>,
@@ -214,22 +258,27 @@ pub struct DepositRecord {
     #[serde(default)]
     pub status: Option<i32>,
     /// Creation time (milliseconds timestamp)
-    #[serde(default)]
-    pub created_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub created_at: Option<NumericTime>,
     /// Update time (milliseconds timestamp)
-    #[serde(default)]
-    pub updated_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub updated_at: Option<NumericTime>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(DepositRecord);
+
 /// Miner fee record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MinerFeeRecord {
     /// Record ID
     #[serde(default)]
-    pub id: Option<i64>,
+    pub id: Option<RecordId>,
     /// Cryptocurrency symbol
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Miner fee amount
     #[serde(default, with = "rust_decimal::serde::str_option")]
     pub amount: Option<Decimal>,
@@ -243,34 +292,54 @@ pub struct MinerFeeRecord {
     #[serde(default)]
     pub status: Option<i32>,
     /// Creation time (milliseconds timestamp)
-    #[serde(default)]
-    pub created_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub created_at: Option<NumericTime>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(MinerFeeRecord);
+
 /// Response for sync withdraw list
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncWithdrawListResponse {
     /// List of withdrawal records
     #[serde(default)]
     pub list: Vec<WithdrawRecord>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(SyncWithdrawListResponse, list);
+
 /// Response for sync deposit list
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncDepositListResponse {
     /// List of deposit records
     #[serde(default)]
     pub list: Vec<DepositRecord>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(SyncDepositListResponse, list);
+
 /// Response for sync miner fee list
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncMinerFeeListResponse {
     /// List of miner fee records
     #[serde(default)]
     pub list: Vec<MinerFeeRecord>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(SyncMinerFeeListResponse, list);
+
 // ============================================================================
 // Billing API Implementation
 // ============================================================================
@@ -310,8 +379,7 @@ impl BillingApi {
     /// ```
     pub fn withdraw(&self, params: WithdrawParams) -> Result<WithdrawResponse> {
         let data = params.to_map();
-        let response = self.base.post("/billing/withdraw", Some(&data))?;
-        self.base.validate_response(response)
+        self.base.request("POST", "/billing/withdraw", Some(&data))
     }
 
     /// Gets withdrawal records by request IDs
@@ -327,11 +395,37 @@ impl BillingApi {
     /// let withdrawals = billing_api.withdraw_list(&["withdraw_001", "withdraw_002"])?;
     /// ```
     pub fn withdraw_list(&self, ids: &[&str]) -> Result<Vec<WithdrawRecord>> {
-        let mut data = HashMap::new();
-        data.insert("ids".to_string(), Value::String(ids.join(",")));
+        Ok(self
+            .lookup_withdraw_list(ids, LookupOptions::default())?
+            .found)
+    }
 
-        let response = self.base.post("/billing/withdrawList", Some(&data))?;
-        self.base.validate_response(response)
+    /// Gets withdrawal records for any number of request IDs
+    ///
+    /// The IDs are sent in batches of up to 100 (see [`LookupOptions`]); the
+    /// result also lists the IDs no record was returned for.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = billing_api.lookup_withdraw_list(&ids, LookupOptions::new().with_concurrency(4))?;
+    /// println!("{} found, missing: {:?}", result.found.len(), result.missing);
+    /// ```
+    pub fn lookup_withdraw_list(
+        &self,
+        ids: &[&str],
+        options: LookupOptions,
+    ) -> Result<LookupResult<WithdrawRecord>> {
+        lookup(
+            ids,
+            options,
+            |chunk| {
+                let mut data = HashMap::new();
+                data.insert("ids".to_string(), Value::String(chunk.join(",")));
+                self.base
+                    .request("POST", "/billing/withdrawList", Some(&data))
+            },
+            |record: &WithdrawRecord| record.request_id.as_ref().map(ToString::to_string),
+        )
     }
 
     /// Syncs withdrawal records by max ID (pagination)
@@ -350,8 +444,51 @@ impl BillingApi {
         let mut data = HashMap::new();
         data.insert("max_id".to_string(), Value::Number(max_id.into()));
 
-        let response = self.base.post("/billing/syncWithdrawList", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/billing/syncWithdrawList", Some(&data))
+    }
+
+    /// Iterates over all withdrawal records after `max_id`, fetching pages as needed
+    ///
+    /// # Example
+    /// ```ignore
+    /// for record in billing_api.sync_withdraw_list_iter(0).with_page_size(100) {
+    ///     println!("Record: {:?}", record?);
+    /// }
+    /// ```
+    pub fn sync_withdraw_list_iter(&self, max_id: i64) -> SyncIter<'_, WithdrawRecord> {
+        SyncIter::new(max_id, move |max_id| self.sync_withdraw_list(max_id))
+    }
+
+    /// Iterates over withdrawal records page by page, resuming from a stored checkpoint
+    ///
+    /// The checkpoint is keyed by the endpoint path and only advances when
+    /// a page is acknowledged; see [`CheckpointedSync`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut sync = billing_api.sync_withdraw_list_checkpointed(&store)?;
+    /// while let Some(page) = sync.next_page() {
+    ///     save_to_db(&page?)?;
+    ///     sync.ack()?;
+    /// }
+    /// ```
+    pub fn sync_withdraw_list_checkpointed<'a>(
+        &'a self,
+        store: &'a dyn CheckpointStore,
+    ) -> Result<CheckpointedSync<'a, WithdrawRecord>> {
+        CheckpointedSync::new(store, "/billing/syncWithdrawList", |max_id| {
+            self.sync_withdraw_list_iter(max_id)
+        })
+    }
+
+    /// Async stream over all withdrawal records after `max_id`
+    ///
+    /// See [`SyncStream`] for how pages are fetched.
+    #[cfg(feature = "async")]
+    pub fn sync_withdraw_list_stream(&self, max_id: i64) -> SyncStream<WithdrawRecord> {
+        let api = Self::new(self.base.config.clone(), self.base.crypto_provider.clone());
+        SyncStream::new(max_id, move |max_id| api.sync_withdraw_list(max_id))
     }
 
     /// Gets deposit records by WaaS IDs
@@ -367,11 +504,37 @@ impl BillingApi {
     /// let deposits = billing_api.deposit_list(&["123", "456"])?;
     /// ```
     pub fn deposit_list(&self, ids: &[&str]) -> Result<Vec<DepositRecord>> {
-        let mut data = HashMap::new();
-        data.insert("ids".to_string(), Value::String(ids.join(",")));
+        Ok(self
+            .lookup_deposit_list(ids, LookupOptions::default())?
+            .found)
+    }
 
-        let response = self.base.post("/billing/depositList", Some(&data))?;
-        self.base.validate_response(response)
+    /// Gets deposit records for any number of WaaS deposit IDs
+    ///
+    /// The IDs are sent in batches of up to 100 (see [`LookupOptions`]); the
+    /// result also lists the IDs no record was returned for.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = billing_api.lookup_deposit_list(&ids, LookupOptions::new().with_concurrency(4))?;
+    /// println!("{} found, missing: {:?}", result.found.len(), result.missing);
+    /// ```
+    pub fn lookup_deposit_list(
+        &self,
+        ids: &[&str],
+        options: LookupOptions,
+    ) -> Result<LookupResult<DepositRecord>> {
+        lookup(
+            ids,
+            options,
+            |chunk| {
+                let mut data = HashMap::new();
+                data.insert("ids".to_string(), Value::String(chunk.join(",")));
+                self.base
+                    .request("POST", "/billing/depositList", Some(&data))
+            },
+            |record: &DepositRecord| record.id.as_ref().map(ToString::to_string),
+        )
     }
 
     /// Syncs deposit records by max ID (pagination)
@@ -390,8 +553,51 @@ impl BillingApi {
         let mut data = HashMap::new();
         data.insert("max_id".to_string(), Value::Number(max_id.into()));
 
-        let response = self.base.post("/billing/syncDepositList", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/billing/syncDepositList", Some(&data))
+    }
+
+    /// Iterates over all deposit records after `max_id`, fetching pages as needed
+    ///
+    /// # Example
+    /// ```ignore
+    /// for record in billing_api.sync_deposit_list_iter(0).with_page_size(100) {
+    ///     println!("Record: {:?}", record?);
+    /// }
+    /// ```
+    pub fn sync_deposit_list_iter(&self, max_id: i64) -> SyncIter<'_, DepositRecord> {
+        SyncIter::new(max_id, move |max_id| self.sync_deposit_list(max_id))
+    }
+
+    /// Iterates over deposit records page by page, resuming from a stored checkpoint
+    ///
+    /// The checkpoint is keyed by the endpoint path and only advances when
+    /// a page is acknowledged; see [`CheckpointedSync`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut sync = billing_api.sync_deposit_list_checkpointed(&store)?;
+    /// while let Some(page) = sync.next_page() {
+    ///     save_to_db(&page?)?;
+    ///     sync.ack()?;
+    /// }
+    /// ```
+    pub fn sync_deposit_list_checkpointed<'a>(
+        &'a self,
+        store: &'a dyn CheckpointStore,
+    ) -> Result<CheckpointedSync<'a, DepositRecord>> {
+        CheckpointedSync::new(store, "/billing/syncDepositList", |max_id| {
+            self.sync_deposit_list_iter(max_id)
+        })
+    }
+
+    /// Async stream over all deposit records after `max_id`
+    ///
+    /// See [`SyncStream`] for how pages are fetched.
+    #[cfg(feature = "async")]
+    pub fn sync_deposit_list_stream(&self, max_id: i64) -> SyncStream<DepositRecord> {
+        let api = Self::new(self.base.config.clone(), self.base.crypto_provider.clone());
+        SyncStream::new(max_id, move |max_id| api.sync_deposit_list(max_id))
     }
 
     /// Gets miner fee records by WaaS IDs
@@ -407,11 +613,37 @@ impl BillingApi {
     /// let fees = billing_api.miner_fee_list(&["123", "456"])?;
     /// ```
     pub fn miner_fee_list(&self, ids: &[&str]) -> Result<Vec<MinerFeeRecord>> {
-        let mut data = HashMap::new();
-        data.insert("ids".to_string(), Value::String(ids.join(",")));
+        Ok(self
+            .lookup_miner_fee_list(ids, LookupOptions::default())?
+            .found)
+    }
 
-        let response = self.base.post("/billing/minerFeeList", Some(&data))?;
-        self.base.validate_response(response)
+    /// Gets miner fee records for any number of WaaS transaction IDs
+    ///
+    /// The IDs are sent in batches of up to 100 (see [`LookupOptions`]); the
+    /// result also lists the IDs no record was returned for.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = billing_api.lookup_miner_fee_list(&ids, LookupOptions::new().with_concurrency(4))?;
+    /// println!("{} found, missing: {:?}", result.found.len(), result.missing);
+    /// ```
+    pub fn lookup_miner_fee_list(
+        &self,
+        ids: &[&str],
+        options: LookupOptions,
+    ) -> Result<LookupResult<MinerFeeRecord>> {
+        lookup(
+            ids,
+            options,
+            |chunk| {
+                let mut data = HashMap::new();
+                data.insert("ids".to_string(), Value::String(chunk.join(",")));
+                self.base
+                    .request("POST", "/billing/minerFeeList", Some(&data))
+            },
+            |record: &MinerFeeRecord| record.id.as_ref().map(ToString::to_string),
+        )
     }
 
     /// Syncs miner fee records by max ID (pagination)
@@ -430,7 +662,50 @@ impl BillingApi {
         let mut data = HashMap::new();
         data.insert("max_id".to_string(), Value::Number(max_id.into()));
 
-        let response = self.base.post("/billing/syncMinerFeeList", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/billing/syncMinerFeeList", Some(&data))
+    }
+
+    /// Iterates over all miner fee records after `max_id`, fetching pages as needed
+    ///
+    /// # Example
+    /// ```ignore
+    /// for record in billing_api.sync_miner_fee_list_iter(0).with_page_size(100) {
+    ///     println!("Record: {:?}", record?);
+    /// }
+    /// ```
+    pub fn sync_miner_fee_list_iter(&self, max_id: i64) -> SyncIter<'_, MinerFeeRecord> {
+        SyncIter::new(max_id, move |max_id| self.sync_miner_fee_list(max_id))
+    }
+
+    /// Iterates over miner fee records page by page, resuming from a stored checkpoint
+    ///
+    /// The checkpoint is keyed by the endpoint path and only advances when
+    /// a page is acknowledged; see [`CheckpointedSync`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut sync = billing_api.sync_miner_fee_list_checkpointed(&store)?;
+    /// while let Some(page) = sync.next_page() {
+    ///     save_to_db(&page?)?;
+    ///     sync.ack()?;
+    /// }
+    /// ```
+    pub fn sync_miner_fee_list_checkpointed<'a>(
+        &'a self,
+        store: &'a dyn CheckpointStore,
+    ) -> Result<CheckpointedSync<'a, MinerFeeRecord>> {
+        CheckpointedSync::new(store, "/billing/syncMinerFeeList", |max_id| {
+            self.sync_miner_fee_list_iter(max_id)
+        })
+    }
+
+    /// Async stream over all miner fee records after `max_id`
+    ///
+    /// See [`SyncStream`] for how pages are fetched.
+    #[cfg(feature = "async")]
+    pub fn sync_miner_fee_list_stream(&self, max_id: i64) -> SyncStream<MinerFeeRecord> {
+        let api = Self::new(self.base.config.clone(), self.base.crypto_provider.clone());
+        SyncStream::new(max_id, move |max_id| api.sync_miner_fee_list(max_id))
     }
 }
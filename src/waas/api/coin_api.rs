@@ -11,7 +11,10 @@ use serde_json::Value;
 use rust_decimal::Decimal;
 
 use crate::crypto::CryptoProvider;
+use crate::decode::impl_unknown_fields;
 use crate::error::Result;
+use crate::ids::Symbol;
+use crate::utils::amount::{decimals_from, AmountRules};
 use crate::utils::serde_helpers::{deserialize_optional_bool, deserialize_optional_i32};
 use crate::waas::api::base_api::BaseApi;
 use crate::waas::config::WaasConfig;
@@ -28,7 +31,7 @@ pub struct CoinInfo {
     pub coin_net: Option<String>,
     /// Coin symbol
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Icon URL
     #[serde(default)]
     pub icon: Option<String>,
@@ -83,6 +86,21 @@ pub struct CoinInfo {
     /// Address link
     #[serde(default)]
     pub address_link: Option<String>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl_unknown_fields!(CoinInfo);
+
+impl CoinInfo {
+    /// Precision to check withdrawal and transfer amounts against
+    pub fn amount_rules(&self) -> AmountRules {
+        AmountRules {
+            decimals: decimals_from(self.decimals),
+            min_amount: None,
+        }
+    }
 }
 
 /// Response for get coin list
@@ -91,8 +109,13 @@ pub struct GetCoinListResponse {
     /// List of coins
     #[serde(default)]
     pub list: Vec<CoinInfo>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(GetCoinListResponse, list);
+
 // ============================================================================
 // Coin API Implementation
 // ============================================================================
@@ -125,7 +148,6 @@ impl CoinApi {
     /// ```
     pub fn get_coin_list(&self) -> Result<Vec<CoinInfo>> {
         let data: HashMap<String, Value> = HashMap::new();
-        let response = self.base.post("/user/getCoinList", Some(&data))?;
-        self.base.validate_response(response)
+        self.base.request("POST", "/user/getCoinList", Some(&data))
     }
 }
@@ -10,9 +10,19 @@ use serde_json::Value;
 
 use rust_decimal::Decimal;
 
+use crate::checkpoint::{CheckpointStore, CheckpointedSync};
 use crate::crypto::CryptoProvider;
+use crate::decode::impl_unknown_fields;
 use crate::enums::QueryIdType;
 use crate::error::Result;
+use crate::ids::{RecordId, RequestId, Symbol};
+use crate::lookup::{lookup, LookupOptions, LookupResult};
+use crate::pagination::SyncIter;
+#[cfg(feature = "async")]
+use crate::pagination::SyncStream;
+use crate::timestamp::NumericTime;
+use crate::utils::amount::format_amount;
+use crate::utils::serde_helpers::deserialize_optional_numeric_time;
 use crate::waas::api::base_api::BaseApi;
 use crate::waas::config::WaasConfig;
 
@@ -24,9 +34,9 @@ use crate::waas::config::WaasConfig;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountTransferParams {
     /// Unique request ID (merchant generated)
-    pub request_id: String,
+    pub request_id: RequestId,
     /// Cryptocurrency symbol (e.g., "BTC", "ETH")
-    pub symbol: String,
+    pub symbol: Symbol,
     /// Transfer amount
     pub amount: String,
     /// Source user ID (as string)
@@ -41,8 +51,8 @@ pub struct AccountTransferParams {
 impl AccountTransferParams {
     /// Creates new parameters
     pub fn new(
-        request_id: impl Into<String>,
-        symbol: impl Into<String>,
+        request_id: impl Into<RequestId>,
+        symbol: impl Into<Symbol>,
         amount: impl Into<String>,
         from: impl Into<String>,
         to: impl Into<String>,
@@ -57,6 +67,20 @@ impl AccountTransferParams {
         }
     }
 
+    /// Creates new parameters from a `Decimal` amount
+    ///
+    /// Check the amount first with `CoinInfo::amount_rules` to catch
+    /// precision errors locally.
+    pub fn from_decimal(
+        request_id: impl Into<RequestId>,
+        symbol: impl Into<Symbol>,
+        amount: Decimal,
+        from: impl Into<String>,
+        to: impl Into<String>,
+    ) -> Self {
+        Self::new(request_id, symbol, format_amount(amount), from, to)
+    }
+
     /// Sets the remark
     pub fn with_remark(mut self, remark: impl Into<String>) -> Self {
         self.remark = Some(remark.into());
@@ -67,9 +91,9 @@ impl AccountTransferParams {
         let mut map = HashMap::new();
         map.insert(
             "request_id".to_string(),
-            Value::String(self.request_id.clone()),
+            Value::String(self.request_id.to_string()),
         );
-        map.insert("symbol".to_string(), Value::String(self.symbol.clone()));
+        map.insert("symbol".to_string(), Value::String(self.symbol.to_string()));
         map.insert("amount".to_string(), Value::String(self.amount.clone()));
         map.insert("from".to_string(), Value::String(self.from.clone()));
         map.insert("to".to_string(), Value::String(self.to.clone()));
@@ -132,16 +156,16 @@ impl GetAccountTransferListParams {
 pub struct TransferRecord {
     /// Record ID
     #[serde(default)]
-    pub id: Option<i64>,
+    pub id: Option<RecordId>,
     /// Request ID
     #[serde(default)]
-    pub request_id: Option<String>,
+    pub request_id: Option<RequestId>,
     /// Receipt number
     #[serde(default)]
     pub receipt: Option<String>,
     /// Cryptocurrency symbol
     #[serde(default)]
-    pub symbol: Option<String>,
+    pub symbol: Option<Symbol>,
     /// Transfer amount
     #[serde(default, with = "rust_decimal::serde::str_option")]
     pub amount: Option<Decimal>,
@@ -158,18 +182,28 @@ pub struct TransferRecord {
     #[serde(default)]
     pub remark: Option<String>,
     /// Creation time (milliseconds timestamp)
-    #[serde(default)]
-    pub created_at: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_numeric_time")]
+    pub created_at: Option<NumericTime>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(TransferRecord);
+
 /// Response for sync account transfer list
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncAccountTransferListResponse {
     /// List of transfer records
     #[serde(default)]
     pub list: Vec<TransferRecord>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(SyncAccountTransferListResponse, list);
+
 // ============================================================================
 // Transfer API Implementation
 // ============================================================================
@@ -209,8 +243,7 @@ impl TransferApi {
     /// ```
     pub fn account_transfer(&self, params: AccountTransferParams) -> Result<TransferRecord> {
         let data = params.to_map();
-        let response = self.base.post("/account/transfer", Some(&data))?;
-        self.base.validate_response(response)
+        self.base.request("POST", "/account/transfer", Some(&data))
     }
 
     /// Gets transfer records by request IDs or receipts
@@ -231,9 +264,55 @@ impl TransferApi {
         &self,
         params: GetAccountTransferListParams,
     ) -> Result<Vec<TransferRecord>> {
-        let data = params.to_map();
-        let response = self.base.post("/account/transferList", Some(&data))?;
-        self.base.validate_response(response)
+        Ok(self
+            .lookup_account_transfer_list(params, LookupOptions::default())?
+            .found)
+    }
+
+    /// Gets transfer records for any number of request IDs or receipts
+    ///
+    /// The IDs are sent in batches of up to 100 (see [`LookupOptions`]); the
+    /// result also lists the IDs no record was returned for.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = transfer_api.lookup_account_transfer_list(
+    ///     GetAccountTransferListParams::by_request_id(&ids),
+    ///     LookupOptions::new().with_concurrency(4),
+    /// )?;
+    /// println!("{} found, missing: {:?}", result.found.len(), result.missing);
+    /// ```
+    pub fn lookup_account_transfer_list(
+        &self,
+        params: GetAccountTransferListParams,
+        options: LookupOptions,
+    ) -> Result<LookupResult<TransferRecord>> {
+        let ids: Vec<&str> = params
+            .ids
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .collect();
+        let ids_type = params.ids_type.as_str();
+
+        lookup(
+            &ids,
+            options,
+            |chunk| {
+                let data = GetAccountTransferListParams {
+                    ids: chunk.join(","),
+                    ids_type: params.ids_type.clone(),
+                }
+                .to_map();
+                self.base
+                    .request("POST", "/account/transferList", Some(&data))
+            },
+            |record: &TransferRecord| match ids_type {
+                t if t == QueryIdType::Receipt.as_str() => record.receipt.clone(),
+                t if t == QueryIdType::WaasId.as_str() => record.id.map(|id| id.to_string()),
+                _ => record.request_id.as_ref().map(ToString::to_string),
+            },
+        )
     }
 
     /// Syncs transfer records by max ID (pagination)
@@ -252,7 +331,52 @@ impl TransferApi {
         let mut data = HashMap::new();
         data.insert("max_id".to_string(), Value::Number(max_id.into()));
 
-        let response = self.base.post("/account/syncTransferList", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/account/syncTransferList", Some(&data))
+    }
+
+    /// Iterates over all transfer records after `max_id`, fetching pages as needed
+    ///
+    /// # Example
+    /// ```ignore
+    /// for record in transfer_api.sync_account_transfer_list_iter(0).with_page_size(100) {
+    ///     println!("Record: {:?}", record?);
+    /// }
+    /// ```
+    pub fn sync_account_transfer_list_iter(&self, max_id: i64) -> SyncIter<'_, TransferRecord> {
+        SyncIter::new(max_id, move |max_id| {
+            self.sync_account_transfer_list(max_id)
+        })
+    }
+
+    /// Iterates over transfer records page by page, resuming from a stored checkpoint
+    ///
+    /// The checkpoint is keyed by the endpoint path and only advances when
+    /// a page is acknowledged; see [`CheckpointedSync`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut sync = transfer_api.sync_account_transfer_list_checkpointed(&store)?;
+    /// while let Some(page) = sync.next_page() {
+    ///     save_to_db(&page?)?;
+    ///     sync.ack()?;
+    /// }
+    /// ```
+    pub fn sync_account_transfer_list_checkpointed<'a>(
+        &'a self,
+        store: &'a dyn CheckpointStore,
+    ) -> Result<CheckpointedSync<'a, TransferRecord>> {
+        CheckpointedSync::new(store, "/account/syncTransferList", |max_id| {
+            self.sync_account_transfer_list_iter(max_id)
+        })
+    }
+
+    /// Async stream over all transfer records after `max_id`
+    ///
+    /// See [`SyncStream`] for how pages are fetched.
+    #[cfg(feature = "async")]
+    pub fn sync_account_transfer_list_stream(&self, max_id: i64) -> SyncStream<TransferRecord> {
+        let api = Self::new(self.base.config.clone(), self.base.crypto_provider.clone());
+        SyncStream::new(max_id, move |max_id| api.sync_account_transfer_list(max_id))
     }
 }
@@ -8,8 +8,14 @@ use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::checkpoint::{CheckpointStore, CheckpointedSync};
 use crate::crypto::CryptoProvider;
+use crate::decode::impl_unknown_fields;
 use crate::error::Result;
+use crate::ids::Uid;
+use crate::pagination::SyncIter;
+#[cfg(feature = "async")]
+use crate::pagination::SyncStream;
 use crate::waas::api::base_api::BaseApi;
 use crate::waas::config::WaasConfig;
 
@@ -121,7 +127,7 @@ impl GetEmailUserParams {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserInfo {
     /// User ID
-    pub uid: i64,
+    pub uid: Uid,
     /// Authentication level
     #[serde(default)]
     pub auth_level: Option<i32>,
@@ -143,8 +149,13 @@ pub struct UserInfo {
     /// Email (for email users, in some APIs)
     #[serde(default)]
     pub email: Option<String>,
+    /// Raw data for additional fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
+impl_unknown_fields!(UserInfo);
+
 // ============================================================================
 // User API Implementation
 // ============================================================================
@@ -178,8 +189,7 @@ impl UserApi {
     /// ```
     pub fn register_mobile_user(&self, params: RegisterMobileUserParams) -> Result<UserInfo> {
         let data = params.to_map();
-        let response = self.base.post("/user/createUser", Some(&data))?;
-        self.base.validate_response(response)
+        self.base.request("POST", "/user/createUser", Some(&data))
     }
 
     /// Registers a new user using email
@@ -196,8 +206,8 @@ impl UserApi {
     /// ```
     pub fn register_email_user(&self, params: RegisterEmailUserParams) -> Result<UserInfo> {
         let data = params.to_map();
-        let response = self.base.post("/user/registerEmail", Some(&data))?;
-        self.base.validate_response(response)
+        self.base
+            .request("POST", "/user/registerEmail", Some(&data))
     }
 
     /// Gets user information by mobile phone
@@ -214,8 +224,7 @@ impl UserApi {
     /// ```
     pub fn get_mobile_user(&self, params: GetMobileUserParams) -> Result<UserInfo> {
         let data = params.to_map();
-        let response = self.base.post("/user/info", Some(&data))?;
-        self.base.validate_response(response)
+        self.base.request("POST", "/user/info", Some(&data))
     }
 
     /// Gets user information by email
@@ -232,8 +241,7 @@ impl UserApi {
     /// ```
     pub fn get_email_user(&self, params: GetEmailUserParams) -> Result<UserInfo> {
         let data = params.to_map();
-        let response = self.base.post("/user/info", Some(&data))?;
-        self.base.validate_response(response)
+        self.base.request("POST", "/user/info", Some(&data))
     }
 
     /// Syncs user list by max ID (pagination)
@@ -252,7 +260,49 @@ impl UserApi {
         let mut data = HashMap::new();
         data.insert("max_id".to_string(), Value::Number(max_id.into()));
 
-        let response = self.base.post("/user/syncList", Some(&data))?;
-        self.base.validate_response(response)
+        self.base.request("POST", "/user/syncList", Some(&data))
+    }
+
+    /// Iterates over all users after `max_id`, fetching pages as needed
+    ///
+    /// # Example
+    /// ```ignore
+    /// for record in user_api.sync_user_list_iter(0).with_page_size(100) {
+    ///     println!("Record: {:?}", record?);
+    /// }
+    /// ```
+    pub fn sync_user_list_iter(&self, max_id: i64) -> SyncIter<'_, UserInfo> {
+        SyncIter::new(max_id, move |max_id| self.sync_user_list(max_id))
+    }
+
+    /// Iterates over users page by page, resuming from a stored checkpoint
+    ///
+    /// The checkpoint is keyed by the endpoint path and only advances when
+    /// a page is acknowledged; see [`CheckpointedSync`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut sync = user_api.sync_user_list_checkpointed(&store)?;
+    /// while let Some(page) = sync.next_page() {
+    ///     save_to_db(&page?)?;
+    ///     sync.ack()?;
+    /// }
+    /// ```
+    pub fn sync_user_list_checkpointed<'a>(
+        &'a self,
+        store: &'a dyn CheckpointStore,
+    ) -> Result<CheckpointedSync<'a, UserInfo>> {
+        CheckpointedSync::new(store, "/user/syncList", |max_id| {
+            self.sync_user_list_iter(max_id)
+        })
+    }
+
+    /// Async stream over all users after `max_id`
+    ///
+    /// See [`SyncStream`] for how pages are fetched.
+    #[cfg(feature = "async")]
+    pub fn sync_user_list_stream(&self, max_id: i64) -> SyncStream<UserInfo> {
+        let api = Self::new(self.base.config.clone(), self.base.crypto_provider.clone());
+        SyncStream::new(max_id, move |max_id| api.sync_user_list(max_id))
     }
 }
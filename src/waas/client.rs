@@ -4,9 +4,15 @@
 
 use std::sync::Arc;
 
+use serde_json::Value;
+
 use crate::crypto::{CryptoProvider, RsaCryptoProvider};
+use crate::decode::DecodeMode;
+use crate::endpoint::Endpoint;
 use crate::error::Result;
-use crate::waas::api::{AccountApi, AsyncNotifyApi, BillingApi, CoinApi, TransferApi, UserApi};
+use crate::waas::api::{
+    AccountApi, AsyncNotifyApi, BaseApi, BillingApi, CoinApi, TransferApi, UserApi,
+};
 use crate::waas::config::WaasConfig;
 
 /// WaaS Client - Main entry point for WaaS API operations
@@ -134,6 +140,65 @@ impl WaasClient {
         self.crypto_provider.clone()
     }
 
+    /// Shorthand for [`Self::get_user_api`]
+    pub fn user(&self) -> UserApi {
+        self.get_user_api()
+    }
+
+    /// Shorthand for [`Self::get_account_api`]
+    pub fn account(&self) -> AccountApi {
+        self.get_account_api()
+    }
+
+    /// Shorthand for [`Self::get_billing_api`]
+    pub fn billing(&self) -> BillingApi {
+        self.get_billing_api()
+    }
+
+    /// Shorthand for [`Self::get_coin_api`]
+    pub fn coin(&self) -> CoinApi {
+        self.get_coin_api()
+    }
+
+    /// Shorthand for [`Self::get_transfer_api`]
+    pub fn transfer(&self) -> TransferApi {
+        self.get_transfer_api()
+    }
+
+    /// Shorthand for [`Self::get_async_notify_api`]
+    pub fn async_notify(&self) -> AsyncNotifyApi {
+        self.get_async_notify_api()
+    }
+
+    /// Calls an [`Endpoint`] that has no dedicated API method yet
+    ///
+    /// Uses the same encryption and response validation as the wrapped APIs.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = client.call::<MyEndpoint>(&params)?;
+    /// ```
+    pub fn call<E: Endpoint>(&self, params: &E::Params) -> Result<E::Response> {
+        self.base_api().call::<E>(params)
+    }
+
+    /// Calls an API path with raw JSON parameters
+    ///
+    /// # Arguments
+    /// * `path` - API path
+    /// * `method` - `"POST"` or `"GET"`
+    /// * `params` - JSON object of request parameters (`Value::Null` for none)
+    ///
+    /// # Returns
+    /// The validated response `data` field
+    pub fn raw_request(&self, path: &str, method: &str, params: Value) -> Result<Value> {
+        self.base_api().raw_request(path, method, params)
+    }
+
+    fn base_api(&self) -> BaseApi {
+        BaseApi::new(self.config.clone(), self.crypto_provider.clone())
+    }
+
     /// Gets the configuration
     pub fn config(&self) -> &WaasConfig {
         &self.config
@@ -152,6 +217,7 @@ pub struct WaasClientBuilder {
     version: Option<String>,
     crypto_provider: Option<Arc<dyn CryptoProvider>>,
     debug: bool,
+    decode_mode: DecodeMode,
 }
 
 impl WaasClientBuilder {
@@ -202,6 +268,12 @@ impl WaasClientBuilder {
         self
     }
 
+    /// Sets how unknown response fields are reported (default: lenient)
+    pub fn set_decode_mode(mut self, decode_mode: DecodeMode) -> Self {
+        self.decode_mode = decode_mode;
+        self
+    }
+
     /// Builds the WaasClient instance
     ///
     /// # Returns
@@ -221,6 +293,7 @@ impl WaasClientBuilder {
             crypto_provider: self.crypto_provider,
             charset: "UTF-8".to_string(),
             debug: self.debug,
+            decode_mode: self.decode_mode,
         };
 
         // Normalize host URL
@@ -3,6 +3,7 @@
 //! Stores configuration parameters for WaaS API client.
 
 use crate::crypto::CryptoProvider;
+use crate::decode::DecodeMode;
 use crate::error::{ConfigError, Result};
 use std::sync::Arc;
 
@@ -36,6 +37,8 @@ pub struct WaasConfig {
     pub charset: String,
     /// Enable debug mode
     pub debug: bool,
+    /// How unknown response fields are reported
+    pub decode_mode: DecodeMode,
 }
 
 impl WaasConfig {
@@ -55,6 +58,7 @@ impl WaasConfig {
             version: "v2".to_string(),
             charset: "UTF-8".to_string(),
             debug: false,
+            decode_mode: DecodeMode::default(),
         }
     }
 
@@ -69,6 +73,7 @@ impl WaasConfig {
             version: "v2".to_string(),
             charset: "UTF-8".to_string(),
             debug: false,
+            decode_mode: DecodeMode::default(),
         }
     }
 
@@ -136,6 +141,7 @@ impl Default for WaasConfig {
             version: "v2".to_string(),
             charset: "UTF-8".to_string(),
             debug: false,
+            decode_mode: DecodeMode::default(),
         }
     }
 }
@@ -0,0 +1,478 @@
+//! Embedded webhook server for ChainUp notifications (`webhook` feature)
+//!
+//! ChainUp posts deposit and withdrawal notifications to a callback URL as an
+//! encrypted `data` field. [`WebhookServer`] receives them, decrypts them
+//! with the client's keys, passes the typed notification to the registered
+//! handler and answers with the acknowledgement ChainUp expects
+//! ([`DEFAULT_ACK`]). Any other answer makes ChainUp deliver the notification
+//! again later, so a handler returning an error gets its notification back on
+//! the next delivery.
+//!
+//! | Outcome                               | Response                |
+//! | ------------------------------------- | ----------------------- |
+//! | Handler succeeded                     | `200` with the ack body |
+//! | Already handled (with dedup)          | `200` with the ack body |
+//! | Still being handled (with dedup)      | `503`                   |
+//! | No `data` field                       | `400`                   |
+//! | `data` cannot be decrypted or checked | `400`                   |
+//! | Decrypted data cannot be parsed       | `200` with the ack body |
+//! | Handler failed                        | `500`                   |
+//!
+//! Decrypted data that cannot be parsed would fail the same way on every
+//! redelivery, so it is logged with its content and acknowledged instead.
+//!
+//! The `data` field is read from a form (`application/x-www-form-urlencoded`)
+//! or JSON body.
+//!
+//! With [`with_dedup`](WebhookServer::with_dedup), notification states that
+//! were already handled are acknowledged without calling the handler again
+//! (see [`crate::dedup`]). A redelivery arriving while the same state is
+//! still being handled is answered with `503`, so ChainUp tries again once
+//! the outcome is known.
+//!
+//! # Example
+//! ```ignore
+//! let server = WebhookServer::new()
+//!     .on_mpc(mpc_client.notify(), |notification: MpcNotification| async move {
+//!         accounting.apply(&notification).await
+//!     })
+//!     .on_waas(waas_client.async_notify(), |notification: NotifyData| async move {
+//!         accounting.apply_waas(&notification).await
+//!     });
+//!
+//! // Standalone ...
+//! server.serve("0.0.0.0:8080").await?;
+//! // ... or mounted into an existing axum app
+//! let app = my_routes.merge(server.router());
+//! ```
+
+use std::collections::HashSet;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use axum::body::Bytes;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use serde_json::Value;
+
+use crate::dedup::{DedupStore, NotificationKey};
+use crate::error::Result;
+use crate::mpc::api::NotifyApi;
+use crate::mpc_types::{MpcNotification, MpcNotifyData};
+use crate::waas::api::AsyncNotifyApi;
+use crate::waas_types::NotifyData;
+
+/// Path MPC notifications are received on unless changed
+pub const DEFAULT_MPC_PATH: &str = "/chainup/mpc/notify";
+
+/// Path WaaS notifications are received on unless changed
+pub const DEFAULT_WAAS_PATH: &str = "/chainup/waas/notify";
+
+/// Response body acknowledging a notification
+pub const DEFAULT_ACK: &str = "SUCCESS";
+
+type HandlerFuture = Pin<Box<dyn Future<Output = std::result::Result<(), String>> + Send>>;
+type Decrypt = Box<dyn Fn(&str) -> Result<String> + Send + Sync>;
+type Convert<T> = Box<dyn Fn(&str) -> Result<(T, NotificationKey)> + Send + Sync>;
+
+/// Decrypts one kind of notification and hands it to its handler
+///
+/// `decrypt` returns the checked JSON text, which `convert` turns into the
+/// notification and its dedup key.
+struct Route<T> {
+    decrypt: Decrypt,
+    convert: Convert<T>,
+    handler: Box<dyn Fn(T) -> HandlerFuture + Send + Sync>,
+}
+
+impl<T> Route<T> {
+    fn new<F, Fut, E>(
+        decrypt: impl Fn(&str) -> Result<String> + Send + Sync + 'static,
+        convert: impl Fn(&str) -> Result<(T, NotificationKey)> + Send + Sync + 'static,
+        handler: F,
+    ) -> Self
+    where
+        F: Fn(T) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = std::result::Result<(), E>> + Send + 'static,
+        E: fmt::Display,
+    {
+        Self {
+            decrypt: Box::new(decrypt),
+            convert: Box::new(convert),
+            handler: Box::new(move |notification| {
+                let result = handler(notification);
+                Box::pin(async move { result.await.map_err(|e| e.to_string()) })
+            }),
+        }
+    }
+}
+
+/// HTTP endpoint receiving ChainUp notifications
+///
+/// Register a handler per client type with [`on_mpc`](Self::on_mpc) and
+/// [`on_waas`](Self::on_waas), then run it with [`serve`](Self::serve) or
+/// take its [`router`](Self::router).
+pub struct WebhookServer {
+    mpc: Option<Route<MpcNotification>>,
+    waas: Option<Route<NotifyData>>,
+    mpc_path: String,
+    waas_path: String,
+    ack: String,
+    dedup: Option<Arc<dyn DedupStore>>,
+}
+
+impl Default for WebhookServer {
+    fn default() -> Self {
+        Self {
+            mpc: None,
+            waas: None,
+            mpc_path: DEFAULT_MPC_PATH.to_string(),
+            waas_path: DEFAULT_WAAS_PATH.to_string(),
+            ack: DEFAULT_ACK.to_string(),
+            dedup: None,
+        }
+    }
+}
+
+impl WebhookServer {
+    /// Creates a server without handlers on the default paths
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handles MPC notifications, decrypted with `notify_api`
+    ///
+    /// An error returned by `handler` is logged and answered with `500`, so
+    /// ChainUp delivers the notification again.
+    pub fn on_mpc<F, Fut, E>(mut self, notify_api: NotifyApi, handler: F) -> Self
+    where
+        F: Fn(MpcNotification) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = std::result::Result<(), E>> + Send + 'static,
+        E: fmt::Display,
+    {
+        self.mpc = Some(Route::new(
+            move |cipher| notify_api.decrypt_text(cipher),
+            |decrypted| {
+                let data: MpcNotifyData = serde_json::from_str(decrypted)?;
+                let key = NotificationKey::from(&data);
+                Ok((MpcNotification::from(data), key))
+            },
+            handler,
+        ));
+        self
+    }
+
+    /// Handles WaaS deposit and withdrawal notifications, decrypted with
+    /// `notify_api`
+    ///
+    /// An error returned by `handler` is logged and answered with `500`, so
+    /// ChainUp delivers the notification again.
+    pub fn on_waas<F, Fut, E>(mut self, notify_api: AsyncNotifyApi, handler: F) -> Self
+    where
+        F: Fn(NotifyData) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = std::result::Result<(), E>> + Send + 'static,
+        E: fmt::Display,
+    {
+        self.waas = Some(Route::new(
+            move |cipher| notify_api.decrypt_text(cipher),
+            |decrypted| {
+                let data: NotifyData = serde_json::from_str(decrypted)?;
+                let key = NotificationKey::from(&data);
+                Ok((data, key))
+            },
+            handler,
+        ));
+        self
+    }
+
+    /// Sets the path MPC notifications are received on
+    pub fn with_mpc_path(mut self, path: impl Into<String>) -> Self {
+        self.mpc_path = path.into();
+        self
+    }
+
+    /// Sets the path WaaS notifications are received on
+    pub fn with_waas_path(mut self, path: impl Into<String>) -> Self {
+        self.waas_path = path.into();
+        self
+    }
+
+    /// Sets the response body acknowledging a notification
+    pub fn with_ack(mut self, ack: impl Into<String>) -> Self {
+        self.ack = ack.into();
+        self
+    }
+
+    /// Skips notification states already recorded in `store`
+    ///
+    /// Duplicates are acknowledged without calling the handler. A state is
+    /// only recorded once its handler succeeded; redeliveries arriving while
+    /// it runs are answered with `503`, so a failed attempt is still
+    /// delivered again.
+    pub fn with_dedup(mut self, store: impl DedupStore + 'static) -> Self {
+        self.dedup = Some(Arc::new(store));
+        self
+    }
+
+    /// Builds an axum router with a `POST` route per registered handler
+    pub fn router(self) -> Router {
+        let shared = Arc::new(Shared {
+            ack: self.ack,
+            dedup: self.dedup,
+            in_flight: Mutex::default(),
+        });
+        let mut router = Router::new();
+        if let Some(route) = self.mpc {
+            router = router.route(&self.mpc_path, endpoint(route, shared.clone()));
+        }
+        if let Some(route) = self.waas {
+            router = router.route(&self.waas_path, endpoint(route, shared));
+        }
+        router
+    }
+
+    /// Listens on `addr` and serves notifications until the task is dropped
+    pub async fn serve(self, addr: impl tokio::net::ToSocketAddrs) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        log::info!("[Webhook] Listening on {}", listener.local_addr()?);
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+}
+
+/// Settings and state shared by every route
+struct Shared {
+    ack: String,
+    dedup: Option<Arc<dyn DedupStore>>,
+    /// Keys of the notifications whose handler is running
+    in_flight: Mutex<HashSet<String>>,
+}
+
+/// A notification key being handled, released when dropped
+struct InFlight<'a> {
+    keys: &'a Mutex<HashSet<String>>,
+    key: &'a str,
+}
+
+impl<'a> InFlight<'a> {
+    /// Marks `key` as being handled, unless it already is
+    fn claim(keys: &'a Mutex<HashSet<String>>, key: &'a str) -> Option<Self> {
+        let claimed = keys
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key.to_string());
+        // Only build the guard once the lock is released: dropping it takes
+        // the lock again
+        if claimed {
+            Some(Self { keys, key })
+        } else {
+            None
+        }
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.keys
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(self.key);
+    }
+}
+
+fn endpoint<T: Send + 'static>(
+    route: Route<T>,
+    shared: Arc<Shared>,
+) -> axum::routing::MethodRouter {
+    let route = Arc::new(route);
+    post(move |headers: HeaderMap, body: Bytes| {
+        let route = route.clone();
+        let shared = shared.clone();
+        async move { receive(&route, &shared, &headers, &body).await }
+    })
+}
+
+/// Runs one notification through `route` and returns the response
+async fn receive<T>(
+    route: &Route<T>,
+    shared: &Shared,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> (StatusCode, String) {
+    let Some(cipher) = extract_data(headers, body) else {
+        log::warn!("[Webhook] Notification without a data field");
+        return (StatusCode::BAD_REQUEST, "missing data".to_string());
+    };
+
+    let decrypted = match (route.decrypt)(&cipher) {
+        Ok(decrypted) => decrypted,
+        Err(e) => {
+            log::warn!("[Webhook] Could not decrypt notification: {}", e);
+            return (StatusCode::BAD_REQUEST, "invalid data".to_string());
+        }
+    };
+    let (notification, key) = match (route.convert)(&decrypted) {
+        Ok(converted) => converted,
+        Err(e) => {
+            log::error!(
+                "[Webhook] Acknowledging notification that cannot be parsed: {}: {}",
+                e,
+                decrypted
+            );
+            return (StatusCode::OK, shared.ack.clone());
+        }
+    };
+
+    let dedup = shared.dedup.as_deref();
+    // Held until the handler finished, so a concurrent redelivery cannot be
+    // acknowledged before the outcome is known
+    let _in_flight = match dedup {
+        Some(store) => {
+            let Some(in_flight) = InFlight::claim(&shared.in_flight, key.as_str()) else {
+                log::debug!("[Webhook] Notification {} is still being handled", key);
+                return (StatusCode::SERVICE_UNAVAILABLE, "in flight".to_string());
+            };
+            match store.contains(key.as_str()) {
+                Ok(false) => {}
+                Ok(true) => {
+                    log::debug!("[Webhook] Duplicate notification {}", key);
+                    return (StatusCode::OK, shared.ack.clone());
+                }
+                Err(e) => {
+                    log::error!("[Webhook] Dedup store failed: {}", e);
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "dedup failed".to_string(),
+                    );
+                }
+            }
+            Some(in_flight)
+        }
+        None => None,
+    };
+
+    match (route.handler)(notification).await {
+        Ok(()) => {
+            // The notification was handled; acknowledge it even if recording
+            // that fails, at the risk of handling a redelivery again
+            if let Some(Err(e)) = dedup.map(|store| store.insert(key.as_str())) {
+                log::error!("[Webhook] Could not record notification {}: {}", key, e);
+            }
+            (StatusCode::OK, shared.ack.clone())
+        }
+        Err(e) => {
+            log::error!("[Webhook] Notification handler failed: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "handler failed".to_string(),
+            )
+        }
+    }
+}
+
+/// Reads the encrypted `data` field from a JSON or form body
+fn extract_data(headers: &HeaderMap, body: &[u8]) -> Option<String> {
+    let is_json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+
+    let data = if is_json {
+        match serde_json::from_slice::<Value>(body).ok()?.get("data")? {
+            Value::String(data) => data.clone(),
+            _ => return None,
+        }
+    } else {
+        serde_urlencoded::from_bytes::<Vec<(String, String)>>(body)
+            .ok()?
+            .into_iter()
+            .find_map(|(key, value)| (key == "data").then_some(value))?
+    };
+    Some(data).filter(|data| !data.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dedup::MemoryDedupStore;
+    use crate::error::ValidationError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_receive_acknowledges_or_asks_for_redelivery() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handled = calls.clone();
+        let route = Route::new(
+            |cipher| {
+                if cipher == "garbage" {
+                    return Err(ValidationError::new("bad cipher").into());
+                }
+                Ok(cipher.to_string())
+            },
+            |decrypted| {
+                if decrypted == "unparseable" {
+                    return Err(ValidationError::new("bad json").into());
+                }
+                let key = NotificationKey::new(Some("deposit"), Some(decrypted), None::<i32>, None);
+                Ok((decrypted.to_string(), key))
+            },
+            move |notification: String| {
+                handled.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if notification == "fail" {
+                        Err("database down")
+                    } else {
+                        Ok(())
+                    }
+                }
+            },
+        );
+        let shared = Shared {
+            ack: DEFAULT_ACK.to_string(),
+            dedup: Some(Arc::new(MemoryDedupStore::default())),
+            in_flight: Mutex::default(),
+        };
+        let form = HeaderMap::new();
+        let mut json = HeaderMap::new();
+        json.insert(header::CONTENT_TYPE, "application/json".parse().unwrap());
+
+        let status = |headers: &HeaderMap, body: &str| {
+            tokio_test::block_on(receive(&route, &shared, headers, body.as_bytes()))
+        };
+        assert_eq!(
+            status(&form, "data=abc%2Bdef&sign=x"),
+            (StatusCode::OK, DEFAULT_ACK.to_string())
+        );
+        assert_eq!(status(&json, r#"{"data":"abc"}"#).0, StatusCode::OK);
+        assert_eq!(status(&form, "sign=x").0, StatusCode::BAD_REQUEST);
+        assert_eq!(status(&form, "data=garbage").0, StatusCode::BAD_REQUEST);
+        // Redelivering would not help, so it is acknowledged unhandled
+        assert_eq!(status(&form, "data=unparseable").0, StatusCode::OK);
+        assert_eq!(
+            status(&form, "data=fail").0,
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // Replays are acknowledged without the handler; failures are retried
+        assert_eq!(status(&form, "data=abc").0, StatusCode::OK);
+        assert_eq!(
+            status(&form, "data=fail").0,
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        // A redelivery while the first attempt runs is not acknowledged
+        let running = InFlight::claim(&shared.in_flight, "deposit:slow:-:-").unwrap();
+        assert_eq!(
+            status(&form, "data=slow").0,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        drop(running);
+        assert_eq!(status(&form, "data=slow").0, StatusCode::OK);
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+    }
+}